@@ -1,4 +1,7 @@
-use crate::{animation::palette::Palette, util::framebuf::FrameBuf};
+use crate::{
+    animation::palette::Palette,
+    util::{cell::Cell, framebuf::FrameBuf},
+};
 
 // Fall style: the rendered text detaches letter by letter (bottom rows first),
 // falls under gravity with a little horizontal drift, bounces, and piles up at
@@ -17,7 +20,7 @@ const PILE_RGB: (u8, u8, u8) = (200, 200, 200);
 
 #[derive(Clone, Copy)]
 struct FallingLetter {
-    ch: char,
+    glyph: usize, // index into `FallSim::glyphs`
     orig_row: usize,
     orig_col: usize,
     xf: f32,
@@ -29,11 +32,11 @@ struct FallingLetter {
 
 #[derive(Clone, Copy)]
 struct SettledUnit {
-    ch: char,
+    glyph: usize,
     tilt: f32,
 }
 
-type OverlayCell = (char, (u8, u8, u8));
+type OverlayCell = (usize, (u8, u8, u8));
 
 #[derive(PartialEq)]
 enum Phase {
@@ -44,13 +47,16 @@ enum Phase {
 
 pub struct FallSim {
     letters: Vec<FallingLetter>,
+    // Source cells of the letters; wide glyphs cover two columns wherever
+    // they land.
+    glyphs: Vec<Cell>,
     pile: Vec<Vec<SettledUnit>>, // per column, bottom-up
     phase: Phase,
     phase_start: f32,
     w: usize,
     h: usize,
     // Reusable per-frame grids (row-major, w*h)
-    settled: Vec<Option<char>>,
+    settled: Vec<Option<usize>>,
     overlay: Vec<Option<OverlayCell>>,
 }
 
@@ -58,6 +64,7 @@ impl FallSim {
     pub fn new() -> Self {
         FallSim {
             letters: Vec::new(),
+            glyphs: Vec::new(),
             pile: Vec::new(),
             phase: Phase::Static,
             phase_start: 0.0,
@@ -80,14 +87,14 @@ impl FallSim {
         self.overlay = vec![None; w * h];
     }
 
-    /// Advance the simulation. `plain` is the static character grid of the
-    /// source text; `elapsed` / `dt` are in animation seconds.
-    pub fn step(&mut self, plain: &[Vec<char>], elapsed: f32, dt: f32) {
+    /// Advance the simulation. `cells` is the static cell grid of the source
+    /// text; `elapsed` / `dt` are in animation seconds.
+    pub fn step(&mut self, cells: &[Vec<Cell>], elapsed: f32, dt: f32) {
         if self.w == 0 || self.h == 0 {
             return;
         }
         if self.phase == Phase::Static && self.letters.is_empty() {
-            self.spawn_letters(plain, elapsed);
+            self.spawn_letters(cells, elapsed);
         }
         match self.phase {
             Phase::Static => {
@@ -117,19 +124,24 @@ impl FallSim {
         }
     }
 
-    fn spawn_letters(&mut self, plain: &[Vec<char>], elapsed: f32) {
-        let total_rows = plain.len().min(self.h);
-        for (row_i, row) in plain.iter().enumerate().take(self.h) {
-            for (col_i, &ch) in row.iter().enumerate().take(self.w) {
-                if ch == ' ' {
+    fn spawn_letters(&mut self, cells: &[Vec<Cell>], elapsed: f32) {
+        self.glyphs.clear();
+        let total_rows = cells.len().min(self.h);
+        for (row_i, row) in cells.iter().enumerate().take(self.h) {
+            for (col_i, cell) in row.iter().enumerate().take(self.w) {
+                if cell.is_blank() || cell.is_continuation() || col_i + cell.width > self.w {
                     continue; // blanks don't fall (and must not occupy pile space)
                 }
                 // Bottom rows release first so the text visibly crumbles upward.
                 let inv = (total_rows.saturating_sub(1)).saturating_sub(row_i) as f32;
                 let release = 0.5 + inv * 0.035 + fastrand::f32() * 0.4;
                 let vx = (fastrand::f32() - 0.5) * 3.0;
+                self.glyphs.push(Cell {
+                    ansi: String::new(),
+                    ..cell.clone()
+                });
                 self.letters.push(FallingLetter {
-                    ch,
+                    glyph: self.glyphs.len() - 1,
                     orig_row: row_i,
                     orig_col: col_i,
                     xf: col_i as f32,
@@ -173,7 +185,10 @@ impl FallSim {
                 } else {
                     fl.y = ground_y;
                     let tilt = settle_tilt(&self.pile, col, col_height);
-                    self.pile[col].push(SettledUnit { ch: fl.ch, tilt });
+                    self.pile[col].push(SettledUnit {
+                        glyph: fl.glyph,
+                        tilt,
+                    });
                     fl.release = f32::INFINITY; // mark for removal
                 }
             }
@@ -220,7 +235,7 @@ impl FallSim {
                 {
                     target = free_col as isize;
                 }
-                self.settled[row * w + target as usize] = Some(su.ch);
+                self.settled[row * w + target as usize] = Some(su.glyph);
             }
         }
 
@@ -239,7 +254,7 @@ impl FallSim {
                     let rgb = fall_palette_rgb(base_rgb, palette, t, sat);
                     let idx = fl.orig_row * w + fl.orig_col;
                     if self.overlay[idx].is_none() {
-                        self.overlay[idx] = Some((fl.ch, rgb));
+                        self.overlay[idx] = Some((fl.glyph, rgb));
                     }
                 }
             } else {
@@ -249,7 +264,7 @@ impl FallSim {
                     // Airborne letters draw over everything else.
                     let t = fall_palette_t(ry as usize, cx, w, h, elapsed);
                     let rgb = fall_palette_rgb(ACTIVE_RGB, palette, t, 0.55);
-                    self.overlay[ry as usize * w + cx] = Some((fl.ch, rgb));
+                    self.overlay[ry as usize * w + cx] = Some((fl.glyph, rgb));
                 }
             }
         }
@@ -257,14 +272,27 @@ impl FallSim {
         for row in 0..h {
             fb.goto_line(row + 1);
             let base = row * w;
-            for col in 0..w {
-                match (self.overlay[base + col], self.settled[base + col]) {
-                    (Some((ch, rgb)), _) => fb.put(ch, rgb),
-                    (None, Some(ch)) => {
+            let mut col = 0;
+            while col < w {
+                let (glyph, rgb) = match (self.overlay[base + col], self.settled[base + col]) {
+                    (Some((glyph, rgb)), _) => (Some(glyph), rgb),
+                    (None, Some(glyph)) => {
                         let t = fall_palette_t(row, col, w, h, elapsed);
-                        fb.put(ch, fall_palette_rgb(PILE_RGB, palette, t, 0.45));
+                        (Some(glyph), fall_palette_rgb(PILE_RGB, palette, t, 0.45))
+                    }
+                    (None, None) => (None, PILE_RGB),
+                };
+                // A wide glyph hides whatever sits in the column to its
+                // right; at the right edge it has no room and shows blank.
+                match glyph.map(|glyph| &self.glyphs[glyph]) {
+                    Some(cell) if col + cell.width <= w => {
+                        fb.put_cell(cell, rgb);
+                        col += cell.width;
+                    }
+                    _ => {
+                        fb.put(' ', rgb);
+                        col += 1;
                     }
-                    (None, None) => fb.put(' ', PILE_RGB),
                 }
             }
             fb.end_line();
@@ -332,7 +360,7 @@ fn settle_tilt(pile: &[Vec<SettledUnit>], col: usize, col_height: usize) -> f32
 }
 
 fn find_free_cell_in_row(
-    settled: &[Option<char>],
+    settled: &[Option<usize>],
     row: usize,
     width: usize,
     home_col: usize,
@@ -359,13 +387,18 @@ fn find_free_cell_in_row(
 mod tests {
     use super::*;
     use crate::animation::palette::default_palette;
+    use crate::util::cell::layout_line;
+
+    fn grid(lines: &[&str]) -> Vec<Vec<Cell>> {
+        lines.iter().map(|line| layout_line(line)).collect()
+    }
 
     #[test]
     fn spaces_do_not_become_letters() {
         let mut sim = FallSim::new();
         sim.resize(10, 5, 0.0);
-        let plain = vec![vec!['a', ' ', 'b'], vec![' ', ' ', ' ']];
-        sim.step(&plain, 0.0, 0.016);
+        let cells = grid(&["a b", "   "]);
+        sim.step(&cells, 0.0, 0.016);
         assert_eq!(sim.letters.len(), 2);
     }
 
@@ -374,12 +407,12 @@ mod tests {
         fastrand::seed(7);
         let mut sim = FallSim::new();
         sim.resize(20, 10, 0.0);
-        let plain = vec![vec!['x'; 10]; 3];
+        let cells = grid(&["xxxxxxxxxx"; 3]);
         let dt = 1.0 / 30.0;
         let mut t = 0.0;
         for _ in 0..3000 {
             t += dt;
-            sim.step(&plain, t, dt);
+            sim.step(&cells, t, dt);
             if sim.phase == Phase::Settled {
                 break;
             }
@@ -389,6 +422,18 @@ mod tests {
         assert_eq!(piled, 30);
     }
 
+    #[test]
+    fn wide_glyphs_fall_as_one_letter() {
+        let mut sim = FallSim::new();
+        sim.resize(10, 5, 0.0);
+        let cells = grid(&["内存 e\u{301}"]);
+        sim.step(&cells, 0.0, 0.016);
+        assert_eq!(sim.letters.len(), 3);
+        assert_eq!(sim.glyphs[1].ch, '存');
+        assert_eq!(sim.letters[1].orig_col, 2);
+        assert_eq!(sim.glyphs[2].marks, "\u{301}");
+    }
+
     #[test]
     fn default_palette_preserves_fall_colors() {
         let palette = default_palette();
@@ -120,6 +120,15 @@ impl Palette {
         (tint(rf), tint(gf), tint(bf))
     }
 
+    /// Color for neofetch's `${c1}`..`${c6}` logo markers: slot `n` (0-based)
+    /// takes the palette's stops in order, wrapping around short palettes.
+    pub fn slot(&self, n: usize) -> (u8, u8, u8) {
+        match self.stops {
+            [] => (255, 255, 255),
+            stops => stops[n % stops.len()],
+        }
+    }
+
     pub fn is_default(&self) -> bool {
         self.name == DEFAULT_PALETTE.name
     }
@@ -176,6 +185,13 @@ mod tests {
         assert_eq!(TEST_PALETTE.sample(0.5), (128, 128, 128));
     }
 
+    #[test]
+    fn slots_cycle_through_stops() {
+        assert_eq!(TEST_PALETTE.slot(0), (0, 0, 0));
+        assert_eq!(TEST_PALETTE.slot(1), (255, 255, 255));
+        assert_eq!(TEST_PALETTE.slot(4), (0, 0, 0));
+    }
+
     #[test]
     fn unknown_palette_falls_back_to_default() {
         assert_eq!(palette_or_default("missing").name, "default");
@@ -1,7 +1,18 @@
+use crate::system::{
+    CustomField, CustomFieldSource, DEFAULT_CUSTOM_TIMEOUT, FieldFormat, INFO_FIELD_KEYS,
+    LogoPosition, MAX_LAYOUT_SPACING, SNAPSHOT_DISTRO_KEY, field_placeholders,
+};
+use crate::util::color::{ColorMode, LogoColors, ansi256_rgb, parse_hex_rgb};
+use crate::util::graphics::ImageProtocol;
+use crate::util::i18n::Lang;
+use crate::util::template::Template;
+use crate::util::time::UptimeStyle;
+use crate::util::units::UnitSystem;
 use std::{
     env, fs,
     io::ErrorKind,
     path::{Path, PathBuf},
+    time::Duration,
 };
 
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -15,7 +26,27 @@ pub struct Config {
     pub no_header: Option<bool>,
     pub mono: Option<bool>,
     pub no_color: Option<bool>,
+    pub color_mode: Option<ColorMode>,
+    pub dither: Option<bool>,
+    pub alt_screen: Option<bool>,
+    pub fill_background: Option<bool>,
+    pub logo_colors: Option<LogoColors>,
+    /// Colors for the `${c1}`..`${c6}` logo markers; unset slots follow the palette.
+    pub logo_slots: [Option<(u8, u8, u8)>; LOGO_SLOTS],
+    pub image_protocol: Option<ImageProtocol>,
+    pub logo_position: Option<LogoPosition>,
+    pub layout_gap: Option<usize>,
+    pub layout_padding: Option<usize>,
+    pub layout_center: Option<bool>,
+    pub min_info_width: Option<usize>,
     pub seed: Option<u64>,
+    pub kernel_details: Option<bool>,
+    pub uptime_format: Option<UptimeStyle>,
+    pub units: Option<UnitSystem>,
+    pub unit_precision: Option<usize>,
+    pub lang: Option<Lang>,
+    pub custom_fields: Vec<CustomField>,
+    pub field_format: FieldFormat,
 }
 
 impl Config {
@@ -89,8 +120,15 @@ fn warn_ignored(path: &Path, reason: &str) {
     );
 }
 
+pub const MAX_UNIT_PRECISION: usize = 6;
+pub const LOGO_SLOTS: usize = 6;
+
 fn parse_config(contents: &str) -> Result<Config, String> {
     let mut config = Config::default();
+    let mut section: Option<Section> = None;
+    // Label and template entries are checked once every custom field is known.
+    let mut labels = Vec::new();
+    let mut templates = Vec::new();
 
     for (line_index, raw_line) in contents.lines().enumerate() {
         let line_number = line_index + 1;
@@ -99,6 +137,30 @@ fn parse_config(contents: &str) -> Result<Config, String> {
             continue;
         }
 
+        if let Some(header) = line.strip_prefix('[') {
+            let name = header
+                .strip_suffix(']')
+                .ok_or_else(|| format!("line {line_number}: expected `]` after section name"))?
+                .trim();
+            if let Some(Section::Field(field)) = section.take() {
+                config.custom_fields.push(field.finish()?);
+            }
+            section = Some(match name {
+                "labels" => Section::Labels,
+                "templates" => Section::Templates,
+                "logo_slots" => Section::LogoSlots,
+                _ => {
+                    let key = name
+                        .strip_prefix("fields.")
+                        .ok_or_else(|| format!("line {line_number}: unknown section `[{name}]`"))?;
+                    validate_custom_key(key, &config.custom_fields)
+                        .map_err(|err| format!("line {line_number}: {err}"))?;
+                    Section::Field(PendingCustomField::new(key))
+                }
+            });
+            continue;
+        }
+
         let (raw_key, raw_value) = line
             .split_once('=')
             .ok_or_else(|| format!("line {line_number}: expected key = value"))?;
@@ -110,6 +172,39 @@ fn parse_config(contents: &str) -> Result<Config, String> {
         let value =
             parse_value(raw_value.trim()).map_err(|err| format!("line {line_number}: {err}"))?;
 
+        match section.as_mut() {
+            Some(Section::Field(field)) => {
+                field
+                    .set(&key, value)
+                    .map_err(|err| format!("line {line_number}: {err}"))?;
+                continue;
+            }
+            Some(Section::Labels) => {
+                labels.push((line_number, key.clone(), value.into_string(&key)?));
+                continue;
+            }
+            Some(Section::Templates) => {
+                templates.push((line_number, key.clone(), value.into_string(&key)?));
+                continue;
+            }
+            Some(Section::LogoSlots) => {
+                let slot = key
+                    .strip_prefix('c')
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .filter(|n| (1..=LOGO_SLOTS).contains(n))
+                    .ok_or_else(|| {
+                        format!("line {line_number}: unknown logo slot `{key}` (expected c1-c6)")
+                    })?;
+                config.logo_slots[slot - 1] = Some(
+                    value
+                        .into_slot_color()
+                        .map_err(|err| format!("line {line_number}: slot `{key}` {err}"))?,
+                );
+                continue;
+            }
+            None => {}
+        }
+
         match key.as_str() {
             "style" => config.style = Some(value.into_string("style")?),
             "speed" => config.speed = Some(value.into_f64("speed")? as f32),
@@ -120,14 +215,259 @@ fn parse_config(contents: &str) -> Result<Config, String> {
             "no_header" => config.no_header = Some(value.into_bool("no_header")?),
             "mono" => config.mono = Some(value.into_bool("mono")?),
             "no_color" => config.no_color = Some(value.into_bool("no_color")?),
+            "color_mode" => {
+                let value = value.into_string("color_mode")?;
+                config.color_mode = Some(ColorMode::parse(&value).ok_or_else(|| {
+                    format!(
+                        "line {line_number}: key `color_mode` expects \"auto\", \"truecolor\", \"256\", \"16\" or \"none\""
+                    )
+                })?);
+            }
+            "dither" => config.dither = Some(value.into_bool("dither")?),
+            "alt_screen" => config.alt_screen = Some(value.into_bool("alt_screen")?),
+            "logo_colors" => {
+                let value = value.into_string("logo_colors")?;
+                config.logo_colors = Some(LogoColors::parse(&value).ok_or_else(|| {
+                    format!(
+                        "line {line_number}: key `logo_colors` expects \"replace\", \"keep\", \"blend\" or \"modulate\""
+                    )
+                })?);
+            }
+            "image_protocol" => {
+                let value = value.into_string("image_protocol")?;
+                config.image_protocol = Some(ImageProtocol::parse(&value).ok_or_else(|| {
+                    format!(
+                        "line {line_number}: key `image_protocol` expects \"auto\", \"halfblocks\", \"sixel\" or \"kitty\""
+                    )
+                })?);
+            }
+            "logo_position" => {
+                let value = value.into_string("logo_position")?;
+                config.logo_position = Some(LogoPosition::parse(&value).ok_or_else(|| {
+                    format!(
+                        "line {line_number}: key `logo_position` expects \"left\", \"right\", \"top\" or \"bottom\""
+                    )
+                })?);
+            }
+            "layout_gap" | "layout_padding" => {
+                let spacing = value.into_u32(&key)?;
+                if spacing > MAX_LAYOUT_SPACING as u32 {
+                    return Err(format!(
+                        "line {line_number}: key `{key}` expects 0-{MAX_LAYOUT_SPACING}"
+                    ));
+                }
+                match key.as_str() {
+                    "layout_gap" => config.layout_gap = Some(spacing as usize),
+                    _ => config.layout_padding = Some(spacing as usize),
+                }
+            }
+            "layout_center" => config.layout_center = Some(value.into_bool("layout_center")?),
+            "min_info_width" => {
+                config.min_info_width = Some(value.into_u32("min_info_width")? as usize)
+            }
+            "fill_background" => config.fill_background = Some(value.into_bool("fill_background")?),
             "seed" => config.seed = Some(value.into_u64("seed")?),
+            "kernel_details" => config.kernel_details = Some(value.into_bool("kernel_details")?),
+            "uptime_format" => {
+                let value = value.into_string("uptime_format")?;
+                config.uptime_format = Some(UptimeStyle::parse(&value).ok_or_else(|| {
+                    format!("line {line_number}: key `uptime_format` expects \"long\" or \"short\"")
+                })?);
+            }
+            "units" => {
+                let value = value.into_string("units")?;
+                config.units = Some(UnitSystem::parse(&value).ok_or_else(|| {
+                    format!("line {line_number}: key `units` expects \"binary\" or \"decimal\"")
+                })?);
+            }
+            "unit_precision" => {
+                let precision = value.into_u32("unit_precision")?;
+                if precision > MAX_UNIT_PRECISION as u32 {
+                    return Err(format!(
+                        "line {line_number}: key `unit_precision` expects 0-{MAX_UNIT_PRECISION}"
+                    ));
+                }
+                config.unit_precision = Some(precision as usize);
+            }
+            "lang" => {
+                let value = value.into_string("lang")?;
+                config.lang = Some(Lang::parse(&value).ok_or_else(|| {
+                    format!("line {line_number}: key `lang` expects \"en\" or \"zh-CN\"")
+                })?);
+            }
+            "separator" => config.field_format.separator = Some(value.into_string("separator")?),
             _ => return Err(format!("line {line_number}: unknown key `{key}`")),
         }
     }
 
+    if let Some(Section::Field(field)) = section {
+        config.custom_fields.push(field.finish()?);
+    }
+
+    for (line_number, key, label) in labels {
+        field_placeholders(&key, &config.custom_fields)
+            .ok_or_else(|| format!("line {line_number}: {}", not_formattable(&key, "labels")))?;
+        config.field_format.labels.push((key, label));
+    }
+    for (line_number, key, source) in templates {
+        let template = parse_field_template(&key, &source, &config.custom_fields)
+            .map_err(|err| format!("line {line_number}: {err}"))?;
+        config.field_format.templates.push((key, template));
+    }
+
     Ok(config)
 }
 
+enum Section {
+    Field(PendingCustomField),
+    Labels,
+    Templates,
+    LogoSlots,
+}
+
+fn not_formattable(key: &str, what: &str) -> String {
+    if INFO_FIELD_KEYS.contains(&key) || key == SNAPSHOT_DISTRO_KEY {
+        format!("field `{key}` does not support custom {what}")
+    } else {
+        format!("unknown field `{key}` in [{what}]")
+    }
+}
+
+fn parse_field_template(
+    key: &str,
+    source: &str,
+    custom_fields: &[CustomField],
+) -> Result<Template, String> {
+    let placeholders =
+        field_placeholders(key, custom_fields).ok_or_else(|| not_formattable(key, "templates"))?;
+    let template =
+        Template::parse(source).map_err(|err| format!("invalid template for `{key}`: {err}"))?;
+    if let Some(unknown) = template
+        .placeholders()
+        .find(|name| *name != "value" && !placeholders.contains(name))
+    {
+        let mut available = placeholders.to_vec();
+        available.push("value");
+        return Err(format!(
+            "template for `{key}` uses unknown placeholder `{{{unknown}}}` (available: {})",
+            available.join(", ")
+        ));
+    }
+    Ok(template)
+}
+
+fn validate_custom_key(key: &str, defined: &[CustomField]) -> Result<(), String> {
+    if key.is_empty()
+        || !key
+            .chars()
+            .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '_')
+    {
+        return Err(format!(
+            "custom field key `{key}` must use lowercase letters, digits and `_`"
+        ));
+    }
+    if INFO_FIELD_KEYS.contains(&key) {
+        return Err(format!(
+            "custom field `{key}` clashes with a built-in field"
+        ));
+    }
+    if defined.iter().any(|field| field.key == key) {
+        return Err(format!("custom field `{key}` is defined twice"));
+    }
+    Ok(())
+}
+
+/// A `[fields.<key>]` section while its keys are still being read.
+struct PendingCustomField {
+    key: String,
+    label: Option<String>,
+    source: Option<CustomFieldSource>,
+    timeout: Option<Duration>,
+    cache_ttl: Option<Duration>,
+}
+
+impl PendingCustomField {
+    fn new(key: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            label: None,
+            source: None,
+            timeout: None,
+            cache_ttl: None,
+        }
+    }
+
+    fn set(&mut self, key: &str, value: Value) -> Result<(), String> {
+        let source = match key {
+            "label" => {
+                self.label = Some(value.into_string("label")?);
+                return Ok(());
+            }
+            "timeout" => {
+                let secs = value.into_f64("timeout")?;
+                if secs <= 0.0 {
+                    return Err("key `timeout` expects a positive number of seconds".to_string());
+                }
+                self.timeout = Some(Duration::from_secs_f64(secs));
+                return Ok(());
+            }
+            "cache_ttl" => {
+                self.cache_ttl = Some(Duration::from_secs(value.into_u64("cache_ttl")?));
+                return Ok(());
+            }
+            "command" => CustomFieldSource::Command(value.into_string("command")?),
+            "file" => CustomFieldSource::File(expand_home(&value.into_string("file")?)),
+            "env" => CustomFieldSource::Env(value.into_string("env")?),
+            _ => return Err(format!("unknown key `{key}` in [fields.{}]", self.key)),
+        };
+        if self.source.is_some() {
+            return Err(format!(
+                "[fields.{}] takes only one of `command`, `file` or `env`",
+                self.key
+            ));
+        }
+        self.source = Some(source);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<CustomField, String> {
+        let source = self.source.ok_or_else(|| {
+            format!(
+                "[fields.{}] needs one of `command`, `file` or `env`",
+                self.key
+            )
+        })?;
+        let label = self.label.unwrap_or_else(|| default_label(&self.key));
+        Ok(CustomField {
+            // Keys are parsed once per run and then flow through the same
+            // `&'static str` selection and ordering as the built-in keys.
+            key: Box::leak(self.key.into_boxed_str()),
+            label,
+            source,
+            timeout: self.timeout.unwrap_or(DEFAULT_CUSTOM_TIMEOUT),
+            cache_ttl: self.cache_ttl,
+        })
+    }
+}
+
+fn default_label(key: &str) -> String {
+    let spaced = key.replace('_', " ");
+    let mut chars = spaced.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => spaced,
+    }
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/")
+        && let Some(home) = env::var_os("HOME")
+    {
+        return PathBuf::from(home).join(rest);
+    }
+    PathBuf::from(path)
+}
+
 fn normalize_key(key: &str) -> String {
     key.replace('-', "_")
 }
@@ -203,6 +543,16 @@ impl Value {
         u64::try_from(value).map_err(|_| format!("key `{key}` expects a non-negative integer"))
     }
 
+    /// A `#rrggbb` string or an xterm-256 index, as neofetch's color lists use.
+    fn into_slot_color(self) -> Result<(u8, u8, u8), String> {
+        let color = match self {
+            Value::String(text) => parse_hex_rgb(&text),
+            Value::Integer(index) => u8::try_from(index).ok().map(ansi256_rgb),
+            _ => None,
+        };
+        color.ok_or_else(|| "expects \"#rrggbb\" or a color index 0-255".to_string())
+    }
+
     fn into_integer(self, key: &str) -> Result<i128, String> {
         match self {
             Value::Integer(value) => Ok(value),
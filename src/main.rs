@@ -10,10 +10,12 @@ use animation::{
     calculate_matrix_color_with_palette, calculate_meteor_color_with_palette,
     calculate_plasma_color_with_palette, calculate_pulse_rings_color_with_palette,
 };
-use config::Config;
+use config::{Config, LOGO_SLOTS, MAX_UNIT_PRECISION};
 use system::{
-    INFO_FIELD_KEYS, InfoFieldSelection, SystemInfoOptions, generate_system_info,
-    generate_system_info_json, info_field_key,
+    Blocks, CustomField, CustomFieldSource, DEFAULT_MIN_INFO_WIDTH, FieldFormat, INFO_FIELD_KEYS,
+    InfoFieldSelection, JSON_SCHEMA, Layout, LogoPosition, MAX_LAYOUT_SPACING, OutputFormat,
+    Snapshot, SystemInfoOptions, arrange_system_info, generate_system_info_json,
+    generate_system_info_output, info_field_key, system_info_blocks,
 };
 
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
@@ -22,12 +24,21 @@ use std::{
     env, fs,
     io::{self, IsTerminal, Write, stdout},
     process::Command,
+    sync::atomic::{AtomicBool, Ordering},
     thread,
     time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
-use util::ansi::parse_ansi_text;
+use util::ansi::{Attrs, SgrState, parse_ansi_text};
+use util::cell::{Cell, layout_line, layout_lines};
+use util::color::{ColorDepth, ColorMode, LogoColors};
 use util::framebuf::FrameBuf;
+use util::graphics::{self, ImageProtocol};
+use util::i18n::{Lang, decimal_separator_from_env};
+use util::term;
+use util::time::{UptimeStyle, civil_from_unix_days};
+use util::units::{ByteUnits, UnitSystem};
+use util::width::char_width;
 
 const MAX_LOGO_LINES: usize = 60;
 const MAX_LOGO_COLUMNS: usize = 120;
@@ -68,12 +79,22 @@ fn main() -> io::Result<()> {
         println!("{}", animation::available_palette_names().join("\n"));
         return Ok(());
     }
-    if args.iter().any(|a| a == "--list-fields") {
-        println!("{}", INFO_FIELD_KEYS.join("\n"));
+    if args.iter().any(|a| a == "--print-json-schema") {
+        print!("{}", JSON_SCHEMA);
         return Ok(());
     }
     let config_path = parse_config_path_argument(&args);
     let config = Config::load(config_path.as_deref(), parse_no_config_argument(&args));
+    if args.iter().any(|a| a == "--list-fields") {
+        for key in INFO_FIELD_KEYS
+            .iter()
+            .copied()
+            .chain(config.custom_fields.iter().map(|field| field.key))
+        {
+            println!("{}", key);
+        }
+        return Ok(());
+    }
     let seed = parse_seed_argument(&args, &config);
     if let Some(seed) = seed {
         fastrand::seed(seed);
@@ -94,8 +115,29 @@ fn main() -> io::Result<()> {
         no_header: parse_no_header_argument(&args, &config),
         mono: parse_mono_argument(&args, &config),
         no_color: parse_no_color_argument(&args, &config),
+        color_mode: parse_color_mode_argument(&args, &config),
+        dither: parse_dither_argument(&args, &config),
+        alt_screen: parse_alt_screen_argument(&args, &config),
+        fill_background: parse_fill_background_argument(&args, &config),
+        logo_colors: parse_logo_colors_argument(&args, &config).unwrap_or(
+            // Image logos show their own colors unless asked otherwise.
+            if parse_flag_value(&args, "--logo-image").is_some() {
+                LogoColors::Modulate
+            } else {
+                LogoColors::default()
+            },
+        ),
+        logo_slots: config.logo_slots,
+        image_protocol: parse_image_protocol_argument(&args, &config),
+        layout: parse_layout_argument(&args, &config),
         seed,
         palette: parse_palette_argument(&args),
+        kernel_details: parse_kernel_details_argument(&args, &config),
+        custom_fields: config.custom_fields.clone(),
+        field_format: config.field_format.clone(),
+        uptime_style: parse_uptime_format_argument(&args, &config),
+        byte_units: parse_byte_units_argument(&args, &config),
+        lang: parse_lang_argument(&args, &config),
     };
     if parse_print_config_argument(&args) {
         print_effective_config(&effective_config);
@@ -104,70 +146,169 @@ fn main() -> io::Result<()> {
     let show_logo = !effective_config.no_logo;
     let show_packages = !effective_config.no_packages;
     let show_header = !effective_config.no_header;
-    let logo_override = if show_logo {
-        parse_logo_file_argument(&args).and_then(|path| match load_logo_file(&path) {
-            Ok(lines) => Some(lines),
+    let keep_logo_colors =
+        effective_config.logo_colors != LogoColors::Replace && !effective_config.no_color;
+    let output_format = match parse_format_argument(&args) {
+        Ok(format) => format,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            std::process::exit(2);
+        }
+    };
+    // JSON and `--format` never show the logo, so don't decode images or
+    // query the terminal for them.
+    let machine_output = output_format.is_some() || parse_json_argument(&args);
+    let logo_slots = logo_slot_colors(&effective_config);
+    let mut logo_overlay = None;
+    let mut logo_slot_map = None;
+    let logo_image =
+        parse_flag_value(&args, "--logo-image").filter(|_| show_logo && !machine_output);
+    let logo_override = if let Some(path) = logo_image {
+        let protocol = if effective_config.no_color {
+            ImageProtocol::HalfBlocks
+        } else {
+            resolve_image_protocol(effective_config.image_protocol)
+        };
+        match load_logo_image(&path, protocol, !effective_config.no_color) {
+            Ok(image) => {
+                logo_overlay = image.overlay;
+                Some(image.lines)
+            }
             Err(err) => {
                 eprintln!(
-                    "warning: could not read logo file '{}': {}; using built-in logo",
+                    "warning: could not load logo image '{}': {}; using built-in logo",
                     path, err
                 );
                 None
             }
+        }
+    } else if show_logo {
+        parse_logo_file_argument(&args).and_then(|path| {
+            match load_logo_file(&path, keep_logo_colors.then_some(&logo_slots)) {
+                Ok(file) => {
+                    logo_slot_map = Some(LogoSlots {
+                        columns: file.slots,
+                        colors: logo_slots,
+                    });
+                    Some(file.lines)
+                }
+                Err(err) => {
+                    eprintln!(
+                        "warning: could not read logo file '{}': {}; using built-in logo",
+                        path, err
+                    );
+                    None
+                }
+            }
         })
     } else {
         None
     };
     let distro_id = parse_distro_argument(&args);
-    let field_selection = match parse_field_selection_argument(&args, show_packages, show_header) {
-        Ok(selection) => selection,
-        Err(message) => {
+    let snapshot = match parse_flag_value(&args, "--from-json").map(|path| Snapshot::load(&path)) {
+        Some(Ok(snapshot)) => Some(snapshot),
+        Some(Err(message)) => {
             eprintln!("error: {}", message);
             std::process::exit(2);
         }
+        None => None,
+    };
+    let field_key = |input: &str| {
+        info_field_key(input, &effective_config.custom_fields).or_else(|| {
+            snapshot
+                .as_ref()
+                .and_then(|snapshot| snapshot.field_key(input))
+        })
     };
+    let field_selection =
+        match parse_field_selection_argument(&args, show_packages, show_header, &field_key) {
+            Ok(selection) => selection,
+            Err(message) => {
+                eprintln!("error: {}", message);
+                std::process::exit(2);
+            }
+        };
     let info_options = SystemInfoOptions::new(show_logo, field_selection)
         .with_logo_override(logo_override)
-        .with_distro_id(distro_id);
+        .with_distro_id(distro_id)
+        .with_kernel_details(effective_config.kernel_details)
+        .with_custom_fields(effective_config.custom_fields.clone())
+        .with_field_format(effective_config.field_format.clone())
+        .with_uptime_style(effective_config.uptime_style)
+        .with_byte_units(effective_config.byte_units)
+        .with_lang(effective_config.lang)
+        .with_decimal_separator(parse_decimal_separator(
+            &args,
+            &config,
+            effective_config.lang,
+        ))
+        .with_snapshot(snapshot)
+        .with_layout(effective_config.layout.with_terminal(terminal_size()));
     let max_frames = if parse_frame_argument(&args) {
         Some(1usize)
     } else {
         None
     };
+    if let Some(format) = output_format {
+        print!("{}", generate_system_info_output(&info_options, format));
+        return Ok(());
+    }
     // Auto fallback to one-shot in non-TTY pipelines
     let is_tty = stdout().is_terminal();
     if !is_tty && !parse_json_argument(&args) {
-        let lines = generate_system_info(&info_options);
-        for line in lines {
+        let arrangement = arrange_system_info(&info_options);
+        for line in &arrangement.lines {
             println!("{}", line);
         }
+        if let Some(overlay) = logo_overlay.zip(arrangement.logo_origin) {
+            write_logo_overlay(&mut stdout(), &overlay, arrangement.lines.len())?;
+        }
         return Ok(());
     }
     if parse_json_argument(&args) {
-        println!("{}", generate_system_info_json(&info_options));
+        println!(
+            "{}",
+            generate_system_info_json(&info_options, parse_json_pretty_argument(&args))
+        );
         return Ok(());
     }
     if parse_fetch_argument(&args) {
         // One-shot system info output, no animation
-        let lines = generate_system_info(&info_options);
+        let arrangement = arrange_system_info(&info_options);
         let mut out = stdout();
-        for line in lines {
+        for line in &arrangement.lines {
             writeln!(out, "{}", line)?;
         }
+        if let Some(overlay) = logo_overlay.zip(arrangement.logo_origin) {
+            write_logo_overlay(&mut out, &overlay, arrangement.lines.len())?;
+        }
         return Ok(());
     }
-    let sysinfo = generate_system_info(&info_options);
+    let blocks = system_info_blocks(&info_options);
     let options = AnimationOptions {
         speed: effective_config.speed,
         style: effective_config.style,
         color_fps: effective_config.color_fps,
         duration: effective_config.duration,
         mono: effective_config.mono,
-        no_color: effective_config.no_color,
+        color_depth: if effective_config.no_color {
+            ColorDepth::None
+        } else {
+            effective_config.color_mode.resolve()
+        },
+        dither: effective_config.dither,
+        alt_screen: effective_config.alt_screen,
+        fill_background: effective_config.fill_background,
+        logo_colors: effective_config.logo_colors,
+        inline: parse_inline_argument(&args),
         max_frames,
         palette: effective_config.palette,
+        stats: parse_stats_argument(&args),
+        layout: info_options.layout,
+        logo_overlay,
+        logo_slots: logo_slot_map,
     };
-    show_animation_mode(&sysinfo, options)
+    show_animation_mode(&blocks, options)
 }
 
 struct AnimationOptions {
@@ -176,9 +317,28 @@ struct AnimationOptions {
     color_fps: f32,
     duration: Option<f32>,
     mono: bool,
-    no_color: bool,
+    color_depth: ColorDepth,
+    dither: bool,
+    alt_screen: bool,
+    fill_background: bool,
+    logo_colors: LogoColors,
+    inline: bool,
     max_frames: Option<usize>,
     palette: &'static Palette,
+    stats: bool,
+    /// Placement of logo and info, redone when the terminal is resized.
+    layout: Layout,
+    /// Sixel or kitty image drawn over the blank logo column.
+    logo_overlay: Option<String>,
+    /// Slots a logo file's markers put its art in.
+    logo_slots: Option<LogoSlots>,
+}
+
+/// Palette slots of a logo file's cells, relative to the logo's top-left.
+struct LogoSlots {
+    /// Slot per column of each logo line.
+    columns: Vec<Vec<Option<usize>>>,
+    colors: [(u8, u8, u8); LOGO_SLOTS],
 }
 
 struct EffectiveConfig {
@@ -191,23 +351,77 @@ struct EffectiveConfig {
     no_header: bool,
     mono: bool,
     no_color: bool,
+    color_mode: ColorMode,
+    dither: bool,
+    alt_screen: bool,
+    fill_background: bool,
+    logo_colors: LogoColors,
+    logo_slots: [Option<(u8, u8, u8)>; LOGO_SLOTS],
+    image_protocol: ImageProtocol,
+    layout: Layout,
     seed: Option<u64>,
     palette: &'static Palette,
+    kernel_details: bool,
+    custom_fields: Vec<CustomField>,
+    field_format: FieldFormat,
+    uptime_style: UptimeStyle,
+    byte_units: ByteUnits,
+    lang: Lang,
+}
+
+/// Where the animation draws.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Screen {
+    /// Clear the main screen and draw from its top.
+    Main,
+    /// Draw on the alternate screen, leaving the main screen untouched.
+    Alternate,
+    /// Draw in this many rows below the cursor, scrolling first if they
+    /// don't fit, and leave the result in scrollback.
+    Inline(usize),
 }
 
 /// RAII guard: raw mode + hidden cursor on entry, always restored on exit
 /// (including early `?` returns and panics, via Drop).
 struct TermGuard {
     raw: bool,
+    screen: Screen,
+    /// The terminal understands synchronized output (DEC mode 2026).
+    sync: bool,
 }
 
+/// Set while the alternate screen is active, so the panic hook can leave it.
+static ALT_SCREEN_ACTIVE: AtomicBool = AtomicBool::new(false);
+
 impl TermGuard {
-    fn new() -> Self {
+    fn new(screen: Screen) -> Self {
         let raw = enable_raw_mode().is_ok();
+        // Replies arrive on stdin, so only ask when raw mode keeps them from
+        // being echoed.
+        let sync = raw
+            && io::stdin().is_terminal()
+            && term::query_synchronized_output(Duration::from_millis(150));
         let mut out = stdout();
-        let _ = out.write_all(b"\x1b[?25l\x1b[2J\x1b[H");
+        let _ = out.write_all(b"\x1b[?25l");
+        match screen {
+            Screen::Main => {
+                let _ = out.write_all(b"\x1b[2J\x1b[H");
+            }
+            Screen::Alternate => {
+                ALT_SCREEN_ACTIVE.store(true, Ordering::SeqCst);
+                let _ = out.write_all(b"\x1b[?1049h\x1b[2J\x1b[H");
+            }
+            Screen::Inline(rows) => {
+                // Line feeds scroll the screen when the cursor is near the
+                // bottom; cursor-down would stop at the last row.
+                let _ = write!(out, "\r{}", "\n".repeat(rows.saturating_sub(1)));
+                if rows > 1 {
+                    let _ = write!(out, "\x1b[{}A", rows - 1);
+                }
+            }
+        }
         let _ = out.flush();
-        TermGuard { raw }
+        TermGuard { raw, screen, sync }
     }
 }
 
@@ -217,18 +431,27 @@ impl Drop for TermGuard {
             let _ = disable_raw_mode();
         }
         let mut out = stdout();
+        if self.screen == Screen::Alternate {
+            ALT_SCREEN_ACTIVE.store(false, Ordering::SeqCst);
+            let _ = out.write_all(b"\x1b[?1049l");
+        }
         let _ = out.write_all(b"\x1b[?25h\x1b[0m");
         let _ = out.flush();
     }
 }
 
 /// Restore the terminal before the default panic message prints, so a panic
-/// mid-animation doesn't leave the shell with a hidden cursor in raw mode.
+/// mid-animation doesn't leave the shell with a hidden cursor in raw mode,
+/// a held synchronized update, or stuck on the alternate screen.
 fn install_panic_hook() {
     let default_hook = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |info| {
         let _ = disable_raw_mode();
         let mut out = io::stdout();
+        let _ = out.write_all(term::SYNC_END.as_bytes());
+        if ALT_SCREEN_ACTIVE.swap(false, Ordering::SeqCst) {
+            let _ = out.write_all(b"\x1b[?1049l");
+        }
         let _ = out.write_all(b"\x1b[?25h\x1b[0m\r\n");
         let _ = out.flush();
         default_hook(info);
@@ -262,33 +485,36 @@ struct GlitchBurst {
     dur: f32,
 }
 
-type GlitchCell = (char, (u8, u8, u8));
+/// Source column and color of a glyph landing in a glitched output column.
+type GlitchCell = (usize, (u8, u8, u8));
 
-fn show_animation_mode(lines: &[String], options: AnimationOptions) -> io::Result<()> {
+fn show_animation_mode(blocks: &Blocks, options: AnimationOptions) -> io::Result<()> {
     let AnimationOptions {
         speed,
         style,
         color_fps,
         duration,
         mono,
-        no_color,
+        color_depth,
+        dither,
+        alt_screen,
+        fill_background,
+        logo_colors,
+        inline,
         max_frames,
         palette,
+        stats,
+        layout,
+        logo_overlay,
+        logo_slots,
     } = options;
-    let parsed: Vec<Vec<(String, char)>> = lines.iter().map(|l| parse_ansi_text(l)).collect();
-    // Plain printable-character grid (ANSI stripped) for styles that need
-    // cell-level layout knowledge: Fall physics, Typing reveal, edge detection.
-    let plain: Vec<Vec<char>> = parsed
-        .iter()
-        .map(|row| {
-            row.iter()
-                .filter(|(a, c)| a.is_empty() && *c != '\0')
-                .map(|(_, c)| *c)
-                .collect()
-        })
-        .collect();
-    let edge_mask = (style == AnimationStyle::EdgeGlow).then(|| build_edge_mask(&plain));
-    let total_chars: usize = plain.iter().map(|r| r.len()).sum();
+    let mut arrangement = layout.arrange(blocks);
+    // One display cell per terminal column, so every renderer (and the edge
+    // mask) agrees on where wide glyphs and combining marks land.
+    let mut cells = layout_lines(&arrangement.lines);
+    mark_logo_slots(&mut cells, logo_slots.as_ref(), arrangement.logo_origin);
+    let mut edge_mask = (style == AnimationStyle::EdgeGlow).then(|| build_edge_mask(&cells));
+    let mut total_glyphs = count_glyphs(&cells);
 
     let speed = speed.max(0.05);
     // Frame pacing is wall-clock based: --speed accelerates the animation
@@ -296,8 +522,18 @@ fn show_animation_mode(lines: &[String], options: AnimationOptions) -> io::Resul
     let target_fps = color_fps.clamp(5.0, 120.0);
     let frame_dt = Duration::from_secs_f32(1.0 / target_fps);
 
+    let mut screen = if inline {
+        // Reserve exactly the rows the info needs, as far as the screen allows.
+        let height = size().map(|(_, th)| th as usize).unwrap_or(cells.len());
+        Screen::Inline(cells.len().clamp(1, height.max(1)))
+    } else if alt_screen {
+        Screen::Alternate
+    } else {
+        Screen::Main
+    };
+
     install_panic_hook();
-    let guard = TermGuard::new();
+    let guard = TermGuard::new(screen);
 
     let start = Instant::now();
     let mut next_frame = Instant::now();
@@ -308,10 +544,17 @@ fn show_animation_mode(lines: &[String], options: AnimationOptions) -> io::Resul
     let mut glitch_shift: Vec<i32> = Vec::new();
     let mut glitch_line: Vec<Option<GlitchCell>> = Vec::new();
     let mut fall = FallSim::new();
-    let mut fb = FrameBuf::new(mono, no_color);
+    let mut fb = FrameBuf::new(mono, color_depth)
+        .with_dither(dither)
+        .with_stats(stats)
+        .with_sync(guard.sync)
+        .with_inline(inline)
+        .with_logo_colors(logo_colors)
+        .with_logo_slots(logo_slots.as_ref().map_or(&[], |slots| &slots.colors[..]));
     let mut last_dims: (u16, u16) = (0, 0);
     let mut frames_rendered = 0usize;
     let mut rows_drawn = 0usize;
+    let mut overlay_pending = logo_overlay.is_some();
 
     loop {
         if duration.is_some_and(|d| start.elapsed().as_secs_f32() >= d) {
@@ -343,33 +586,57 @@ fn show_animation_mode(lines: &[String], options: AnimationOptions) -> io::Resul
         last_anim_time = elapsed;
 
         let (tw, th) = size()?;
-        let (twu, thu) = (tw as usize, th as usize);
+        let (twu, mut thu) = (tw as usize, th as usize);
+        if let Screen::Inline(rows) = screen {
+            thu = thu.min(rows);
+        }
         if twu == 0 || thu == 0 {
             continue;
         }
         fb.begin();
         if (tw, th) != last_dims {
-            if style == AnimationStyle::Fall {
-                fall.resize(twu, thu, elapsed);
+            // Narrower or wider, the logo may shrink, move or come back.
+            let resized = layout
+                .with_terminal(Some((twu, th as usize)))
+                .arrange(blocks);
+            if resized != arrangement {
+                arrangement = resized;
+                cells = layout_lines(&arrangement.lines);
+                mark_logo_slots(&mut cells, logo_slots.as_ref(), arrangement.logo_origin);
+                edge_mask = (style == AnimationStyle::EdgeGlow).then(|| build_edge_mask(&cells));
+                total_glyphs = count_glyphs(&cells);
             }
             if last_dims != (0, 0) {
-                fb.push_ansi("\x1b[2J"); // wipe stale cells after a resize
+                fb.clear_screen(); // wipe stale cells after a resize
+                overlay_pending = logo_overlay.is_some();
+            }
+            if let Screen::Inline(rows) = &mut screen
+                && cells.len().min(th as usize) > *rows
+            {
+                // The re-arranged frame is taller than the rows reserved so
+                // far; scroll to make room rather than clip it.
+                *rows = cells.len().min(th as usize);
+                fb.reserve_rows(*rows);
+                thu = *rows;
+            }
+            if style == AnimationStyle::Fall {
+                fall.resize(twu, thu, elapsed);
             }
             last_dims = (tw, th);
         }
 
         rows_drawn = match style {
             AnimationStyle::Fall => {
-                fall.step(&plain, elapsed, dt);
+                fall.step(&cells, elapsed, dt);
                 fall.render(&mut fb, elapsed, palette);
                 thu
             }
             AnimationStyle::Typing => {
-                render_typing(&mut fb, &plain, elapsed, twu, thu, total_chars, palette)
+                render_typing(&mut fb, &cells, elapsed, twu, thu, total_glyphs, palette)
             }
             AnimationStyle::Glitch => render_glitch(
                 &mut fb,
-                &plain,
+                &cells,
                 elapsed,
                 twu,
                 thu,
@@ -385,7 +652,7 @@ fn show_animation_mode(lines: &[String], options: AnimationOptions) -> io::Resul
                 }
                 render_generic(
                     &mut fb,
-                    &parsed,
+                    &cells,
                     &style,
                     elapsed,
                     twu,
@@ -394,31 +661,69 @@ fn show_animation_mode(lines: &[String], options: AnimationOptions) -> io::Resul
                     &sparks,
                     edge_mask.as_deref(),
                     palette,
+                    fill_background,
                 )
             }
         };
         fb.write_to(&mut stdout())?;
+        if overlay_pending
+            && let Some(overlay) = &logo_overlay
+            && let Some(origin) = arrangement.logo_origin
+        {
+            fb.write_overlay(&mut stdout(), overlay, origin)?;
+            overlay_pending = false;
+        }
         frames_rendered += 1;
         if max_frames.is_some_and(|limit| frames_rendered >= limit) {
             break;
         }
     }
     // Park the cursor below the rendered content, then let the guard restore
-    // cursor visibility, colors and raw mode.
-    let mut out = stdout();
-    write!(out, "\x1b[{};1H", rows_drawn + 1)?;
-    out.flush()?;
-    drop(guard);
-    println!();
+    // cursor visibility, colors and raw mode. Leaving the alternate screen
+    // brings back the shell's cursor on its own; inline frames are replaced
+    // by the static info, so scrollback reads like a normal fetch.
+    match screen {
+        Screen::Main => {
+            let mut out = stdout();
+            write!(out, "\x1b[{};1H", rows_drawn + 1)?;
+            out.flush()?;
+            drop(guard);
+            println!();
+        }
+        Screen::Alternate => drop(guard),
+        Screen::Inline(_) => {
+            fb.begin();
+            fb.clear_screen();
+            fb.write_to(&mut stdout())?;
+            drop(guard);
+            let mut out = stdout();
+            for line in &arrangement.lines {
+                writeln!(out, "{}", line)?;
+            }
+            if let Some(overlay) = logo_overlay.zip(arrangement.logo_origin) {
+                write_logo_overlay(&mut out, &overlay, arrangement.lines.len())?;
+            }
+        }
+    }
+    if let Some(stats) = fb.stats() {
+        eprintln!("{}", stats.summary());
+    }
     Ok(())
 }
 
+fn count_glyphs(cells: &[Vec<Cell>]) -> usize {
+    cells
+        .iter()
+        .map(|row| row.iter().filter(|cell| !cell.is_continuation()).count())
+        .sum()
+}
+
 /// Per-cell color styles (everything except Fall / Typing / Glitch, which
 /// need their own layout logic). Returns the number of rows drawn.
 #[allow(clippy::too_many_arguments)]
 fn render_generic(
     fb: &mut FrameBuf,
-    parsed: &[Vec<(String, char)>],
+    cells: &[Vec<Cell>],
     style: &AnimationStyle,
     elapsed: f32,
     tw: usize,
@@ -427,58 +732,71 @@ fn render_generic(
     sparks: &[Spark],
     edge_mask: Option<&[Vec<bool>]>,
     palette: &Palette,
+    fill_background: bool,
 ) -> usize {
+    let color_at = |li: usize, printed: usize| {
+        let stable_id = li * tw + printed;
+        match style {
+            AnimationStyle::Matrix => {
+                calculate_matrix_color_with_palette(elapsed, li, printed, th, palette)
+            }
+            AnimationStyle::Fire => {
+                calculate_fire_color_with_palette(elapsed, li, printed, th, tw, palette)
+            }
+            AnimationStyle::Plasma => {
+                calculate_plasma_color_with_palette(elapsed, li, printed, tw, th, speed, palette)
+            }
+            AnimationStyle::Aurora => {
+                calculate_aurora_color_with_palette(elapsed, li, printed, tw, th, speed, palette)
+            }
+            AnimationStyle::PulseRings => calculate_pulse_rings_color_with_palette(
+                elapsed, li, printed, tw, th, speed, palette,
+            ),
+            AnimationStyle::Lava => {
+                calculate_lava_color_with_palette(elapsed, li, printed, tw, th, speed, palette)
+            }
+            AnimationStyle::Marquee => {
+                calculate_marquee_color_with_palette(elapsed, li, printed, tw, palette)
+            }
+            AnimationStyle::MeteorRain => {
+                calculate_meteor_color_with_palette(elapsed, li, printed, tw, th, palette)
+            }
+            // EdgeGlow rides on the Neon palette, adjusted below.
+            AnimationStyle::EdgeGlow => {
+                calculate_color_with_palette(&AnimationStyle::Neon, elapsed, stable_id, palette)
+            }
+            _ => calculate_color_with_palette(style, elapsed, stable_id, palette),
+        }
+    };
+    // Field styles can paint their color field, dimmed, behind the text.
+    let fill = fill_background
+        && matches!(
+            style,
+            AnimationStyle::Fire | AnimationStyle::Lava | AnimationStyle::Plasma
+        );
+    let background_at = |li: usize, printed: usize| {
+        let (r, g, b) = color_at(li, printed);
+        let dim = |channel: u8| (channel as f32 * 0.3) as u8;
+        (dim(r), dim(g), dim(b))
+    };
     let mut rows = 0usize;
-    for (li, row) in parsed.iter().take(th).enumerate() {
+    for (li, row) in cells.iter().take(th).enumerate() {
         fb.goto_line(li + 1);
-        let mut printed = 0usize;
-        for (ansi, ch) in row {
-            if !ansi.is_empty() {
-                fb.push_ansi(ansi);
-                continue;
+        let mut filled = 0;
+        for (printed, cell) in row.iter().enumerate() {
+            if !cell.ansi.is_empty() {
+                fb.push_ansi(&cell.ansi);
             }
-            if *ch == '\0' {
+            if cell.is_continuation() {
                 continue;
             }
-            if printed >= tw {
+            if printed + cell.width > tw {
                 break;
             }
-            let stable_id = li * tw + printed;
-            let mut rgb = match style {
-                AnimationStyle::Matrix => {
-                    calculate_matrix_color_with_palette(elapsed, li, printed, th, palette)
-                }
-                AnimationStyle::Fire => {
-                    calculate_fire_color_with_palette(elapsed, li, printed, th, tw, palette)
-                }
-                AnimationStyle::Plasma => calculate_plasma_color_with_palette(
-                    elapsed, li, printed, tw, th, speed, palette,
-                ),
-                AnimationStyle::Aurora => calculate_aurora_color_with_palette(
-                    elapsed, li, printed, tw, th, speed, palette,
-                ),
-                AnimationStyle::PulseRings => calculate_pulse_rings_color_with_palette(
-                    elapsed, li, printed, tw, th, speed, palette,
-                ),
-                AnimationStyle::Lava => {
-                    calculate_lava_color_with_palette(elapsed, li, printed, tw, th, speed, palette)
-                }
-                AnimationStyle::Marquee => {
-                    calculate_marquee_color_with_palette(elapsed, li, printed, tw, palette)
-                }
-                AnimationStyle::MeteorRain => {
-                    calculate_meteor_color_with_palette(elapsed, li, printed, tw, th, palette)
-                }
-                // EdgeGlow rides on the Neon palette, adjusted below.
-                AnimationStyle::EdgeGlow => {
-                    calculate_color_with_palette(&AnimationStyle::Neon, elapsed, stable_id, palette)
-                }
-                _ => calculate_color_with_palette(style, elapsed, stable_id, palette),
-            };
+            let mut rgb = color_at(li, printed);
             // Matrix marks non-trail cells with pure black: hide them.
             if *style == AnimationStyle::Matrix && rgb == (0, 0, 0) {
-                fb.put(' ', rgb);
-                printed += 1;
+                fb.put_blank(cell.width);
                 continue;
             }
             if *style == AnimationStyle::Fire {
@@ -489,7 +807,7 @@ fn render_generic(
                     }
                 }
             }
-            if *style == AnimationStyle::EdgeGlow && *ch != ' ' {
+            if *style == AnimationStyle::EdgeGlow && !cell.is_blank() {
                 let edge = edge_mask
                     .and_then(|m| m.get(li))
                     .and_then(|r| r.get(printed))
@@ -512,8 +830,19 @@ fn render_generic(
                     )
                 };
             }
-            fb.put(*ch, rgb);
-            printed += 1;
+            let bg = fill.then(|| background_at(li, printed));
+            fb.put_cell_styled(cell, rgb, bg, Attrs::NONE);
+            filled = printed + cell.width;
+        }
+        if fill {
+            for printed in filled..tw {
+                fb.put_styled(
+                    ' ',
+                    (0, 0, 0),
+                    Some(background_at(li, printed)),
+                    Attrs::NONE,
+                );
+            }
         }
         fb.end_line();
         rows = li + 1;
@@ -576,25 +905,31 @@ fn update_sparks(sparks: &mut Vec<Spark>, dt: f32, tw: usize, th: usize) {
     }
 }
 
-/// Typewriter reveal: characters appear at a fixed rate on the animation
-/// clock (so --speed scales it linearly), with a subtle hue drift.
+/// Typewriter reveal: glyphs appear at a fixed rate on the animation clock
+/// (so --speed scales it linearly), with a subtle hue drift.
 fn render_typing(
     fb: &mut FrameBuf,
-    plain: &[Vec<char>],
+    cells: &[Vec<Cell>],
     elapsed: f32,
     tw: usize,
     th: usize,
-    total_chars: usize,
+    total_glyphs: usize,
     palette: &Palette,
 ) -> usize {
-    let reveal_speed = 120.0f32; // chars per animation second
-    let chars_to_show = ((elapsed * reveal_speed) as usize).min(total_chars);
+    let reveal_speed = 120.0f32; // glyphs per animation second
+    let glyphs_to_show = ((elapsed * reveal_speed) as usize).min(total_glyphs);
     let mut shown = 0usize;
     let mut rows = 0usize;
-    for (li, row) in plain.iter().take(th).enumerate() {
+    for (li, row) in cells.iter().take(th).enumerate() {
         fb.goto_line(li + 1);
-        for (ci, &ch) in row.iter().take(tw).enumerate() {
-            let visible = shown < chars_to_show;
+        for (ci, cell) in row.iter().enumerate() {
+            if cell.is_continuation() {
+                continue;
+            }
+            if ci + cell.width > tw {
+                break;
+            }
+            let visible = shown < glyphs_to_show;
             shown += 1;
             if visible {
                 let hue = (elapsed * 35.0 + ci as f32 * 1.5 + li as f32 * 4.0) % 360.0;
@@ -603,9 +938,9 @@ fn render_typing(
                 } else {
                     palette.sample_tinted(hue / 360.0, 0.25, 0.92)
                 };
-                fb.put(ch, rgb);
+                fb.put_cell(cell, rgb);
             } else {
-                fb.put(' ', (0, 0, 0));
+                fb.put_blank(cell.width);
             }
         }
         fb.end_line();
@@ -619,7 +954,7 @@ fn render_typing(
 #[allow(clippy::too_many_arguments)]
 fn render_glitch(
     fb: &mut FrameBuf,
-    plain: &[Vec<char>],
+    cells: &[Vec<Cell>],
     elapsed: f32,
     tw: usize,
     th: usize,
@@ -654,11 +989,14 @@ fn render_glitch(
     }
     let distortion_energy = burst_energy;
     let mut rows = 0usize;
-    for (li, row) in plain.iter().take(th).enumerate() {
+    for (li, row) in cells.iter().take(th).enumerate() {
         fb.goto_line(li + 1);
         line_scratch.clear();
         line_scratch.resize(tw, None);
-        for (source_col, &ch) in row.iter().take(tw).enumerate() {
+        for (source_col, cell) in row.iter().enumerate().take(tw) {
+            if cell.is_continuation() {
+                continue;
+            }
             let shift = col_shift.get(source_col).copied().unwrap_or(0);
             let dest = (source_col as i32 + shift).clamp(0, tw as i32 - 1) as usize;
             let base_hue = (elapsed * 120.0 + source_col as f32 * 3.0) % 360.0;
@@ -673,12 +1011,21 @@ fn render_glitch(
             if distortion_energy > 0.0 && fastrand::f32() < 0.06 * distortion_energy {
                 b = b.saturating_add(70);
             }
-            line_scratch[dest] = Some((ch, (r, g, b)));
+            line_scratch[dest] = Some((source_col, (r, g, b)));
         }
-        for &cell in line_scratch.iter() {
-            match cell {
-                Some((ch, rgb)) => fb.put(ch, rgb),
-                None => fb.put(' ', (0, 0, 0)),
+        // A wide glyph covers the next output column too; one that would run
+        // past the right edge is dropped rather than wrapped.
+        let mut col = 0;
+        while col < tw {
+            match line_scratch[col] {
+                Some((source_col, rgb)) if col + row[source_col].width <= tw => {
+                    fb.put_cell(&row[source_col], rgb);
+                    col += row[source_col].width;
+                }
+                _ => {
+                    fb.put_blank(1);
+                    col += 1;
+                }
             }
         }
         fb.end_line();
@@ -700,28 +1047,29 @@ fn glitch_burst_energy(gb: &GlitchBurst, elapsed: f32) -> f32 {
 }
 
 /// A cell is an "edge" if it is printable and touches a blank (or the text
-/// boundary) on any of its four sides.
-fn build_edge_mask(plain: &[Vec<char>]) -> Vec<Vec<bool>> {
+/// boundary) on any of its four sides. Both columns of a wide glyph count as
+/// printable, so its neighbors are measured from the glyph's real extent.
+fn build_edge_mask(cells: &[Vec<Cell>]) -> Vec<Vec<bool>> {
     let blank = |r: isize, c: isize| -> bool {
         if r < 0 || c < 0 {
             return true;
         }
-        plain
+        cells
             .get(r as usize)
             .and_then(|row| row.get(c as usize))
-            .is_none_or(|&ch| ch == ' ')
+            .is_none_or(Cell::is_blank)
     };
-    plain
+    cells
         .iter()
         .enumerate()
         .map(|(ri, row)| {
             row.iter()
                 .enumerate()
-                .map(|(ci, &ch)| {
+                .map(|(ci, cell)| {
                     let (r, c) = (ri as isize, ci as isize);
-                    ch != ' '
+                    !cell.is_blank()
                         && (blank(r, c - 1)
-                            || blank(r, c + 1)
+                            || blank(r, c + cell.width.max(1) as isize)
                             || blank(r - 1, c)
                             || blank(r + 1, c))
                 })
@@ -868,18 +1216,8 @@ fn current_utc_yyyymmdd() -> u32 {
 }
 
 fn yyyymmdd_from_unix_days(days: i64) -> u32 {
-    let z = days + 719_468;
-    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
-    let doe = z - era * 146_097;
-    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
-    let y = yoe + era * 400;
-    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
-    let mp = (5 * doy + 2) / 153;
-    let day = doy - (153 * mp + 2) / 5 + 1;
-    let month = mp + if mp < 10 { 3 } else { -9 };
-    let year = y + if month <= 2 { 1 } else { 0 };
-
-    (year * 10_000 + month * 100 + day) as u32
+    let (year, month, day) = civil_from_unix_days(days);
+    (year * 10_000 + month as i64 * 100 + day as i64) as u32
 }
 
 fn parse_color_fps_argument(args: &[String], config: &Config) -> f32 {
@@ -914,6 +1252,7 @@ fn parse_field_selection_argument(
     args: &[String],
     show_packages: bool,
     show_header: bool,
+    field_key: &dyn Fn(&str) -> Option<&'static str>,
 ) -> Result<InfoFieldSelection, String> {
     let mut show_values = Vec::new();
     let mut hide_values = Vec::new();
@@ -966,12 +1305,14 @@ fn parse_field_selection_argument(
         return Ok(InfoFieldSelection::Show(parse_field_key_list(
             &show_values,
             "--show",
+            field_key,
         )));
     }
     if !hide_values.is_empty() {
         return Ok(InfoFieldSelection::Hide(parse_field_key_list(
             &hide_values,
             "--hide",
+            field_key,
         )));
     }
     Ok(InfoFieldSelection::All)
@@ -987,7 +1328,11 @@ fn parse_required_value(args: &[String], index: usize, flag: &str) -> Result<Str
     Ok(value.clone())
 }
 
-fn parse_field_key_list(values: &[String], flag: &str) -> Vec<&'static str> {
+fn parse_field_key_list(
+    values: &[String],
+    flag: &str,
+    field_key: &dyn Fn(&str) -> Option<&'static str>,
+) -> Vec<&'static str> {
     let mut keys = Vec::new();
     for value in values {
         for raw_key in value.split(',') {
@@ -996,7 +1341,7 @@ fn parse_field_key_list(values: &[String], flag: &str) -> Vec<&'static str> {
                 continue;
             }
             let normalized = trimmed.to_ascii_lowercase();
-            if let Some(key) = info_field_key(&normalized) {
+            if let Some(key) = field_key(&normalized) {
                 if !keys.contains(&key) {
                     keys.push(key);
                 }
@@ -1011,18 +1356,142 @@ fn parse_field_key_list(values: &[String], flag: &str) -> Vec<&'static str> {
     keys
 }
 
+fn parse_format_argument(args: &[String]) -> Result<Option<OutputFormat>, String> {
+    match parse_flag_value(args, "--format") {
+        None => Ok(None),
+        Some(value) => OutputFormat::parse(&value).map(Some).ok_or_else(|| {
+            format!(
+                "unknown output format '{}'; expected one of: {}",
+                value,
+                OutputFormat::NAMES.join(", ")
+            )
+        }),
+    }
+}
+
 fn parse_fetch_argument(args: &[String]) -> bool {
     args.iter().any(|a| a == "--fetch")
 }
 
+fn parse_inline_argument(args: &[String]) -> bool {
+    args.iter().any(|a| a == "--inline")
+}
+
+fn parse_stats_argument(args: &[String]) -> bool {
+    args.iter().any(|a| a == "--stats")
+}
+
 fn parse_json_argument(args: &[String]) -> bool {
-    args.iter().any(|a| a == "--json")
+    args.iter().any(|a| a == "--json") || parse_json_pretty_argument(args)
+}
+
+fn parse_json_pretty_argument(args: &[String]) -> bool {
+    args.iter().any(|a| a == "--json-pretty")
 }
 
 fn parse_no_color_argument(args: &[String], config: &Config) -> bool {
     args.iter().any(|a| a == "--no-color" || a == "-C") || config.no_color.unwrap_or(false)
 }
 
+/// `--color-mode` beats the config key; `auto` detects the terminal's depth.
+fn parse_color_mode_argument(args: &[String], config: &Config) -> ColorMode {
+    if let Some(value) = parse_flag_value(args, "--color-mode") {
+        match ColorMode::parse(&value) {
+            Some(mode) => return mode,
+            None => eprintln!(
+                "warning: unknown color mode '{}'; expected auto, truecolor, 256, 16 or none",
+                value
+            ),
+        }
+    }
+    config.color_mode.unwrap_or_default()
+}
+
+fn parse_dither_argument(args: &[String], config: &Config) -> bool {
+    args.iter().any(|a| a == "--dither") || config.dither.unwrap_or(false)
+}
+
+fn parse_alt_screen_argument(args: &[String], config: &Config) -> bool {
+    args.iter().any(|a| a == "--alt-screen") || config.alt_screen.unwrap_or(false)
+}
+
+fn parse_logo_colors_argument(args: &[String], config: &Config) -> Option<LogoColors> {
+    if let Some(value) = parse_flag_value(args, "--logo-colors") {
+        match LogoColors::parse(&value) {
+            Some(mode) => return Some(mode),
+            None => eprintln!(
+                "warning: unknown logo colors '{}'; expected replace, keep, blend or modulate",
+                value
+            ),
+        }
+    }
+    config.logo_colors
+}
+
+fn parse_image_protocol_argument(args: &[String], config: &Config) -> ImageProtocol {
+    if let Some(value) = parse_flag_value(args, "--image-protocol") {
+        match ImageProtocol::parse(&value) {
+            Some(protocol) => return protocol,
+            None => eprintln!(
+                "warning: unknown image protocol '{}'; expected auto, halfblocks, sixel or kitty",
+                value
+            ),
+        }
+    }
+    config.image_protocol.unwrap_or_default()
+}
+
+fn parse_layout_argument(args: &[String], config: &Config) -> Layout {
+    let mut layout = Layout {
+        logo_position: config.logo_position.unwrap_or_default(),
+        gap: config.layout_gap.unwrap_or(0),
+        padding: config.layout_padding.unwrap_or(0),
+        center: args.iter().any(|a| a == "--layout-center")
+            || config.layout_center.unwrap_or(false),
+        min_info_width: config.min_info_width.unwrap_or(DEFAULT_MIN_INFO_WIDTH),
+        terminal: None,
+    };
+    if let Some(value) = parse_flag_value(args, "--logo-position") {
+        match LogoPosition::parse(&value) {
+            Some(position) => layout.logo_position = position,
+            None => eprintln!(
+                "warning: unknown logo position '{}'; expected left, right, top or bottom",
+                value
+            ),
+        }
+    }
+    if let Some(value) = parse_flag_value(args, "--min-info-width") {
+        match value.parse::<usize>() {
+            Ok(width) => layout.min_info_width = width,
+            Err(_) => eprintln!(
+                "warning: invalid min info width '{}'; expected a column count",
+                value
+            ),
+        }
+    }
+    for (flag, spacing) in [
+        ("--layout-gap", &mut layout.gap),
+        ("--layout-padding", &mut layout.padding),
+    ] {
+        if let Some(value) = parse_flag_value(args, flag) {
+            match value.parse::<usize>() {
+                Ok(value) if value <= MAX_LAYOUT_SPACING => *spacing = value,
+                _ => eprintln!(
+                    "warning: invalid {} '{}'; expected 0-{}",
+                    &flag[2..],
+                    value,
+                    MAX_LAYOUT_SPACING
+                ),
+            }
+        }
+    }
+    layout
+}
+
+fn parse_fill_background_argument(args: &[String], config: &Config) -> bool {
+    args.iter().any(|a| a == "--fill-background") || config.fill_background.unwrap_or(false)
+}
+
 fn parse_mono_argument(args: &[String], config: &Config) -> bool {
     args.iter().any(|a| a == "--mono") || config.mono.unwrap_or(false)
 }
@@ -1068,6 +1537,10 @@ fn parse_no_header_argument(args: &[String], config: &Config) -> bool {
     args.iter().any(|a| a == "--no-header") || config.no_header.unwrap_or(false)
 }
 
+fn parse_kernel_details_argument(args: &[String], config: &Config) -> bool {
+    args.iter().any(|a| a == "--kernel-details") || config.kernel_details.unwrap_or(false)
+}
+
 fn parse_logo_file_argument(args: &[String]) -> Option<String> {
     for i in 0..args.len() {
         if args[i] == "--logo-file" {
@@ -1100,6 +1573,91 @@ fn parse_distro_argument(args: &[String]) -> Option<String> {
     None
 }
 
+/// Value of `--flag value` or `--flag=value`, last occurrence winning.
+fn parse_flag_value(args: &[String], flag: &str) -> Option<String> {
+    let mut value = None;
+    for i in 0..args.len() {
+        if args[i] == flag {
+            if let Some(next) = args.get(i + 1) {
+                value = Some(next.clone());
+            }
+        } else if let Some(rest) = args[i]
+            .strip_prefix(flag)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            value = Some(rest.to_string());
+        }
+    }
+    value
+}
+
+fn parse_uptime_format_argument(args: &[String], config: &Config) -> UptimeStyle {
+    if let Some(value) = parse_flag_value(args, "--uptime-format") {
+        match UptimeStyle::parse(&value) {
+            Some(style) => return style,
+            None => eprintln!(
+                "warning: unknown uptime format '{}'; expected long or short",
+                value
+            ),
+        }
+    }
+    config.uptime_format.unwrap_or_default()
+}
+
+/// `--lang` beats the config key, which beats `LC_ALL`/`LC_MESSAGES`/`LANG`.
+fn parse_lang_argument(args: &[String], config: &Config) -> Lang {
+    if let Some(value) = parse_flag_value(args, "--lang") {
+        match Lang::parse(&value) {
+            Some(lang) => return lang,
+            None => eprintln!(
+                "warning: unsupported language '{}'; expected en or zh-CN",
+                value
+            ),
+        }
+    }
+    config.lang.unwrap_or_else(Lang::from_env)
+}
+
+/// The numeric locale's decimal mark, unless English was asked for with
+/// `--lang` or the config key: then numbers keep `.` whatever `LANG` says.
+fn parse_decimal_separator(args: &[String], config: &Config, lang: Lang) -> char {
+    let explicit = parse_flag_value(args, "--lang").is_some() || config.lang.is_some();
+    if explicit && lang == Lang::En {
+        '.'
+    } else {
+        decimal_separator_from_env()
+    }
+}
+
+fn parse_byte_units_argument(args: &[String], config: &Config) -> ByteUnits {
+    let mut units = ByteUnits::default();
+    if let Some(system) = config.units {
+        units.system = system;
+    }
+    if let Some(precision) = config.unit_precision {
+        units.precision = precision;
+    }
+    if let Some(value) = parse_flag_value(args, "--units") {
+        match UnitSystem::parse(&value) {
+            Some(system) => units.system = system,
+            None => eprintln!(
+                "warning: unknown units '{}'; expected binary or decimal",
+                value
+            ),
+        }
+    }
+    if let Some(value) = parse_flag_value(args, "--unit-precision") {
+        match value.parse::<usize>() {
+            Ok(precision) if precision <= MAX_UNIT_PRECISION => units.precision = precision,
+            _ => eprintln!(
+                "warning: invalid unit precision '{}'; expected 0-{}",
+                value, MAX_UNIT_PRECISION
+            ),
+        }
+    }
+    units
+}
+
 fn parse_seed_argument(args: &[String], config: &Config) -> Option<u64> {
     for i in 0..args.len() {
         if args[i] == "--seed"
@@ -1128,10 +1686,66 @@ fn print_effective_config(config: &EffectiveConfig) {
     println!("no_header = {}", config.no_header);
     println!("mono = {}", config.mono);
     println!("no_color = {}", config.no_color);
+    println!("color_mode = \"{}\"", config.color_mode.name());
+    println!("dither = {}", config.dither);
+    println!("alt_screen = {}", config.alt_screen);
+    println!("fill_background = {}", config.fill_background);
+    println!("logo_colors = \"{}\"", config.logo_colors.name());
+    println!("image_protocol = \"{}\"", config.image_protocol.name());
+    println!("logo_position = \"{}\"", config.layout.logo_position.name());
+    println!("layout_gap = {}", config.layout.gap);
+    println!("layout_padding = {}", config.layout.padding);
+    println!("layout_center = {}", config.layout.center);
+    println!("min_info_width = {}", config.layout.min_info_width);
     if let Some(seed) = config.seed {
         println!("seed = {}", seed);
     }
     println!("palette = \"{}\"", config.palette.name);
+    println!("kernel_details = {}", config.kernel_details);
+    println!("uptime_format = \"{}\"", config.uptime_style.name());
+    println!("units = \"{}\"", config.byte_units.system.name());
+    println!("unit_precision = {}", config.byte_units.precision);
+    println!("lang = \"{}\"", config.lang.code());
+    if let Some(separator) = &config.field_format.separator {
+        println!("separator = {:?}", separator);
+    }
+    if config.logo_slots.iter().any(Option::is_some) {
+        println!();
+        println!("[logo_slots]");
+        for (n, color) in config.logo_slots.iter().enumerate() {
+            if let Some((r, g, b)) = color {
+                println!("c{} = \"#{:02x}{:02x}{:02x}\"", n + 1, r, g, b);
+            }
+        }
+    }
+    for field in &config.custom_fields {
+        println!();
+        println!("[fields.{}]", field.key);
+        println!("label = {:?}", field.label);
+        match &field.source {
+            CustomFieldSource::Command(command) => println!("command = {:?}", command),
+            CustomFieldSource::File(path) => println!("file = {:?}", path.display().to_string()),
+            CustomFieldSource::Env(name) => println!("env = {:?}", name),
+        }
+        println!("timeout = {}", format_float(field.timeout.as_secs_f32()));
+        if let Some(ttl) = field.cache_ttl {
+            println!("cache_ttl = {}", ttl.as_secs());
+        }
+    }
+    if !config.field_format.labels.is_empty() {
+        println!();
+        println!("[labels]");
+        for (key, label) in &config.field_format.labels {
+            println!("{} = {:?}", key, label);
+        }
+    }
+    if !config.field_format.templates.is_empty() {
+        println!();
+        println!("[templates]");
+        for (key, template) in &config.field_format.templates {
+            println!("{} = {:?}", key, template.to_string());
+        }
+    }
 }
 
 fn animation_style_name(style: &AnimationStyle) -> &'static str {
@@ -1172,33 +1786,272 @@ fn format_fps(value: f32) -> String {
     }
 }
 
-fn load_logo_file(path: &str) -> io::Result<Vec<String>> {
+/// Cells an image logo may cover; about the size of the built-in logos.
+const IMAGE_LOGO_COLUMNS: usize = 32;
+const IMAGE_LOGO_ROWS: usize = 16;
+/// Cell size to assume when the terminal doesn't report its pixel size.
+const FALLBACK_CELL_PIXELS: (usize, usize) = (10, 20);
+
+struct LogoImage {
+    /// Half-block art, or blanks holding the place of `overlay`.
+    lines: Vec<String>,
+    /// Sixel or kitty escapes that draw the image over those blanks.
+    overlay: Option<String>,
+}
+
+fn load_logo_image(path: &str, protocol: ImageProtocol, colors: bool) -> Result<LogoImage, String> {
+    let data = fs::read(path).map_err(|err| err.to_string())?;
+    let image = util::png::decode(&data)?;
+    let (width, height) = graphics::half_block_size(
+        image.width,
+        image.height,
+        IMAGE_LOGO_COLUMNS,
+        IMAGE_LOGO_ROWS,
+    );
+    let (cols, rows) = (width, height.div_ceil(2));
+    let placeholder = vec![" ".repeat(cols); rows];
+    Ok(match protocol {
+        ImageProtocol::Kitty => LogoImage {
+            lines: placeholder,
+            overlay: Some(graphics::kitty(&data, cols, rows)),
+        },
+        ImageProtocol::Sixel => {
+            let (cell_width, cell_height) = cell_pixel_size();
+            let (width, height) = graphics::fit(
+                image.width,
+                image.height,
+                cols * cell_width,
+                rows * cell_height,
+            );
+            LogoImage {
+                lines: placeholder,
+                overlay: Some(graphics::sixel(&graphics::resize(&image, width, height))),
+            }
+        }
+        ImageProtocol::Auto | ImageProtocol::HalfBlocks => LogoImage {
+            lines: graphics::half_blocks(&graphics::resize(&image, width, height), colors),
+            overlay: None,
+        },
+    })
+}
+
+/// Pick kitty, then Sixel, by asking the terminal; half-blocks work anywhere.
+fn resolve_image_protocol(protocol: ImageProtocol) -> ImageProtocol {
+    if protocol != ImageProtocol::Auto {
+        return protocol;
+    }
+    if !(stdout().is_terminal() && io::stdin().is_terminal()) || enable_raw_mode().is_err() {
+        return ImageProtocol::HalfBlocks;
+    }
+    let found = term::query_graphics(Duration::from_millis(150));
+    let _ = disable_raw_mode();
+    if found.kitty {
+        ImageProtocol::Kitty
+    } else if found.sixel {
+        ImageProtocol::Sixel
+    } else {
+        ImageProtocol::HalfBlocks
+    }
+}
+
+fn cell_pixel_size() -> (usize, usize) {
+    match crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => (
+            (size.width / size.columns) as usize,
+            (size.height / size.rows) as usize,
+        ),
+        _ => FALLBACK_CELL_PIXELS,
+    }
+}
+
+/// Draw a Sixel or kitty logo over the blank logo cells of the `lines`
+/// just printed above the cursor, then return the cursor below them.
+fn write_logo_overlay(
+    out: &mut impl Write,
+    (overlay, (row, col)): &(String, (usize, usize)),
+    lines: usize,
+) -> io::Result<()> {
+    if lines <= *row {
+        return Ok(());
+    }
+    write!(out, "\x1b7\x1b[{}A\r", lines - row)?;
+    if *col > 0 {
+        write!(out, "\x1b[{}G", col + 1)?;
+    }
+    write!(out, "{overlay}\x1b8")?;
+    out.flush()
+}
+
+/// Columns and rows of the terminal stdout is on, for the layout to fit.
+fn terminal_size() -> Option<(usize, usize)> {
+    if !stdout().is_terminal() {
+        return None;
+    }
+    size()
+        .ok()
+        .filter(|&(cols, rows)| cols > 0 && rows > 0)
+        .map(|(cols, rows)| (cols as usize, rows as usize))
+}
+
+/// Colors for the `${c1}`..`${c6}` logo markers: config overrides first,
+/// then the active palette's stops.
+fn logo_slot_colors(config: &EffectiveConfig) -> [(u8, u8, u8); LOGO_SLOTS] {
+    std::array::from_fn(|n| config.logo_slots[n].unwrap_or_else(|| config.palette.slot(n)))
+}
+
+struct LogoFile {
+    lines: Vec<String>,
+    /// Slot per column of each line, from the `${cN}` markers.
+    slots: Vec<Vec<Option<usize>>>,
+}
+
+fn load_logo_file(path: &str, slots: Option<&[(u8, u8, u8); LOGO_SLOTS]>) -> io::Result<LogoFile> {
     let text = fs::read_to_string(path)?;
-    Ok(sanitize_logo_text(&text))
+    Ok(LogoFile {
+        lines: sanitize_logo_text(&text, slots),
+        slots: logo_marker_slots(&text),
+    })
 }
 
-fn sanitize_logo_text(text: &str) -> Vec<String> {
+/// Clip a logo file to the supported size and drop escape sequences. With
+/// `slots` (colors are kept), SGR sequences survive, neofetch's `${cN}`
+/// markers become the slot colors, and each line is made self-contained: it
+/// restates the colors carried over from earlier lines and ends in a reset.
+/// Without, markers are dropped along with the escapes.
+fn sanitize_logo_text(text: &str, slots: Option<&[(u8, u8, u8); LOGO_SLOTS]>) -> Vec<String> {
+    let mut carry = slots.map(|_| SgrState::default());
     let lines: Vec<String> = text
         .lines()
         .take(MAX_LOGO_LINES)
-        .map(sanitize_logo_line)
+        .map(|line| sanitize_logo_line(&expand_logo_markers(line, slots), carry.as_mut()))
         .collect();
 
-    if lines.iter().all(|line| line.trim().is_empty()) {
+    if lines
+        .iter()
+        .all(|line| layout_line(line).iter().all(Cell::is_blank))
+    {
         Vec::new()
     } else {
         lines
     }
 }
 
-fn sanitize_logo_line(line: &str) -> String {
+/// Replace `${c1}`..`${c6}` with a truecolor SGR for that slot, or with
+/// nothing when `slots` is `None`. Anything else containing `${` is kept.
+fn expand_logo_markers<'a>(
+    line: &'a str,
+    slots: Option<&[(u8, u8, u8); LOGO_SLOTS]>,
+) -> std::borrow::Cow<'a, str> {
+    if !line.contains("${c") {
+        return line.into();
+    }
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(at) = rest.find("${c") {
+        out.push_str(&rest[..at]);
+        let marker = &rest[at..];
+        match logo_marker_slot(marker) {
+            Some(n) => {
+                if let Some((r, g, b)) = slots.map(|slots| slots[n]) {
+                    out.push_str(&format!("\x1b[38;2;{r};{g};{b}m"));
+                }
+                rest = &marker[5..];
+            }
+            None => {
+                out.push_str("${c");
+                rest = &marker[3..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out.into()
+}
+
+/// The 0-based slot of the `${cN}` marker `marker` starts with.
+fn logo_marker_slot(marker: &str) -> Option<usize> {
+    marker
+        .as_bytes()
+        .get(3)
+        .filter(|digit| (b'1'..=b'0' + LOGO_SLOTS as u8).contains(digit))
+        .filter(|_| marker.as_bytes().get(4) == Some(&b'}'))
+        .map(|digit| (digit - b'1') as usize)
+}
+
+/// The slot each column of each logo line is in. As in neofetch, a marker
+/// holds until the next one, across lines.
+fn logo_marker_slots(text: &str) -> Vec<Vec<Option<usize>>> {
+    let mut slot = None;
+    text.lines()
+        .take(MAX_LOGO_LINES)
+        .map(|line| {
+            let mut columns = Vec::new();
+            let mut rest = line;
+            loop {
+                let at = rest.find("${c").unwrap_or(rest.len());
+                let art = layout_line(&sanitize_logo_line(&rest[..at], None));
+                columns.extend(art.iter().map(|_| slot));
+                let marker = &rest[at..];
+                if marker.is_empty() {
+                    break;
+                }
+                match logo_marker_slot(marker) {
+                    Some(n) => {
+                        slot = Some(n);
+                        rest = &marker[5..];
+                    }
+                    None => {
+                        columns.extend([slot; 3]);
+                        rest = &marker[3..];
+                    }
+                }
+            }
+            columns
+        })
+        .collect()
+}
+
+/// Put the cells of a logo file's art in the slots its markers chose.
+fn mark_logo_slots(
+    cells: &mut [Vec<Cell>],
+    slots: Option<&LogoSlots>,
+    origin: Option<(usize, usize)>,
+) {
+    let (Some(slots), Some((top, left))) = (slots, origin) else {
+        return;
+    };
+    for (row, columns) in cells.iter_mut().skip(top).zip(&slots.columns) {
+        for (cell, &slot) in row.iter_mut().skip(left).zip(columns) {
+            cell.slot = slot;
+        }
+    }
+}
+
+fn sanitize_logo_line(line: &str, mut carry: Option<&mut SgrState>) -> String {
     let line = line.strip_suffix('\r').unwrap_or(line);
     let expanded = line.replace('\t', &" ".repeat(TAB_WIDTH));
-    parse_ansi_text(&expanded)
-        .into_iter()
-        .filter_map(|(ansi, ch)| (ansi.is_empty() && ch != '\0').then_some(ch))
-        .take(MAX_LOGO_COLUMNS)
-        .collect()
+    let mut out = carry.as_deref().map(SgrState::sequence).unwrap_or_default();
+    let mut columns = 0usize;
+    for (ansi, ch) in parse_ansi_text(&expanded) {
+        if let Some(carry) = carry.as_deref_mut()
+            && ansi.starts_with("\x1b[")
+            && ansi.ends_with('m')
+        {
+            carry.apply(&ansi);
+            out.push_str(&ansi);
+        }
+        if !ansi.is_empty() || ch == '\0' {
+            continue;
+        }
+        columns += char_width(ch);
+        if columns > MAX_LOGO_COLUMNS {
+            break;
+        }
+        out.push(ch);
+    }
+    if carry.is_some_and(|carry| *carry != SgrState::default()) {
+        out.push_str("\x1b[0m");
+    }
+    out
 }
 
 fn print_help() {
@@ -1209,7 +2062,7 @@ fn print_help() {
     let distros = system::supported_distro_ids().join(", ");
     let palettes = animation::available_palette_names().join(", ");
     println!(
-        "neonfetch - fast colorful animated system info\n\nUsage:\n  neonfetch [options]\n\nOptions:\n  --style <name>        Animation style (default: neon; real style, random, or daily)\n  --palette <name>      Color palette (default: default)\n  --speed <val>         Animation speed (0.1-20.0, default 1.0)\n  --color-fps <val>     Color refresh FPS (5-120, default 30)\n  --duration <sec>      Auto-exit after N seconds (animation mode)\n  --frame               Render one frame and exit (animation mode)\n  --fetch               Print info once and exit\n  --json                Print keyed JSON object and exit\n  --show <keys>         Show only comma-separated info fields in that order\n  --hide <keys>         Hide comma-separated info fields\n  --list-fields         List available info field keys\n  --mono                Render in grayscale (animations/info)\n  --no-color, -C        Disable ANSI colors (plain text)\n  --logo-file <path>    Use a UTF-8 text file as the ASCII logo\n  --no-logo, -L         Hide ASCII logo\n  --distro <id>         Force a distro logo on any platform\n  --no-packages, -P     Hide packages field and skip package detection\n  --no-header           Hide username@hostname header divider\n  --seed <u64>          Deterministic random seed for animations and --style random\n  --config <path>       Load config from path\n  --no-config           Ignore config files\n  --print-config        Print effective config and exit\n  --list-styles         List available styles\n  --list-palettes       List available palettes\n  -h, --help            Show this help\n  -V, --version         Show version\n\nConfig search:\n  --config, NEONFETCH_CONFIG, XDG_CONFIG_HOME, ~/.config/neonfetch/config.toml\n\nInfo fields:\n  {}\n\nKeys (animation mode):\n  q / Esc / Ctrl+C      Quit and restore the terminal\n\nDistros:\n  {}\n\nStyles:\n  {}\n\nPalettes:\n  {}\n\nPseudo-styles:\n  random                Pick a random showcase style each run; honors --seed\n  daily                 Pick one showcase style from the local date",
+        "neonfetch - fast colorful animated system info\n\nUsage:\n  neonfetch [options]\n\nOptions:\n  --style <name>        Animation style (default: neon; real style, random, or daily)\n  --palette <name>      Color palette (default: default)\n  --speed <val>         Animation speed (0.1-20.0, default 1.0)\n  --color-fps <val>     Color refresh FPS (5-120, default 30)\n  --duration <sec>      Auto-exit after N seconds (animation mode)\n  --frame               Render one frame and exit (animation mode)\n  --fetch               Print info once and exit\n  --json                Print versioned JSON document and exit\n  --json-pretty         Same as --json, indented\n  --print-json-schema   Print the JSON Schema for --json and exit\n  --format <name>       Print json, yaml, toml, env, markdown, csv or prometheus and exit\n  --from-json <file|->  Render fields from a saved --json snapshot instead of probing\n  --show <keys>         Show only comma-separated info fields in that order\n  --hide <keys>         Hide comma-separated info fields\n  --list-fields         List available info field keys\n  --kernel-details      Add kernel build, taint and module lines\n  --uptime-format <f>   Uptime as long (3 days, 4 hours, 5 mins) or short (3d 4h 5m)\n  --units <system>      Byte sizes in binary (GiB) or decimal (GB) units\n  --unit-precision <n>  Decimal places for byte sizes (0-6, default 2)\n  --lang <code>         Label language: en or zh-CN (default: from LANG)\n  --mono                Render in grayscale (animations/info)\n  --no-color, -C        Disable ANSI colors (plain text)\n  --color-mode <mode>   Color depth: auto, truecolor, 256, 16 or none (default: auto)\n  --dither              Ordered-dither gradients in 256/16-color modes\n  --alt-screen          Animate on the alternate screen, keeping scrollback intact\n  --fill-background     Paint fire, lava and plasma behind the text too\n  --inline              Animate below the prompt and leave the info in scrollback\n  --stats               Print bytes written per frame on exit (animation mode)\n  --logo-file <path>    Use a UTF-8 text file as the ASCII logo\n  --logo-image <png>    Use a PNG image as the logo (half-blocks, Sixel or kitty)\n  --image-protocol <p>  Image logos: auto, halfblocks, sixel or kitty (default: auto)\n  --logo-colors <mode>  Logo file colors: replace, keep, blend or modulate (default: replace);\n                        neofetch ${{c1}}..${{c6}} markers take palette colors\n  --logo-position <p>   Logo left, right, top or bottom of the info (default: left)\n  --layout-gap <n>      Extra columns (rows when stacked) between logo and info\n  --layout-padding <n>  Blank rows above and columns left of the output\n  --layout-center       Center the shorter of logo and info vertically\n  --min-info-width <n>  Info columns kept beside the logo before it shrinks, moves on\n                        top or hides in narrow terminals (default: 40)\n  --no-logo, -L         Hide ASCII logo\n  --distro <id>         Force a distro logo on any platform\n  --no-packages, -P     Hide packages field and skip package detection\n  --no-header           Hide username@hostname header divider\n  --seed <u64>          Deterministic random seed for animations and --style random\n  --config <path>       Load config from path\n  --no-config           Ignore config files\n  --print-config        Print effective config and exit\n  --list-styles         List available styles\n  --list-palettes       List available palettes\n  -h, --help            Show this help\n  -V, --version         Show version\n\nConfig search:\n  --config, NEONFETCH_CONFIG, XDG_CONFIG_HOME, ~/.config/neonfetch/config.toml\n\nInfo fields:\n  {}\n\nKeys (animation mode):\n  q / Esc / Ctrl+C      Quit and restore the terminal\n\nDistros:\n  {}\n\nStyles:\n  {}\n\nPalettes:\n  {}\n\nPseudo-styles:\n  random                Pick a random showcase style each run; honors --seed\n  daily                 Pick one showcase style from the local date",
         INFO_FIELD_KEYS.join(", "),
         distros,
         styles,
@@ -1232,6 +2085,7 @@ fn print_version() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::util::ansi::SgrColor;
 
     #[test]
     fn daily_style_is_deterministic_for_date() {
@@ -1281,6 +2135,56 @@ mod tests {
         assert!(seen.len() > 1);
     }
 
+    #[test]
+    fn edge_mask_measures_wide_glyphs_by_columns() {
+        let rows = ["xxxxx", "x内xx", "xxxx "].map(String::from);
+        let mask = build_edge_mask(&layout_lines(&rows));
+        // Surrounded on all sides once the glyph spans columns 1-2.
+        assert!(!mask[1][1]);
+        assert!(!mask[1][3]);
+        assert!(mask[1][4]);
+        assert!(mask[2][3]);
+    }
+
+    #[test]
+    fn logo_lines_are_clipped_by_display_width() {
+        let line = "内".repeat(MAX_LOGO_COLUMNS);
+        let clipped = sanitize_logo_line(&line, None);
+        assert_eq!(clipped.chars().count(), MAX_LOGO_COLUMNS / 2);
+    }
+
+    #[test]
+    fn neofetch_color_markers_map_to_slots() {
+        let mut slots = [(0, 0, 0); LOGO_SLOTS];
+        slots[0] = (255, 0, 0);
+        slots[1] = (0, 0, 255);
+        let art = "${c1}/\\${c2}##\n  ${c7}${c1}\\/";
+
+        let lines = sanitize_logo_text(art, Some(&slots));
+        assert_eq!(
+            lines,
+            [
+                "\x1b[38;2;255;0;0m/\\\x1b[38;2;0;0;255m##\x1b[0m",
+                "\x1b[38;2;0;0;255m  ${c7}\x1b[38;2;255;0;0m\\/\x1b[0m",
+            ]
+        );
+        let cells = layout_line(&lines[0]);
+        assert_eq!(cells[0].base, Some(SgrColor::Rgb(255, 0, 0)));
+        assert_eq!(cells[2].base, Some(SgrColor::Rgb(0, 0, 255)));
+
+        // Replace mode drops the markers and keeps the art aligned.
+        assert_eq!(sanitize_logo_text(art, None), ["/\\##", "  ${c7}\\/"]);
+        // ...but each column still knows its slot.
+        let (red, blue) = (Some(0), Some(1));
+        assert_eq!(
+            logo_marker_slots(art),
+            [
+                vec![red, red, blue, blue],
+                vec![blue; 7].into_iter().chain([red, red]).collect()
+            ]
+        );
+    }
+
     #[test]
     fn unix_days_to_yyyymmdd_handles_known_dates() {
         assert_eq!(yyyymmdd_from_unix_days(0), 19_700_101);
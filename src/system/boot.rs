@@ -0,0 +1,238 @@
+use crate::util::i18n::Lang;
+use crate::util::template::TemplateValue;
+use std::fs;
+use std::path::Path;
+
+const EFI_GLOBAL_GUID: &str = "8be4df61-93ca-11d2-aa0d-00e098032b8c";
+const SYSTEMD_LOADER_GUID: &str = "4a67b082-0a4c-41cf-b6c7-440b29bb8c4f";
+const REFIND_GUID: &str = "36d08fa7-cf0b-42f5-8f14-68df73ed3740";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BootInfo {
+    pub boot_time: Option<u64>,
+    pub uefi: bool,
+    pub secure_boot: Option<bool>,
+    pub bootloader: Option<String>,
+}
+
+pub fn detect_boot() -> Option<BootInfo> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    Some(read_boot_info(Path::new("/")))
+}
+
+/// Collect boot details from a filesystem root, so fixtures can stand in for
+/// `/proc`, `/sys/firmware/efi` and `/boot`.
+pub fn read_boot_info(root: &Path) -> BootInfo {
+    let boot_time = fs::read_to_string(root.join("proc/stat"))
+        .ok()
+        .and_then(|stat| parse_btime(&stat));
+    let efi = root.join("sys/firmware/efi");
+    let uefi = efi.is_dir();
+    let efivars = efi.join("efivars");
+    let secure_boot = uefi
+        .then(|| read_efivar(&efivars, "SecureBoot", EFI_GLOBAL_GUID))
+        .flatten()
+        .and_then(|data| data.first().map(|&value| value == 1));
+    let bootloader = uefi
+        .then(|| detect_bootloader_from_efivars(&efivars))
+        .flatten()
+        .or_else(|| detect_bootloader_from_files(root));
+    BootInfo {
+        boot_time,
+        uefi,
+        secure_boot,
+        bootloader,
+    }
+}
+
+fn parse_btime(stat: &str) -> Option<u64> {
+    stat.lines()
+        .find_map(|line| line.strip_prefix("btime "))
+        .and_then(|value| value.trim().parse::<u64>().ok())
+}
+
+/// efivarfs files start with a 4-byte attribute mask followed by the payload.
+fn read_efivar(efivars: &Path, name: &str, guid: &str) -> Option<Vec<u8>> {
+    let data = fs::read(efivars.join(format!("{name}-{guid}"))).ok()?;
+    (data.len() > 4).then(|| data[4..].to_vec())
+}
+
+fn detect_bootloader_from_efivars(efivars: &Path) -> Option<String> {
+    // systemd-boot and recent GRUB builds both publish LoaderInfo.
+    if let Some(data) = read_efivar(efivars, "LoaderInfo", SYSTEMD_LOADER_GUID)
+        && let Some(name) = decode_utf16_efivar(&data)
+    {
+        return Some(name);
+    }
+    read_efivar(efivars, "PreviousBoot", REFIND_GUID).map(|_| "rEFInd".to_string())
+}
+
+fn decode_utf16_efivar(data: &[u8]) -> Option<String> {
+    let units: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|&unit| unit != 0)
+        .collect();
+    let text = String::from_utf16_lossy(&units).trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+fn detect_bootloader_from_files(root: &Path) -> Option<String> {
+    let candidates: &[(&str, &str)] = &[
+        ("boot/loader/loader.conf", "systemd-boot"),
+        ("efi/loader/loader.conf", "systemd-boot"),
+        ("boot/efi/loader/loader.conf", "systemd-boot"),
+        ("boot/EFI/refind", "rEFInd"),
+        ("boot/efi/EFI/refind", "rEFInd"),
+        ("efi/EFI/refind", "rEFInd"),
+        ("boot/grub/grub.cfg", "GRUB"),
+        ("boot/grub2/grub.cfg", "GRUB"),
+    ];
+    candidates
+        .iter()
+        .find(|(path, _)| root.join(path).exists())
+        .map(|(_, name)| name.to_string())
+}
+
+/// The `Boot` value; `boot_time` is `info.boot_time` formatted for display.
+pub fn format_boot_value(info: &BootInfo, boot_time: Option<&str>, lang: Lang) -> String {
+    let mut details = vec![if info.uefi { "UEFI" } else { "BIOS" }.to_string()];
+    match info.secure_boot {
+        Some(true) => details.push(lang.tr("Secure Boot on").to_string()),
+        Some(false) => details.push(lang.tr("Secure Boot off").to_string()),
+        None => {}
+    }
+    if let Some(loader) = &info.bootloader {
+        details.push(loader.clone());
+    }
+    format!(
+        "{} ({})",
+        boot_time.unwrap_or(lang.tr("Unknown")),
+        details.join(", ")
+    )
+}
+
+/// Template placeholders for the `boot` field.
+pub fn boot_values(info: &BootInfo, boot_time: Option<&str>) -> Vec<(&'static str, TemplateValue)> {
+    let mut values = vec![("firmware", if info.uefi { "UEFI" } else { "BIOS" }.into())];
+    if let (Some(secs), Some(time)) = (info.boot_time, boot_time) {
+        values.push(("time", time.into()));
+        values.push(("timestamp", secs.into()));
+    }
+    if let Some(on) = info.secure_boot {
+        values.push(("secure_boot", if on { "on" } else { "off" }.into()));
+    }
+    if let Some(loader) = &info.bootloader {
+        values.push(("bootloader", loader.as_str().into()));
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::fixture::{temp_path, write};
+
+    fn efivar(payload: &[u8]) -> Vec<u8> {
+        let mut data = vec![0x06, 0x00, 0x00, 0x00];
+        data.extend_from_slice(payload);
+        data
+    }
+
+    fn utf16(text: &str) -> Vec<u8> {
+        text.encode_utf16()
+            .chain([0])
+            .flat_map(u16::to_le_bytes)
+            .collect()
+    }
+
+    #[test]
+    fn reads_uefi_secure_boot_and_loader_info() {
+        let root = temp_path("boot-uefi");
+        write(
+            &root,
+            "proc/stat",
+            b"cpu  1 2 3\nbtime 1728047580\nprocesses 9\n",
+        );
+        write(
+            &root,
+            &format!("sys/firmware/efi/efivars/SecureBoot-{EFI_GLOBAL_GUID}"),
+            efivar(&[1]),
+        );
+        write(
+            &root,
+            &format!("sys/firmware/efi/efivars/LoaderInfo-{SYSTEMD_LOADER_GUID}"),
+            efivar(&utf16("systemd-boot 255.4")),
+        );
+        write(&root, "boot/grub/grub.cfg", b"");
+
+        let info = read_boot_info(&root);
+        assert_eq!(
+            info,
+            BootInfo {
+                boot_time: Some(1_728_047_580),
+                uefi: true,
+                secure_boot: Some(true),
+                bootloader: Some("systemd-boot 255.4".to_string()),
+            }
+        );
+        assert_eq!(
+            format_boot_value(&info, Some("2024-10-04 13:13 UTC"), Lang::En),
+            "2024-10-04 13:13 UTC (UEFI, Secure Boot on, systemd-boot 255.4)"
+        );
+        let values = boot_values(&info, Some("2024-10-04 13:13 UTC"));
+        let names: Vec<&str> = values.iter().map(|(name, _)| *name).collect();
+        assert_eq!(
+            names,
+            ["firmware", "time", "timestamp", "secure_boot", "bootloader"]
+        );
+        assert_eq!(values[2].1, TemplateValue::Integer(1_728_047_580));
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn legacy_bios_falls_back_to_boot_directory() {
+        let root = temp_path("boot-bios");
+        write(&root, "proc/stat", b"btime 0\n");
+        write(&root, "boot/grub2/grub.cfg", b"");
+
+        let info = read_boot_info(&root);
+        assert!(!info.uefi);
+        assert_eq!(info.secure_boot, None);
+        assert_eq!(info.bootloader.as_deref(), Some("GRUB"));
+        assert_eq!(
+            format_boot_value(&info, None, Lang::En),
+            "Unknown (BIOS, GRUB)"
+        );
+        assert_eq!(
+            format_boot_value(&info, None, Lang::ZhCn),
+            "未知 (BIOS, GRUB)"
+        );
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn detects_refind_and_disabled_secure_boot() {
+        let root = temp_path("boot-refind");
+        write(
+            &root,
+            &format!("sys/firmware/efi/efivars/SecureBoot-{EFI_GLOBAL_GUID}"),
+            efivar(&[0]),
+        );
+        write(
+            &root,
+            &format!("sys/firmware/efi/efivars/PreviousBoot-{REFIND_GUID}"),
+            efivar(&utf16("Boot Linux")),
+        );
+
+        let info = read_boot_info(&root);
+        assert_eq!(info.secure_boot, Some(false));
+        assert_eq!(info.bootloader.as_deref(), Some("rEFInd"));
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,304 @@
+use crate::util::i18n::Lang;
+use crate::util::template::TemplateValue;
+#[cfg(unix)]
+use std::env;
+#[cfg(unix)]
+use std::path::PathBuf;
+#[cfg(unix)]
+use std::time::{Duration, Instant};
+
+/// Container engines are probed on the startup path, so a stuck or slow
+/// daemon must not hold up the fetch: this bounds all sockets together.
+#[cfg(unix)]
+const API_TIMEOUT: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContainerSummary {
+    pub engine: &'static str,
+    pub running: u64,
+    pub total: u64,
+    pub images: u64,
+}
+
+/// The first engine that answers; `Ok(None)` when no engine socket exists.
+pub fn detect_containers() -> Result<Option<ContainerSummary>, String> {
+    #[cfg(unix)]
+    {
+        let deadline = Instant::now() + API_TIMEOUT;
+        let mut error = None;
+        for (engine, path) in candidate_sockets() {
+            if !path.exists() {
+                continue;
+            }
+            match query_engine(engine, &path, deadline) {
+                Some(summary) => return Ok(Some(summary)),
+                None => {
+                    error.get_or_insert_with(|| {
+                        format!("{} API at {} did not answer", engine, path.display())
+                    });
+                }
+            }
+        }
+        if let Some(error) = error {
+            return Err(error);
+        }
+    }
+    Ok(None)
+}
+
+/// Docker first (honoring a `unix://` `DOCKER_HOST`), then rootless and
+/// rootful Podman.
+#[cfg(unix)]
+fn candidate_sockets() -> Vec<(&'static str, PathBuf)> {
+    let mut sockets = Vec::new();
+    if let Ok(host) = env::var("DOCKER_HOST")
+        && let Some(path) = host.strip_prefix("unix://")
+    {
+        let engine = if path.contains("podman") {
+            "podman"
+        } else {
+            "docker"
+        };
+        sockets.push((engine, PathBuf::from(path)));
+    }
+    sockets.push(("docker", PathBuf::from("/var/run/docker.sock")));
+    if let Some(runtime_dir) = env::var_os("XDG_RUNTIME_DIR")
+        && !runtime_dir.is_empty()
+    {
+        sockets.push((
+            "podman",
+            PathBuf::from(runtime_dir).join("podman/podman.sock"),
+        ));
+    }
+    sockets.push(("podman", PathBuf::from("/run/podman/podman.sock")));
+    sockets
+}
+
+/// Ask the engine's Docker-compatible `/info` endpoint for container and
+/// image counts, giving up at `deadline` however the reply trickles in.
+#[cfg(unix)]
+pub fn query_engine(
+    engine: &'static str,
+    socket: &std::path::Path,
+    deadline: Instant,
+) -> Option<ContainerSummary> {
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixStream;
+
+    let remaining =
+        || Some(deadline.saturating_duration_since(Instant::now())).filter(|left| !left.is_zero());
+    let mut stream = UnixStream::connect(socket).ok()?;
+    stream.set_write_timeout(Some(remaining()?)).ok()?;
+    stream
+        .write_all(b"GET /info HTTP/1.0\r\nHost: localhost\r\nAccept: application/json\r\n\r\n")
+        .ok()?;
+    let mut response = Vec::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        stream.set_read_timeout(Some(remaining()?)).ok()?;
+        match stream.read(&mut buf).ok()? {
+            0 => break,
+            n => response.extend_from_slice(&buf[..n]),
+        }
+    }
+    let body = http_response_body(&response)?;
+    let info: serde_json::Value = serde_json::from_slice(&body).ok()?;
+    Some(ContainerSummary {
+        engine,
+        running: info.get("ContainersRunning")?.as_u64()?,
+        total: info.get("Containers")?.as_u64()?,
+        images: info.get("Images")?.as_u64()?,
+    })
+}
+
+/// Split a raw HTTP/1.x response and return the body of a 200 reply,
+/// de-chunking it when the server used chunked transfer encoding anyway.
+#[cfg(unix)]
+fn http_response_body(response: &[u8]) -> Option<Vec<u8>> {
+    let split = response.windows(4).position(|w| w == b"\r\n\r\n")?;
+    let head = std::str::from_utf8(&response[..split]).ok()?;
+    let body = &response[split + 4..];
+    let mut lines = head.lines();
+    let status = lines.next()?.split_whitespace().nth(1)?;
+    if status != "200" {
+        return None;
+    }
+    let chunked = lines.any(|line| {
+        line.split_once(':').is_some_and(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("transfer-encoding")
+                && value.trim().eq_ignore_ascii_case("chunked")
+        })
+    });
+    if chunked {
+        dechunk(body)
+    } else {
+        Some(body.to_vec())
+    }
+}
+
+#[cfg(unix)]
+fn dechunk(mut body: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    loop {
+        let line_end = body.windows(2).position(|w| w == b"\r\n")?;
+        let size_text = std::str::from_utf8(&body[..line_end]).ok()?;
+        let size_hex = size_text.split(';').next()?.trim();
+        let size = usize::from_str_radix(size_hex, 16).ok()?;
+        body = &body[line_end + 2..];
+        if size == 0 {
+            return Some(out);
+        }
+        out.extend_from_slice(body.get(..size)?);
+        body = body.get(size + 2..)?;
+    }
+}
+
+pub fn format_containers_value(summary: &ContainerSummary, lang: Lang) -> String {
+    format!(
+        "{} {} / {} {}, {} {} ({})",
+        summary.running,
+        lang.tr("running"),
+        summary.total,
+        lang.tr("total"),
+        summary.images,
+        lang.tr("images"),
+        summary.engine
+    )
+}
+
+/// Template placeholders for the `containers` field.
+pub fn containers_values(summary: &ContainerSummary) -> Vec<(&'static str, TemplateValue)> {
+    vec![
+        ("running", summary.running.into()),
+        ("total", summary.total.into()),
+        ("images", summary.images.into()),
+        ("engine", summary.engine.into()),
+    ]
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::util::fixture::temp_path;
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixListener;
+    use std::path::Path;
+    use std::thread;
+
+    /// Serve one canned HTTP response and hand back the request line.
+    fn serve_once(path: &Path, response: &'static [u8]) -> thread::JoinHandle<String> {
+        let listener = UnixListener::bind(path).expect("bind test socket");
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("accept");
+            let mut request = Vec::new();
+            let mut buf = [0u8; 512];
+            while !request.ends_with(b"\r\n\r\n") {
+                let n = stream.read(&mut buf).expect("read request");
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            stream.write_all(response).expect("write response");
+            String::from_utf8_lossy(&request)
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        })
+    }
+
+    #[test]
+    fn queries_info_endpoint_over_unix_socket() {
+        let path = temp_path("containers-docker");
+        let server = serve_once(
+            &path,
+            b"HTTP/1.0 200 OK\r\nContent-Type: application/json\r\n\r\n\
+              {\"Containers\":7,\"ContainersRunning\":3,\"ContainersPaused\":0,\"Images\":12}",
+        );
+
+        let summary = query_engine("docker", &path, Instant::now() + Duration::from_secs(2));
+        assert_eq!(server.join().unwrap(), "GET /info HTTP/1.0");
+        assert_eq!(
+            summary,
+            Some(ContainerSummary {
+                engine: "docker",
+                running: 3,
+                total: 7,
+                images: 12,
+            })
+        );
+        assert_eq!(
+            format_containers_value(&summary.unwrap(), Lang::En),
+            "3 running / 7 total, 12 images (docker)"
+        );
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn handles_chunked_responses() {
+        let path = temp_path("containers-podman");
+        let server = serve_once(
+            &path,
+            b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+              14\r\n{\"Containers\":2,\"Con\r\n\
+              19\r\ntainersRunning\":1,\"Images\r\n\
+              4\r\n\":5}\r\n0\r\n\r\n",
+        );
+
+        let summary = query_engine("podman", &path, Instant::now() + Duration::from_secs(2));
+        server.join().unwrap();
+        assert_eq!(
+            summary,
+            Some(ContainerSummary {
+                engine: "podman",
+                running: 1,
+                total: 2,
+                images: 5,
+            })
+        );
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn error_status_and_missing_socket_yield_none() {
+        let path = temp_path("containers-forbidden");
+        let server = serve_once(&path, b"HTTP/1.0 403 Forbidden\r\n\r\n{}");
+        assert_eq!(
+            query_engine("docker", &path, Instant::now() + Duration::from_secs(2)),
+            None
+        );
+        server.join().unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(
+            query_engine("docker", &path, Instant::now() + Duration::from_secs(2)),
+            None
+        );
+    }
+
+    #[test]
+    fn trickled_reply_stops_at_the_deadline() {
+        let path = temp_path("containers-trickle");
+        let listener = UnixListener::bind(&path).expect("bind test socket");
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("accept");
+            // Every byte beats a per-read timeout; the whole reply does not.
+            for byte in b"HTTP/1.0 200 OK\r\n\r\n{}" {
+                thread::sleep(Duration::from_millis(40));
+                if stream.write_all(&[*byte]).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let started = Instant::now();
+        let deadline = started + Duration::from_millis(200);
+        assert_eq!(query_engine("docker", &path, deadline), None);
+        assert!(started.elapsed() < Duration::from_millis(600));
+        server.join().unwrap();
+        let _ = std::fs::remove_file(path);
+    }
+}
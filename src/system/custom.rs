@@ -0,0 +1,309 @@
+use std::env;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+pub const DEFAULT_CUSTOM_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A user-defined info field from a `[fields.<key>]` config section.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CustomField {
+    pub key: &'static str,
+    pub label: String,
+    pub source: CustomFieldSource,
+    pub timeout: Duration,
+    pub cache_ttl: Option<Duration>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CustomFieldSource {
+    Command(String),
+    File(PathBuf),
+    Env(String),
+}
+
+impl CustomFieldSource {
+    /// Stable description stored with cached values, so editing the source in
+    /// the config invalidates the old cache entry.
+    fn describe(&self) -> String {
+        match self {
+            Self::Command(command) => format!("command:{}", command),
+            Self::File(path) => format!("file:{}", path.display()),
+            Self::Env(name) => format!("env:{}", name),
+        }
+    }
+}
+
+/// Read the field's value (without its label), consulting the cache first
+/// when `cache_ttl` is set. `Ok(None)` (empty output, unset variable) hides
+/// the field quietly; errors say why the source failed.
+pub fn detect_custom_field(field: &CustomField) -> Result<Option<String>, String> {
+    let cache_path = field
+        .cache_ttl
+        .and_then(|_| cache_dir())
+        .map(|dir| dir.join(field.key));
+    match (&cache_path, field.cache_ttl) {
+        (Some(path), Some(ttl)) => {
+            if let Some(value) = read_cached(path, &field.source, ttl) {
+                return Ok(Some(value));
+            }
+            let value = read_source(&field.source, field.timeout)?;
+            if let Some(value) = &value {
+                write_cached(path, &field.source, value);
+            }
+            Ok(value)
+        }
+        _ => read_source(&field.source, field.timeout),
+    }
+}
+
+/// Read the raw value, trimmed; empty output hides the field.
+fn read_source(source: &CustomFieldSource, timeout: Duration) -> Result<Option<String>, String> {
+    let raw = match source {
+        CustomFieldSource::Command(command) => run_command(command, timeout)?,
+        CustomFieldSource::File(path) => fs::read_to_string(path)
+            .map_err(|err| format!("could not read {}: {}", path.display(), err))?,
+        CustomFieldSource::Env(name) => match env::var(name) {
+            Ok(value) => value,
+            Err(_) => return Ok(None),
+        },
+    };
+    let value = raw.trim();
+    Ok((!value.is_empty()).then(|| value.to_string()))
+}
+
+/// Run `command` through the platform shell, killing it once `timeout`
+/// elapses. Only a successful exit status counts, and the output must be
+/// complete by the deadline too: a background job can keep the pipe open
+/// after the shell itself exits.
+fn run_command(command: &str, timeout: Duration) -> Result<String, String> {
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    };
+    // Its own process group, so a timeout takes down everything it started.
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| format!("could not start the shell: {}", err))?;
+
+    // Drain stdout on a separate thread so a chatty command cannot block on
+    // a full pipe while we wait for it.
+    let mut stdout = child.stdout.take().ok_or("no stdout pipe")?;
+    let (sender, output) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stdout.read_to_end(&mut buf);
+        let _ = sender.send(buf);
+    });
+
+    let deadline = Instant::now() + timeout;
+    let timed_out = || format!("timed out after {} ms", timeout.as_millis());
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(5)),
+            Ok(None) => {
+                kill_command(&mut child);
+                return Err(timed_out());
+            }
+            Err(err) => {
+                kill_command(&mut child);
+                return Err(err.to_string());
+            }
+        }
+    };
+    let Ok(output) = output.recv_timeout(deadline.saturating_duration_since(Instant::now())) else {
+        kill_command(&mut child);
+        return Err(timed_out());
+    };
+    if !status.success() {
+        return Err(format!("command failed ({})", status));
+    }
+    Ok(String::from_utf8_lossy(&output).into_owned())
+}
+
+fn kill_command(child: &mut Child) {
+    // SAFETY: kill(2) takes no pointers; the negative pid names the process
+    // group created for this command alone.
+    #[cfg(unix)]
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+fn cache_dir() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            env::var_os("HOME")
+                .filter(|dir| !dir.is_empty())
+                .map(|home| PathBuf::from(home).join(".cache"))
+        })?;
+    Some(base.join("neonfetch").join("fields"))
+}
+
+/// Cache files hold the source description on the first line and the value
+/// after it; freshness comes from the file's modification time.
+fn read_cached(path: &Path, source: &CustomFieldSource, ttl: Duration) -> Option<String> {
+    let age = fs::metadata(path)
+        .ok()?
+        .modified()
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())?;
+    if age > ttl {
+        return None;
+    }
+    let contents = fs::read_to_string(path).ok()?;
+    let (description, value) = contents.split_once('\n')?;
+    (description == source.describe()).then(|| value.to_string())
+}
+
+/// Write through a temporary file in the same directory and rename it into
+/// place, so a fetch starting at the same moment never reads half a file.
+fn write_cached(path: &Path, source: &CustomFieldSource, value: &str) {
+    let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+        return;
+    };
+    if fs::create_dir_all(parent).is_err() {
+        return;
+    }
+    let temp = parent.join(format!(
+        ".{}.{}.tmp",
+        name.to_string_lossy(),
+        std::process::id()
+    ));
+    let written = fs::write(&temp, format!("{}\n{}", source.describe(), value))
+        .and_then(|()| fs::rename(&temp, path));
+    if written.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::fixture::temp_path;
+
+    #[cfg(unix)]
+    #[test]
+    fn runs_commands_with_timeout() {
+        let timeout = Duration::from_secs(5);
+        assert_eq!(
+            read_source(
+                &CustomFieldSource::Command("printf '  v1.2  \\n'".into()),
+                timeout
+            ),
+            Ok(Some("v1.2".to_string()))
+        );
+        assert_eq!(
+            read_source(
+                &CustomFieldSource::Command("echo out; exit 3".into()),
+                timeout
+            ),
+            Err("command failed (exit status: 3)".to_string())
+        );
+        assert_eq!(
+            read_source(&CustomFieldSource::Command("true".into()), timeout),
+            Ok(None)
+        );
+
+        let started = Instant::now();
+        assert_eq!(
+            read_source(
+                &CustomFieldSource::Command("sleep 5; echo late".into()),
+                Duration::from_millis(100)
+            ),
+            Err("timed out after 100 ms".to_string())
+        );
+        assert!(started.elapsed() < Duration::from_secs(4));
+
+        // A background job holding stdout must not outlive the deadline.
+        let started = Instant::now();
+        assert_eq!(
+            read_source(
+                &CustomFieldSource::Command("sleep 5 & echo early".into()),
+                Duration::from_millis(100)
+            ),
+            Err("timed out after 100 ms".to_string())
+        );
+        assert!(started.elapsed() < Duration::from_secs(4));
+    }
+
+    #[test]
+    fn reads_files_and_environment() {
+        let root = temp_path("custom-file");
+        fs::create_dir_all(&root).unwrap();
+        let path = root.join("motd");
+        fs::write(&path, "hello from file\n").unwrap();
+        assert_eq!(
+            read_source(&CustomFieldSource::File(path), DEFAULT_CUSTOM_TIMEOUT),
+            Ok(Some("hello from file".to_string()))
+        );
+        let missing = read_source(
+            &CustomFieldSource::File(root.join("missing")),
+            DEFAULT_CUSTOM_TIMEOUT,
+        );
+        assert!(
+            missing
+                .as_ref()
+                .is_err_and(|err| err.starts_with("could not read ")),
+            "{missing:?}"
+        );
+        let _ = fs::remove_dir_all(root);
+
+        assert_eq!(
+            read_source(
+                &CustomFieldSource::Env("NEONFETCH_TEST_UNSET_VARIABLE".into()),
+                DEFAULT_CUSTOM_TIMEOUT
+            ),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn cache_entries_expire_and_track_source() {
+        let root = temp_path("custom-cache");
+        let path = root.join("weather");
+        let source = CustomFieldSource::Command("curl wttr.in".into());
+        write_cached(&path, &source, "Sunny +21°C");
+
+        let ttl = Duration::from_secs(60);
+        assert_eq!(
+            read_cached(&path, &source, ttl),
+            Some("Sunny +21°C".to_string())
+        );
+        assert_eq!(
+            read_cached(&path, &CustomFieldSource::Command("other".into()), ttl),
+            None
+        );
+
+        write_cached(&path, &source, "Rain +14°C");
+        assert_eq!(
+            read_cached(&path, &source, ttl),
+            Some("Rain +14°C".to_string())
+        );
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 1);
+
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(read_cached(&path, &source, Duration::from_millis(1)), None);
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,976 @@
+use crate::util::i18n::Lang;
+use crate::util::template::TemplateValue;
+use miniz_oxide::inflate::decompress_to_vec_zlib;
+use sha1::{Digest, Sha1};
+use std::cell::OnceCell;
+use std::collections::{BinaryHeap, HashMap};
+use std::env;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+type ObjectId = [u8; 20];
+
+/// Upper bound on commits visited while counting ahead/behind, so a fetch in
+/// a huge repository with diverged history stays fast.
+const MAX_WALK_COMMITS: usize = 10_000;
+
+const OBJ_COMMIT: u8 = 1;
+const OBJ_OFS_DELTA: u8 = 6;
+const OBJ_REF_DELTA: u8 = 7;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GitSummary {
+    pub name: String,
+    /// Branch name, or a short commit id when HEAD is detached.
+    pub head: String,
+    pub ahead_behind: Option<(usize, usize)>,
+    /// Tracked files whose worktree content differs from the index. Staged
+    /// changes and untracked files are not counted.
+    pub modified: Option<usize>,
+    pub last_commit_time: Option<u64>,
+}
+
+pub fn detect_git() -> Option<GitSummary> {
+    let cwd = env::current_dir().ok()?;
+    read_git_summary(&cwd)
+}
+
+/// Summarize the repository containing `start` by reading `.git` directly.
+pub fn read_git_summary(start: &Path) -> Option<GitSummary> {
+    let repo = Repo::discover(start)?;
+    let name = repo
+        .work_tree
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "repo".to_string());
+
+    let head_text = fs::read_to_string(repo.git_dir.join("HEAD")).ok()?;
+    let head_text = head_text.trim();
+    let (head, head_id) = if let Some(reference) = head_text.strip_prefix("ref: ") {
+        let branch = reference
+            .strip_prefix("refs/heads/")
+            .unwrap_or(reference)
+            .to_string();
+        (branch, repo.resolve_ref(reference))
+    } else {
+        let id = parse_hex_id(head_text)?;
+        (head_text[..7].to_string(), Some(id))
+    };
+
+    let ahead_behind = head_text
+        .strip_prefix("ref: refs/heads/")
+        .and_then(|branch| repo.upstream_ref(branch))
+        .and_then(|upstream| repo.resolve_ref(&upstream))
+        .zip(head_id)
+        .and_then(|(upstream_id, local_id)| repo.ahead_behind(local_id, upstream_id));
+    let last_commit_time = head_id
+        .and_then(|id| repo.commit(&id))
+        .map(|commit| commit.time);
+    let modified = fs::read(repo.git_dir.join("index"))
+        .ok()
+        .and_then(|data| parse_index(&data))
+        .map(|entries| count_modified(&repo.work_tree, &entries));
+
+    Some(GitSummary {
+        name,
+        head,
+        ahead_behind,
+        modified,
+        last_commit_time,
+    })
+}
+
+/// The `Git` value; `now` (Unix seconds) dates the last commit.
+pub fn format_git_value(summary: &GitSummary, now: u64, lang: Lang) -> String {
+    let mut head = summary.head.clone();
+    if let Some((ahead, behind)) = summary.ahead_behind {
+        if ahead > 0 {
+            head.push_str(&format!(" ↑{}", ahead));
+        }
+        if behind > 0 {
+            head.push_str(&format!(" ↓{}", behind));
+        }
+    }
+    let mut details = vec![head];
+    match summary.modified {
+        Some(0) => details.push(lang.tr("clean").to_string()),
+        Some(count) => details.push(format!("{} {}", count, lang.tr("modified"))),
+        None => {}
+    }
+    if let Some(time) = summary.last_commit_time {
+        details.push(format!(
+            "{} {}",
+            lang.tr("last commit"),
+            format_age(now.saturating_sub(time), lang)
+        ));
+    }
+    format!("{} ({})", summary.name, details.join(", "))
+}
+
+/// Template placeholders for the `git` field.
+pub fn git_values(
+    summary: &GitSummary,
+    now: u64,
+    lang: Lang,
+) -> Vec<(&'static str, TemplateValue)> {
+    let mut values = vec![
+        ("repo", summary.name.as_str().into()),
+        ("branch", summary.head.as_str().into()),
+    ];
+    if let Some((ahead, behind)) = summary.ahead_behind {
+        values.push(("ahead", ahead.into()));
+        values.push(("behind", behind.into()));
+    }
+    if let Some(modified) = summary.modified {
+        values.push(("modified", modified.into()));
+    }
+    if let Some(time) = summary.last_commit_time {
+        values.push((
+            "last_commit",
+            format_age(now.saturating_sub(time), lang).into(),
+        ));
+        values.push(("last_commit_time", time.into()));
+    }
+    values
+}
+
+fn format_age(secs: u64, lang: Lang) -> String {
+    let (value, one, many) = match secs {
+        0..60 => return lang.tr("just now").to_string(),
+        60..3_600 => (secs / 60, "minute", "minutes"),
+        3_600..86_400 => (secs / 3_600, "hour", "hours"),
+        86_400..2_592_000 => (secs / 86_400, "day", "days"),
+        2_592_000..31_536_000 => (secs / 2_592_000, "month", "months"),
+        _ => (secs / 31_536_000, "year", "years"),
+    };
+    format!(
+        "{} {} {}",
+        value,
+        lang.tr(if value == 1 { one } else { many }),
+        lang.tr("ago")
+    )
+}
+
+struct Repo {
+    /// Per-worktree directory holding HEAD and the index.
+    git_dir: PathBuf,
+    /// Shared directory holding refs, objects and config (differs from
+    /// `git_dir` for linked worktrees).
+    common_dir: PathBuf,
+    work_tree: PathBuf,
+    /// Pack indexes, read on the first object that is not stored loose.
+    packs: OnceCell<Vec<Pack>>,
+}
+
+struct Commit {
+    parents: Vec<ObjectId>,
+    time: u64,
+}
+
+impl Repo {
+    fn discover(start: &Path) -> Option<Repo> {
+        for dir in start.ancestors() {
+            let dot_git = dir.join(".git");
+            let git_dir = if dot_git.is_dir() {
+                dot_git
+            } else if let Ok(text) = fs::read_to_string(&dot_git)
+                && let Some(target) = text.trim().strip_prefix("gitdir: ")
+            {
+                dir.join(target)
+            } else {
+                continue;
+            };
+            let common_dir = fs::read_to_string(git_dir.join("commondir"))
+                .map(|rel| git_dir.join(rel.trim()))
+                .unwrap_or_else(|_| git_dir.clone());
+            return Some(Repo {
+                git_dir,
+                common_dir,
+                work_tree: dir.to_path_buf(),
+                packs: OnceCell::new(),
+            });
+        }
+        None
+    }
+
+    fn resolve_ref(&self, name: &str) -> Option<ObjectId> {
+        let mut name = name.to_string();
+        for _ in 0..5 {
+            let loose = [&self.git_dir, &self.common_dir]
+                .iter()
+                .find_map(|dir| fs::read_to_string(dir.join(&name)).ok());
+            match loose {
+                Some(text) => match text.trim().strip_prefix("ref: ") {
+                    Some(target) => name = target.to_string(),
+                    None => return parse_hex_id(text.trim()),
+                },
+                None => return self.packed_ref(&name),
+            }
+        }
+        None
+    }
+
+    fn packed_ref(&self, name: &str) -> Option<ObjectId> {
+        let text = fs::read_to_string(self.common_dir.join("packed-refs")).ok()?;
+        text.lines()
+            .filter(|line| !line.starts_with('#') && !line.starts_with('^'))
+            .find_map(|line| {
+                let (id, reference) = line.split_once(' ')?;
+                (reference.trim() == name)
+                    .then(|| parse_hex_id(id))
+                    .flatten()
+            })
+    }
+
+    /// `refs/remotes/<remote>/<branch>` from `[branch "<name>"]` in config.
+    fn upstream_ref(&self, branch: &str) -> Option<String> {
+        let config = fs::read_to_string(self.common_dir.join("config")).ok()?;
+        let wanted = format!("branch \"{}\"", branch);
+        let mut in_section = false;
+        let (mut remote, mut merge) = (None, None);
+        for line in config.lines() {
+            let line = line.trim();
+            if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                in_section = section.trim() == wanted;
+                continue;
+            }
+            if !in_section {
+                continue;
+            }
+            if let Some((key, value)) = line.split_once('=') {
+                match key.trim().to_ascii_lowercase().as_str() {
+                    "remote" => remote = Some(value.trim().to_string()),
+                    "merge" => merge = Some(value.trim().to_string()),
+                    _ => {}
+                }
+            }
+        }
+        let (remote, merge) = (remote?, merge?);
+        if remote == "." {
+            return Some(merge);
+        }
+        let short = merge.strip_prefix("refs/heads/").unwrap_or(&merge);
+        Some(format!("refs/remotes/{}/{}", remote, short))
+    }
+
+    fn read_object(&self, id: &ObjectId) -> Option<(u8, Vec<u8>)> {
+        let hex = to_hex(id);
+        let loose = self
+            .common_dir
+            .join("objects")
+            .join(&hex[..2])
+            .join(&hex[2..]);
+        if let Ok(compressed) = fs::read(loose) {
+            let data = decompress_to_vec_zlib(&compressed).ok()?;
+            let nul = data.iter().position(|&b| b == 0)?;
+            let header = std::str::from_utf8(&data[..nul]).ok()?;
+            let kind = match header.split(' ').next()? {
+                "commit" => OBJ_COMMIT,
+                "tree" => 2,
+                "blob" => 3,
+                "tag" => 4,
+                _ => return None,
+            };
+            return Some((kind, data[nul + 1..].to_vec()));
+        }
+        let packs = self
+            .packs
+            .get_or_init(|| Pack::load_all(&self.common_dir.join("objects/pack")));
+        packs.iter().find_map(|pack| {
+            let offset = pack.find(id)?;
+            pack.read_at(offset, self, 0)
+        })
+    }
+
+    fn commit(&self, id: &ObjectId) -> Option<Commit> {
+        let (kind, data) = self.read_object(id)?;
+        if kind != OBJ_COMMIT {
+            return None;
+        }
+        let text = String::from_utf8_lossy(&data);
+        let mut parents = Vec::new();
+        let mut time = 0;
+        for line in text.lines() {
+            if line.is_empty() {
+                break;
+            }
+            if let Some(parent) = line.strip_prefix("parent ") {
+                parents.extend(parse_hex_id(parent));
+            } else if let Some(committer) = line.strip_prefix("committer ") {
+                // "Name <email> 1700000000 +0100"
+                time = committer
+                    .rsplit(' ')
+                    .nth(1)
+                    .and_then(|t| t.parse().ok())
+                    .unwrap_or(0);
+            }
+        }
+        Some(Commit { parents, time })
+    }
+
+    /// Count commits reachable only from `local` (ahead) and only from
+    /// `upstream` (behind), walking newest-first like `git rev-list
+    /// --left-right --count` until every pending commit is shared.
+    fn ahead_behind(&self, local: ObjectId, upstream: ObjectId) -> Option<(usize, usize)> {
+        const LOCAL: u8 = 1;
+        const UPSTREAM: u8 = 2;
+        const BOTH: u8 = LOCAL | UPSTREAM;
+        const QUEUED: u8 = 4;
+        if local == upstream {
+            return Some((0, 0));
+        }
+        let mut flags: HashMap<ObjectId, u8> = HashMap::new();
+        // Parents of commits already taken off the queue. Commit times are
+        // not always in order, so a side can reach a commit after it was
+        // walked; its flag is then passed on through these.
+        let mut walked: HashMap<ObjectId, Vec<ObjectId>> = HashMap::new();
+        let mut queue = BinaryHeap::new();
+        for (id, flag) in [(local, LOCAL), (upstream, UPSTREAM)] {
+            let commit = self.commit(&id)?;
+            flags.insert(id, flag | QUEUED);
+            queue.push((commit.time, id, commit.parents));
+        }
+        // Queued commits not yet reachable from both sides; the walk is over
+        // when none are left.
+        let mut pending = queue.len();
+        while pending > 0
+            && let Some((_, id, parents)) = queue.pop()
+        {
+            if walked.len() >= MAX_WALK_COMMITS {
+                return None;
+            }
+            let entry = flags.entry(id).or_default();
+            *entry &= !QUEUED;
+            let flag = *entry;
+            if flag != BOTH {
+                pending -= 1;
+            }
+            let mut marks = parents.clone();
+            while let Some(parent) = marks.pop() {
+                let entry = flags.entry(parent).or_insert(0);
+                let old = *entry;
+                *entry |= flag;
+                if *entry == old {
+                    continue;
+                }
+                if old == 0 {
+                    if let Some(commit) = self.commit(&parent) {
+                        *entry |= QUEUED;
+                        queue.push((commit.time, parent, commit.parents));
+                        if flag != BOTH {
+                            pending += 1;
+                        }
+                    }
+                } else if old & QUEUED != 0 {
+                    if *entry & BOTH == BOTH {
+                        pending -= 1;
+                    }
+                } else if let Some(grandparents) = walked.get(&parent) {
+                    marks.extend(grandparents);
+                }
+            }
+            walked.insert(id, parents);
+        }
+        let count = |side: u8| walked.keys().filter(|id| flags[*id] == side).count();
+        Some((count(LOCAL), count(UPSTREAM)))
+    }
+}
+
+/// A packfile plus its v2 index, read on demand.
+struct Pack {
+    /// Opened once; reads seek through a shared reference.
+    file: File,
+    ids: Vec<ObjectId>,
+    offsets: Vec<u64>,
+    /// All object offsets sorted ascending, to bound each entry's size.
+    sorted_offsets: Vec<u64>,
+    pack_len: u64,
+}
+
+impl Pack {
+    fn load_all(dir: &Path) -> Vec<Pack> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "idx"))
+            .filter_map(|idx| Pack::load(&idx, &idx.with_extension("pack")))
+            .collect()
+    }
+
+    fn load(idx_path: &Path, pack_path: &Path) -> Option<Pack> {
+        let idx = fs::read(idx_path).ok()?;
+        if idx.get(..8)? != b"\xfftOc\x00\x00\x00\x02" {
+            return None;
+        }
+        let be32 = |pos: usize| -> Option<u32> {
+            idx.get(pos..pos + 4)
+                .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+        };
+        let count = be32(8 + 255 * 4)? as usize;
+        let ids_start = 8 + 256 * 4;
+        let offsets_start = ids_start + count * 20 + count * 4;
+        let large_start = offsets_start + count * 4;
+        let mut ids = Vec::with_capacity(count);
+        let mut offsets = Vec::with_capacity(count);
+        for i in 0..count {
+            let mut id = [0u8; 20];
+            id.copy_from_slice(idx.get(ids_start + i * 20..ids_start + i * 20 + 20)?);
+            ids.push(id);
+            let raw = be32(offsets_start + i * 4)?;
+            let offset = if raw & 0x8000_0000 != 0 {
+                let pos = large_start + (raw & 0x7fff_ffff) as usize * 8;
+                u64::from_be_bytes(idx.get(pos..pos + 8)?.try_into().ok()?)
+            } else {
+                u64::from(raw)
+            };
+            offsets.push(offset);
+        }
+        let mut sorted_offsets = offsets.clone();
+        sorted_offsets.sort_unstable();
+        let file = File::open(pack_path).ok()?;
+        let pack_len = file.metadata().ok()?.len();
+        Some(Pack {
+            file,
+            ids,
+            offsets,
+            sorted_offsets,
+            pack_len,
+        })
+    }
+
+    fn find(&self, id: &ObjectId) -> Option<u64> {
+        self.ids
+            .binary_search(id)
+            .ok()
+            .map(|index| self.offsets[index])
+    }
+
+    fn read_at(&self, offset: u64, repo: &Repo, depth: usize) -> Option<(u8, Vec<u8>)> {
+        if depth > 50 {
+            return None;
+        }
+        let next = self
+            .sorted_offsets
+            .get(self.sorted_offsets.partition_point(|&o| o <= offset))
+            .copied()
+            .unwrap_or(self.pack_len.saturating_sub(20));
+        let mut raw = vec![0u8; next.checked_sub(offset)? as usize];
+        let mut file = &self.file;
+        file.seek(SeekFrom::Start(offset)).ok()?;
+        file.read_exact(&mut raw).ok()?;
+
+        let mut pos = 0;
+        let mut byte = *raw.first()?;
+        let kind = (byte >> 4) & 0x07;
+        while byte & 0x80 != 0 {
+            pos += 1;
+            byte = *raw.get(pos)?;
+        }
+        pos += 1;
+        match kind {
+            OBJ_OFS_DELTA => {
+                let mut byte = *raw.get(pos)?;
+                let mut distance = u64::from(byte & 0x7f);
+                while byte & 0x80 != 0 {
+                    pos += 1;
+                    byte = *raw.get(pos)?;
+                    distance = ((distance + 1) << 7) | u64::from(byte & 0x7f);
+                }
+                pos += 1;
+                let (base_kind, base) =
+                    self.read_at(offset.checked_sub(distance)?, repo, depth + 1)?;
+                let delta = decompress_to_vec_zlib(raw.get(pos..)?).ok()?;
+                Some((base_kind, apply_delta(&base, &delta)?))
+            }
+            OBJ_REF_DELTA => {
+                let base_id: ObjectId = raw.get(pos..pos + 20)?.try_into().ok()?;
+                let (base_kind, base) = repo.read_object(&base_id)?;
+                let delta = decompress_to_vec_zlib(raw.get(pos + 20..)?).ok()?;
+                Some((base_kind, apply_delta(&base, &delta)?))
+            }
+            1..=4 => Some((kind, decompress_to_vec_zlib(raw.get(pos..)?).ok()?)),
+            _ => None,
+        }
+    }
+}
+
+/// Apply a git delta (base size, result size, then copy/insert opcodes).
+fn apply_delta(base: &[u8], delta: &[u8]) -> Option<Vec<u8>> {
+    fn varint(data: &[u8], pos: &mut usize) -> Option<usize> {
+        let (mut value, mut shift) = (0usize, 0);
+        loop {
+            let byte = *data.get(*pos)?;
+            *pos += 1;
+            value |= usize::from(byte & 0x7f) << shift;
+            shift += 7;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+    }
+    let mut pos = 0;
+    if varint(delta, &mut pos)? != base.len() {
+        return None;
+    }
+    let result_len = varint(delta, &mut pos)?;
+    let mut out = Vec::with_capacity(result_len);
+    while pos < delta.len() {
+        let op = delta[pos];
+        pos += 1;
+        if op & 0x80 != 0 {
+            let mut offset = 0usize;
+            let mut size = 0usize;
+            for bit in 0..4 {
+                if op & (1 << bit) != 0 {
+                    offset |= usize::from(*delta.get(pos)?) << (8 * bit);
+                    pos += 1;
+                }
+            }
+            for bit in 0..3 {
+                if op & (0x10 << bit) != 0 {
+                    size |= usize::from(*delta.get(pos)?) << (8 * bit);
+                    pos += 1;
+                }
+            }
+            if size == 0 {
+                size = 0x10000;
+            }
+            out.extend_from_slice(base.get(offset..offset + size)?);
+        } else if op != 0 {
+            out.extend_from_slice(delta.get(pos..pos + op as usize)?);
+            pos += op as usize;
+        } else {
+            return None;
+        }
+    }
+    (out.len() == result_len).then_some(out)
+}
+
+fn sha1(data: &[u8]) -> ObjectId {
+    Sha1::digest(data).into()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn parse_hex_id(text: &str) -> Option<ObjectId> {
+    let text = text.trim();
+    if text.len() != 40 {
+        return None;
+    }
+    let mut id = [0u8; 20];
+    for (i, byte) in id.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(text.get(i * 2..i * 2 + 2)?, 16).ok()?;
+    }
+    Some(id)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct IndexEntry {
+    path: String,
+    mtime: (u32, u32),
+    mode: u32,
+    size: u32,
+    id: ObjectId,
+    stage: u16,
+    skip_worktree: bool,
+}
+
+/// Parse a v2/v3/v4 `.git/index` file into its entries.
+fn parse_index(data: &[u8]) -> Option<Vec<IndexEntry>> {
+    if data.get(..4)? != b"DIRC" {
+        return None;
+    }
+    let be32 = |pos: usize| -> Option<u32> {
+        data.get(pos..pos + 4)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    };
+    let version = be32(4)?;
+    if !(2..=4).contains(&version) {
+        return None;
+    }
+    let count = be32(8)? as usize;
+    let mut entries = Vec::with_capacity(count);
+    let mut pos = 12;
+    let mut previous_path: Vec<u8> = Vec::new();
+    for _ in 0..count {
+        let start = pos;
+        let mtime = (be32(pos + 8)?, be32(pos + 12)?);
+        let mode = be32(pos + 24)?;
+        let size = be32(pos + 36)?;
+        let id: ObjectId = data.get(pos + 40..pos + 60)?.try_into().ok()?;
+        let flags = u16::from_be_bytes([*data.get(pos + 60)?, *data.get(pos + 61)?]);
+        pos += 62;
+        let mut skip_worktree = false;
+        if flags & 0x4000 != 0 {
+            let extended = u16::from_be_bytes([*data.get(pos)?, *data.get(pos + 1)?]);
+            skip_worktree = extended & 0x4000 != 0;
+            pos += 2;
+        }
+        let path = if version == 4 {
+            let mut strip = 0usize;
+            loop {
+                let byte = *data.get(pos)?;
+                pos += 1;
+                strip = (strip << 7) | usize::from(byte & 0x7f);
+                if byte & 0x80 == 0 {
+                    break;
+                }
+                strip += 1;
+            }
+            let nul = data.get(pos..)?.iter().position(|&b| b == 0)?;
+            let keep = previous_path.len().checked_sub(strip)?;
+            let mut path = previous_path[..keep].to_vec();
+            path.extend_from_slice(&data[pos..pos + nul]);
+            pos += nul + 1;
+            path
+        } else {
+            let nul = data.get(pos..)?.iter().position(|&b| b == 0)?;
+            let path = data[pos..pos + nul].to_vec();
+            // Entries are NUL-padded to a multiple of eight bytes.
+            pos = start + (pos + nul - start + 8) / 8 * 8;
+            path
+        };
+        entries.push(IndexEntry {
+            path: String::from_utf8_lossy(&path).into_owned(),
+            mtime,
+            mode,
+            size,
+            id,
+            stage: (flags >> 12) & 0x3,
+            skip_worktree,
+        });
+        previous_path = path;
+    }
+    Some(entries)
+}
+
+/// Count tracked paths whose worktree content differs from the index, using
+/// the stat data as a fast path and re-hashing files whose stat changed.
+fn count_modified(work_tree: &Path, entries: &[IndexEntry]) -> usize {
+    let mut modified = 0;
+    let mut last_conflict: Option<&str> = None;
+    for entry in entries {
+        if entry.stage != 0 {
+            if last_conflict != Some(entry.path.as_str()) {
+                modified += 1;
+                last_conflict = Some(&entry.path);
+            }
+            continue;
+        }
+        // Submodules (gitlinks) and sparse entries are not checked.
+        if entry.skip_worktree || entry.mode & 0o170000 == 0o160000 {
+            continue;
+        }
+        if is_entry_modified(&work_tree.join(&entry.path), entry) {
+            modified += 1;
+        }
+    }
+    modified
+}
+
+fn is_entry_modified(path: &Path, entry: &IndexEntry) -> bool {
+    let Ok(meta) = fs::symlink_metadata(path) else {
+        return true;
+    };
+    let is_link = entry.mode & 0o170000 == 0o120000;
+    if meta.file_type().is_symlink() != is_link {
+        return true;
+    }
+    #[cfg(unix)]
+    if !is_link {
+        use std::os::unix::fs::PermissionsExt;
+        let executable = meta.permissions().mode() & 0o100 != 0;
+        if executable != (entry.mode & 0o100 != 0) {
+            return true;
+        }
+    }
+    if meta.len() as u32 != entry.size {
+        return true;
+    }
+    let mtime = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| (d.as_secs() as u32, d.subsec_nanos()));
+    if mtime == Some(entry.mtime) {
+        return false;
+    }
+    let content = if is_link {
+        fs::read_link(path)
+            .map(|target| target.to_string_lossy().into_owned().into_bytes())
+            .unwrap_or_default()
+    } else {
+        match fs::read(path) {
+            Ok(content) => content,
+            Err(_) => return true,
+        }
+    };
+    let mut blob = format!("blob {}\0", content.len()).into_bytes();
+    blob.extend_from_slice(&content);
+    sha1(&blob) != entry.id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::fixture::{temp_path, write};
+    use miniz_oxide::deflate::compress_to_vec_zlib;
+
+    fn write_loose(git_dir: &Path, kind: &str, body: &[u8]) -> ObjectId {
+        let mut data = format!("{} {}\0", kind, body.len()).into_bytes();
+        data.extend_from_slice(body);
+        let id = sha1(&data);
+        let hex = to_hex(&id);
+        write(
+            git_dir,
+            &format!("objects/{}/{}", &hex[..2], &hex[2..]),
+            compress_to_vec_zlib(&data, 6),
+        );
+        id
+    }
+
+    fn commit_body(parents: &[ObjectId], time: u64) -> Vec<u8> {
+        let mut body = String::from("tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n");
+        for parent in parents {
+            body.push_str(&format!("parent {}\n", to_hex(parent)));
+        }
+        body.push_str(&format!(
+            "author A <a@example.com> {time} +0000\ncommitter A <a@example.com> {time} +0000\n\nmsg\n"
+        ));
+        body.into_bytes()
+    }
+
+    fn index_entry(path: &str, content: &[u8], mtime: (u32, u32)) -> Vec<u8> {
+        let mut blob = format!("blob {}\0", content.len()).into_bytes();
+        blob.extend_from_slice(content);
+        let mut entry = Vec::new();
+        for value in [
+            0,
+            0,
+            mtime.0,
+            mtime.1,
+            0,
+            0,
+            0o100644,
+            0,
+            0,
+            content.len() as u32,
+        ] {
+            entry.extend_from_slice(&u32::to_be_bytes(value));
+        }
+        entry.extend_from_slice(&sha1(&blob));
+        entry.extend_from_slice(&(path.len() as u16).to_be_bytes());
+        entry.extend_from_slice(path.as_bytes());
+        let padded = (62 + path.len() + 8) / 8 * 8;
+        entry.resize(padded, 0);
+        entry
+    }
+
+    #[test]
+    fn summarizes_branch_upstream_modified_files_and_age() {
+        let root = temp_path("git-repo").join("project");
+        let git = root.join(".git");
+
+        let base = write_loose(&git, "commit", &commit_body(&[], 1_000));
+        let shared = write_loose(&git, "commit", &commit_body(&[base], 2_000));
+        let local1 = write_loose(&git, "commit", &commit_body(&[shared], 3_000));
+        let local2 = write_loose(&git, "commit", &commit_body(&[local1], 4_000));
+        let remote1 = write_loose(&git, "commit", &commit_body(&[shared], 3_500));
+
+        write(&git, "HEAD", b"ref: refs/heads/main\n");
+        write(
+            &git,
+            "refs/heads/main",
+            format!("{}\n", to_hex(&local2)).as_bytes(),
+        );
+        write(
+            &git,
+            "packed-refs",
+            format!(
+                "# pack-refs with: peeled fully-peeled sorted\n{} refs/remotes/origin/main\n",
+                to_hex(&remote1)
+            )
+            .as_bytes(),
+        );
+        write(
+            &git,
+            "config",
+            b"[core]\n\tbare = false\n[branch \"main\"]\n\tremote = origin\n\tmerge = refs/heads/main\n",
+        );
+
+        write(&root, "clean.txt", b"same\n");
+        write(&root, "edited.txt", b"changed\n");
+        write(&root, "sub/nested.txt", b"same\n");
+        let mut index = b"DIRC".to_vec();
+        index.extend_from_slice(&2u32.to_be_bytes());
+        index.extend_from_slice(&4u32.to_be_bytes());
+        index.extend(index_entry("clean.txt", b"same\n", (1, 0)));
+        index.extend(index_entry("edited.txt", b"original\n", (1, 0)));
+        index.extend(index_entry("missing.txt", b"gone\n", (1, 0)));
+        index.extend(index_entry("sub/nested.txt", b"same\n", (1, 0)));
+        write(&git, "index", &index);
+
+        let summary = read_git_summary(&root.join("sub")).expect("repository found");
+        assert_eq!(
+            summary,
+            GitSummary {
+                name: "project".to_string(),
+                head: "main".to_string(),
+                ahead_behind: Some((2, 1)),
+                modified: Some(2),
+                last_commit_time: Some(4_000),
+            }
+        );
+        assert_eq!(
+            format_git_value(&summary, 4_000 + 7_200, Lang::En),
+            "project (main ↑2 ↓1, 2 modified, last commit 2 hours ago)"
+        );
+        let values = git_values(&summary, 4_000 + 7_200, Lang::En);
+        assert_eq!(values[2], ("ahead", TemplateValue::Integer(2)));
+        assert_eq!(
+            values[5],
+            (
+                "last_commit",
+                TemplateValue::Text("2 hours ago".to_string())
+            )
+        );
+
+        let _ = fs::remove_dir_all(root.parent().unwrap());
+    }
+
+    #[test]
+    fn detached_head_and_no_repository() {
+        let root = temp_path("git-detached");
+        let git = root.join(".git");
+        let commit = write_loose(&git, "commit", &commit_body(&[], 50));
+        write(&git, "HEAD", format!("{}\n", to_hex(&commit)).as_bytes());
+
+        let summary = read_git_summary(&root).expect("repository found");
+        assert_eq!(summary.head, to_hex(&commit)[..7]);
+        assert_eq!(summary.ahead_behind, None);
+        assert_eq!(summary.modified, None);
+        assert_eq!(summary.last_commit_time, Some(50));
+        let _ = fs::remove_dir_all(&root);
+
+        assert_eq!(read_git_summary(&temp_path("git-nowhere")), None);
+    }
+
+    #[test]
+    fn counts_ahead_behind_across_skewed_commit_times() {
+        let root = temp_path("git-skew");
+        let git = root.join(".git");
+        // The shared commit is dated after the local ones, so the upstream
+        // side walks it before the local side gets there.
+        let base = write_loose(&git, "commit", &commit_body(&[], 1_000));
+        let shared = write_loose(&git, "commit", &commit_body(&[base], 5_000));
+        let local1 = write_loose(&git, "commit", &commit_body(&[shared], 2_000));
+        let local2 = write_loose(&git, "commit", &commit_body(&[local1], 3_000));
+        let remote1 = write_loose(&git, "commit", &commit_body(&[shared], 4_000));
+
+        let repo = Repo::discover(&root).expect("repository found");
+        assert_eq!(repo.ahead_behind(local2, remote1), Some((2, 1)));
+        assert_eq!(repo.ahead_behind(local2, shared), Some((2, 0)));
+        assert_eq!(repo.ahead_behind(base, remote1), Some((0, 2)));
+    }
+
+    #[test]
+    fn reads_packed_objects_with_offset_delta() {
+        let root = temp_path("git-pack");
+        let git = root.join(".git");
+        fs::create_dir_all(git.join("objects/pack")).unwrap();
+
+        let base_body = commit_body(&[], 1_000);
+        let mut base_object = format!("commit {}\0", base_body.len()).into_bytes();
+        base_object.extend_from_slice(&base_body);
+        let base_id = sha1(&base_object);
+
+        // Second commit = first commit with the timestamps rewritten, stored
+        // as an OFS_DELTA: copy the unchanged prefix, insert the rest.
+        let target_body = commit_body(&[], 2_000);
+        let mut target_object = format!("commit {}\0", target_body.len()).into_bytes();
+        target_object.extend_from_slice(&target_body);
+        let target_id = sha1(&target_object);
+        let prefix = base_body
+            .iter()
+            .zip(&target_body)
+            .take_while(|(a, b)| a == b)
+            .count();
+        let mut delta = Vec::new();
+        for mut len in [base_body.len(), target_body.len()] {
+            while len >= 0x80 {
+                delta.push(0x80 | (len & 0x7f) as u8);
+                len >>= 7;
+            }
+            delta.push(len as u8);
+        }
+        delta.extend_from_slice(&[0x90, prefix as u8]);
+        let tail = &target_body[prefix..];
+        delta.push(tail.len() as u8);
+        delta.extend_from_slice(tail);
+
+        let mut pack = b"PACK".to_vec();
+        pack.extend_from_slice(&2u32.to_be_bytes());
+        pack.extend_from_slice(&2u32.to_be_bytes());
+        let base_offset = pack.len() as u32;
+        let size = base_body.len();
+        pack.push(0x80 | (OBJ_COMMIT << 4) | (size & 0x0f) as u8);
+        pack.push((size >> 4) as u8);
+        pack.extend(compress_to_vec_zlib(&base_body, 6));
+        let delta_offset = pack.len() as u32;
+        pack.push((OBJ_OFS_DELTA << 4) | delta.len() as u8 & 0x0f);
+        // Offset-delta distance: big-endian base-128 with an implicit +1 per
+        // continuation byte.
+        let mut distance = u64::from(delta_offset - base_offset);
+        let mut encoded = vec![(distance & 0x7f) as u8];
+        while distance >= 0x80 {
+            distance = (distance >> 7) - 1;
+            encoded.insert(0, 0x80 | (distance & 0x7f) as u8);
+        }
+        pack.extend(encoded);
+        pack.extend(compress_to_vec_zlib(&delta, 6));
+        pack.extend_from_slice(&[0u8; 20]);
+        fs::write(git.join("objects/pack/pack-test.pack"), &pack).unwrap();
+
+        let mut entries = [(base_id, base_offset), (target_id, delta_offset)];
+        entries.sort();
+        let mut idx = b"\xfftOc\x00\x00\x00\x02".to_vec();
+        for bucket in 0..256usize {
+            let count = entries
+                .iter()
+                .filter(|(id, _)| id[0] as usize <= bucket)
+                .count();
+            idx.extend_from_slice(&(count as u32).to_be_bytes());
+        }
+        for (id, _) in &entries {
+            idx.extend_from_slice(id);
+        }
+        idx.extend_from_slice(&[0u8; 8]);
+        for (_, offset) in &entries {
+            idx.extend_from_slice(&offset.to_be_bytes());
+        }
+        fs::write(git.join("objects/pack/pack-test.idx"), &idx).unwrap();
+        write(&git, "HEAD", b"ref: refs/heads/main\n");
+
+        let repo = Repo::discover(&root).expect("repository found");
+        assert_eq!(repo.commit(&base_id).map(|c| c.time), Some(1_000));
+        assert_eq!(repo.commit(&target_id).map(|c| c.time), Some(2_000));
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn formats_ages() {
+        assert_eq!(format_age(5, Lang::En), "just now");
+        assert_eq!(format_age(60, Lang::En), "1 minute ago");
+        assert_eq!(format_age(3 * 86_400, Lang::En), "3 days ago");
+        assert_eq!(format_age(400 * 86_400, Lang::En), "1 year ago");
+        assert_eq!(format_age(7_200, Lang::ZhCn), "2 小时 前");
+    }
+}
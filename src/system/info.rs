@@ -1,11 +1,29 @@
-use super::ascii_logo_with_distro;
+use super::boot::{boot_values, detect_boot, format_boot_value};
+use super::containers::{containers_values, detect_containers, format_containers_value};
+use super::custom::{CustomField, detect_custom_field};
+use super::git::{detect_git, format_git_value, git_values};
+use super::kernel::{detect_kernel_details, format_kernel_detail_lines, kernel_detail_values};
+use super::layout::{Arrangement, Blocks, Layout};
+use super::probe::{ProbeReport, ProbeStatus, spawn_probe};
+use super::snapshot::Snapshot;
+use super::storage::{detect_storage, format_storage_value, storage_device_values, storage_values};
+use super::users::{
+    detect_last_login, detect_users, format_last_login_value, format_users_value,
+    last_login_values, users_values,
+};
+use super::{ascii_logo_small_with_distro, ascii_logo_with_distro, detect_distro_id};
+use crate::util::i18n::{Lang, localize_decimal};
+use crate::util::template::{Template, TemplateValue};
+use crate::util::time::{UptimeStyle, format_local_timestamp, format_uptime};
+use crate::util::units::ByteUnits;
+use crate::util::width::str_width;
 #[cfg(target_os = "macos")]
 use libc;
 use std::env;
 #[cfg(target_os = "linux")]
 use std::fs;
 use std::process::Command;
-use std::thread;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use sysinfo::{CpuRefreshKind, Disks, MemoryRefreshKind, RefreshKind, System};
 
 fn detect_host_model() -> Option<String> {
@@ -131,7 +149,8 @@ fn detect_gpu_and_resolution() -> (Option<String>, Option<String>) {
     (None, None)
 }
 
-fn detect_battery() -> Option<String> {
+/// Charge percentage and status (`charging`, `Discharging`, ...).
+fn detect_battery() -> Option<(u32, String)> {
     #[cfg(target_os = "macos")]
     {
         use std::process::Command;
@@ -151,7 +170,7 @@ fn detect_battery() -> Option<String> {
                         break;
                     }
                 }
-                if !digits.is_empty() {
+                if let Ok(percent) = digits.parse::<u32>() {
                     let status = if line.contains("discharging") {
                         "discharging"
                     } else if line.contains("charging") {
@@ -161,7 +180,7 @@ fn detect_battery() -> Option<String> {
                     } else {
                         ""
                     };
-                    return Some(format!("{}% {}", digits, status));
+                    return Some((percent, status.to_string()));
                 }
             }
         }
@@ -176,10 +195,10 @@ fn detect_battery() -> Option<String> {
                     let base = e.path();
                     let cap = fs::read_to_string(base.join("capacity")).ok();
                     let stat = fs::read_to_string(base.join("status")).ok();
-                    if let Some(cap_trim) = cap.as_deref().map(str::trim).filter(|s| !s.is_empty())
+                    if let Some(percent) = cap.as_deref().and_then(|s| s.trim().parse::<u32>().ok())
                     {
                         let s = stat.unwrap_or_default();
-                        return Some(format!("{}% {}", cap_trim, s.trim()));
+                        return Some((percent, s.trim().to_string()));
                     }
                 }
             }
@@ -188,7 +207,8 @@ fn detect_battery() -> Option<String> {
     None
 }
 
-fn detect_pkg_count() -> Option<String> {
+/// Package count from the first package manager that reports any.
+fn detect_pkg_count() -> Option<(&'static str, usize)> {
     use std::process::Command;
     let candidates: &[(&str, &[&str], &str)] = &[
         ("brew", &["list"], "brew"),
@@ -205,14 +225,15 @@ fn detect_pkg_count() -> Option<String> {
         {
             let count = text.lines().filter(|line| !line.trim().is_empty()).count();
             if count > 0 {
-                return Some(format!("{} ({} pkgs)", label, count));
+                return Some((label, count));
             }
         }
     }
     None
 }
 
-fn detect_temperature() -> Option<String> {
+/// Average thermal sensor reading in degrees Celsius.
+fn detect_temperature() -> Option<f64> {
     #[cfg(target_os = "linux")]
     {
         use std::fs;
@@ -247,8 +268,7 @@ fn detect_temperature() -> Option<String> {
             }
         }
         if !temps.is_empty() {
-            let avg = temps.iter().sum::<f64>() / temps.len() as f64;
-            return Some(format!("{:.1}°C", avg));
+            return Some(temps.iter().sum::<f64>() / temps.len() as f64);
         }
     }
     None
@@ -430,6 +450,7 @@ pub const INFO_FIELD_KEYS: &[&str] = &[
     "host",
     "kernel",
     "uptime",
+    "boot",
     "shell",
     "terminal",
     "cpu",
@@ -442,10 +463,220 @@ pub const INFO_FIELD_KEYS: &[&str] = &[
     "memory",
     "swap",
     "disk",
+    "storage",
     "network",
     "locale",
+    "users",
+    "last_login",
+    "containers",
+    "git",
 ];
 
+/// Placeholders each field offers to value templates, besides `{value}` (the
+/// default rendering). Fields missing here come preformatted from their probe
+/// and only honor the separator.
+pub const FIELD_PLACEHOLDERS: &[(&str, &[&str])] = &[
+    ("os", &["name", "version", "arch"]),
+    ("host", &["model"]),
+    (
+        "kernel",
+        &[
+            "release",
+            "flavor",
+            "preemption",
+            "build_date",
+            "taint",
+            "modules",
+        ],
+    ),
+    (
+        "uptime",
+        &["days", "hours", "minutes", "total_hours", "seconds"],
+    ),
+    (
+        "boot",
+        &["time", "timestamp", "firmware", "secure_boot", "bootloader"],
+    ),
+    ("shell", &["name", "path"]),
+    ("terminal", &["name"]),
+    ("cpu", &["model", "cores", "arch", "ghz"]),
+    ("cores", &["physical", "logical"]),
+    ("gpu", &["name"]),
+    ("resolution", &[]),
+    // Extra `--kernel-details` lines; only their label and `{value}`.
+    ("kernel_build", &[]),
+    ("kernel_taint", &[]),
+    ("kernel_modules", &[]),
+    ("battery", &["percent", "status"]),
+    ("packages", &["manager", "count"]),
+    ("temperature", &["celsius"]),
+    (
+        "memory",
+        &[
+            "used",
+            "total",
+            "used_gib",
+            "total_gib",
+            "percent",
+            "used_bytes",
+            "total_bytes",
+        ],
+    ),
+    (
+        "swap",
+        &[
+            "used",
+            "total",
+            "used_gib",
+            "total_gib",
+            "percent",
+            "used_bytes",
+            "total_bytes",
+        ],
+    ),
+    (
+        "disk",
+        &[
+            "mount",
+            "used",
+            "total",
+            "used_gib",
+            "total_gib",
+            "percent",
+            "used_bytes",
+            "total_bytes",
+        ],
+    ),
+    (
+        "storage",
+        &["name", "model", "size", "size_bytes", "kind", "celsius"],
+    ),
+    ("network", &["interface", "ip"]),
+    ("locale", &["lang"]),
+    ("users", &["count", "sessions", "names"]),
+    ("last_login", &["time", "timestamp", "tty", "host"]),
+    ("containers", &["running", "total", "images", "engine"]),
+    (
+        "git",
+        &[
+            "repo",
+            "branch",
+            "ahead",
+            "behind",
+            "modified",
+            "last_commit",
+            "last_commit_time",
+        ],
+    ),
+];
+
+/// Placeholders for `key`, or `None` when the field cannot be templated.
+/// Custom fields only offer `{value}`.
+pub fn field_placeholders(
+    key: &str,
+    custom_fields: &[CustomField],
+) -> Option<&'static [&'static str]> {
+    FIELD_PLACEHOLDERS
+        .iter()
+        .find(|(name, _)| *name == key)
+        .map(|(_, placeholders)| *placeholders)
+        .or_else(|| {
+            custom_fields
+                .iter()
+                .any(|field| field.key == key)
+                .then_some(&[][..])
+        })
+}
+
+/// Label, separator and value-template overrides from config.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FieldFormat {
+    pub separator: Option<String>,
+    pub labels: Vec<(String, String)>,
+    pub templates: Vec<(String, Template)>,
+}
+
+impl FieldFormat {
+    pub(super) fn separator(&self) -> &str {
+        self.separator.as_deref().unwrap_or(": ")
+    }
+}
+
+/// Config overrides plus the display language and decimal mark, as used while
+/// building one set of field lines.
+struct LineFormat<'a> {
+    overrides: &'a FieldFormat,
+    lang: Lang,
+    decimal_separator: char,
+}
+
+impl LineFormat<'_> {
+    /// Build a field whose line is `label<separator>value`, rendering the
+    /// configured template for `key` when the field has typed `values`
+    /// (unknown readings keep their plain text).
+    fn field(
+        &self,
+        key: &'static str,
+        label: &str,
+        value: String,
+        values: Vec<(&'static str, TemplateValue)>,
+    ) -> SystemInfoField {
+        let line = self.line(key, label, value, &values);
+        SystemInfoField { key, line, values }
+    }
+
+    fn line(
+        &self,
+        key: &str,
+        label: &str,
+        value: String,
+        values: &[(&'static str, TemplateValue)],
+    ) -> String {
+        let label = match self.overrides.labels.iter().find(|(name, _)| name == key) {
+            Some((_, label)) => label.clone(),
+            None => translate_label(self.lang, label),
+        };
+        let value = match self
+            .overrides
+            .templates
+            .iter()
+            .find(|(name, _)| name == key)
+        {
+            Some((_, template)) if !values.is_empty() || key_is_value_only(key) => {
+                let mut values = values.to_vec();
+                values.push(("value", TemplateValue::Text(value)));
+                template.render(&values, self.decimal_separator)
+            }
+            _ => value,
+        };
+        format!("{}{}{}", label, self.overrides.separator(), value)
+    }
+
+    fn tr<'m>(&self, message: &'m str) -> &'m str {
+        self.lang.tr(message)
+    }
+}
+
+/// Translate a label, keeping a trailing ` (detail)` such as the mount point
+/// in `Disk (/)` as is.
+fn translate_label(lang: Lang, label: &str) -> String {
+    match label.split_once(" (") {
+        Some((name, detail)) => format!("{} ({}", lang.tr(name), detail),
+        None => lang.tr(label).to_string(),
+    }
+}
+
+fn key_is_value_only(key: &str) -> bool {
+    FIELD_PLACEHOLDERS
+        .iter()
+        .find(|(name, _)| *name == key)
+        .is_none_or(|(_, placeholders)| placeholders.is_empty())
+}
+
+/// Fields that are skipped by default (they talk to daemons or are only useful
+/// in specific setups) and only appear when listed in `--show`.
+pub const OPT_IN_FIELD_KEYS: &[&str] = &["containers", "git"];
+
 #[derive(Clone, Debug)]
 pub enum InfoFieldSelection {
     All,
@@ -455,6 +686,16 @@ pub enum InfoFieldSelection {
 
 impl InfoFieldSelection {
     fn includes(&self, key: &str) -> bool {
+        match self {
+            Self::All => !OPT_IN_FIELD_KEYS.contains(&key),
+            Self::Show(keys) => keys.contains(&key),
+            Self::Hide(keys) => !OPT_IN_FIELD_KEYS.contains(&key) && !keys.contains(&key),
+        }
+    }
+
+    /// Like `includes`, for fields already present in a snapshot: opt-in
+    /// fields were opted into when the snapshot was taken.
+    fn includes_recorded(&self, key: &str) -> bool {
         match self {
             Self::All => true,
             Self::Show(keys) => keys.contains(&key),
@@ -469,6 +710,15 @@ pub struct SystemInfoOptions {
     pub fields: InfoFieldSelection,
     pub logo_override: Option<Vec<String>>,
     pub distro_id: Option<String>,
+    pub kernel_details: bool,
+    pub custom_fields: Vec<CustomField>,
+    pub field_format: FieldFormat,
+    pub byte_units: ByteUnits,
+    pub uptime_style: UptimeStyle,
+    pub lang: Lang,
+    pub decimal_separator: char,
+    pub snapshot: Option<Snapshot>,
+    pub layout: Layout,
 }
 
 impl SystemInfoOptions {
@@ -478,6 +728,15 @@ impl SystemInfoOptions {
             fields,
             logo_override: None,
             distro_id: None,
+            kernel_details: false,
+            custom_fields: Vec::new(),
+            field_format: FieldFormat::default(),
+            byte_units: ByteUnits::default(),
+            uptime_style: UptimeStyle::default(),
+            lang: Lang::default(),
+            decimal_separator: '.',
+            snapshot: None,
+            layout: Layout::default(),
         }
     }
 
@@ -490,16 +749,121 @@ impl SystemInfoOptions {
         self.distro_id = distro_id;
         self
     }
+
+    pub fn with_kernel_details(mut self, kernel_details: bool) -> Self {
+        self.kernel_details = kernel_details;
+        self
+    }
+
+    pub fn with_custom_fields(mut self, custom_fields: Vec<CustomField>) -> Self {
+        self.custom_fields = custom_fields;
+        self
+    }
+
+    pub fn with_field_format(mut self, field_format: FieldFormat) -> Self {
+        self.field_format = field_format;
+        self
+    }
+
+    pub fn with_byte_units(mut self, byte_units: ByteUnits) -> Self {
+        self.byte_units = byte_units;
+        self
+    }
+
+    pub fn with_uptime_style(mut self, uptime_style: UptimeStyle) -> Self {
+        self.uptime_style = uptime_style;
+        self
+    }
+
+    pub fn with_lang(mut self, lang: Lang) -> Self {
+        self.lang = lang;
+        self
+    }
+
+    pub fn with_decimal_separator(mut self, decimal_separator: char) -> Self {
+        self.decimal_separator = decimal_separator;
+        self
+    }
+
+    /// English labels and `.` decimals, for `--json` and `--format` output
+    /// that other programs parse.
+    pub fn locale_neutral(&self) -> Self {
+        self.clone().with_lang(Lang::En).with_decimal_separator('.')
+    }
+
+    /// Render the fields of a saved `--json` snapshot instead of probing.
+    pub fn with_snapshot(mut self, snapshot: Option<Snapshot>) -> Self {
+        self.snapshot = snapshot;
+        self
+    }
+
+    pub fn with_layout(mut self, layout: Layout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Distro id to record in machine output: `--distro`, else the snapshot's,
+    /// else the local one.
+    pub fn reported_distro_id(&self) -> Option<String> {
+        match &self.snapshot {
+            Some(_) => self.logo_distro_id().map(str::to_string),
+            None => self.distro_id.clone().or_else(detect_distro_id),
+        }
+    }
+
+    /// Hostname to record in machine output: the snapshot's, else the local
+    /// one.
+    pub fn reported_hostname(&self) -> Option<String> {
+        match &self.snapshot {
+            Some(snapshot) => snapshot.hostname.clone(),
+            None => System::host_name(),
+        }
+    }
+
+    /// `--distro` wins over the id recorded in a snapshot.
+    fn logo_distro_id(&self) -> Option<&str> {
+        self.distro_id.as_deref().or_else(|| {
+            self.snapshot
+                .as_ref()
+                .and_then(|snapshot| snapshot.distro_id.as_deref())
+        })
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct SystemInfoField {
     pub key: &'static str,
     pub line: String,
+    /// Typed readings behind `line` (the template placeholders), for machine
+    /// output formats. Empty for preformatted probe lines and unknowns.
+    pub values: Vec<(&'static str, TemplateValue)>,
+}
+
+impl SystemInfoField {
+    pub fn new(key: &'static str, line: String) -> Self {
+        Self {
+            key,
+            line,
+            values: Vec::new(),
+        }
+    }
+
+    pub fn value(&self, name: &str) -> Option<&TemplateValue> {
+        self.values
+            .iter()
+            .find(|(value_name, _)| *value_name == name)
+            .map(|(_, value)| value)
+    }
 }
 
-pub fn info_field_key(input: &str) -> Option<&'static str> {
-    INFO_FIELD_KEYS.iter().copied().find(|key| *key == input)
+/// Resolve a user-supplied key against the built-in fields and the custom
+/// fields defined in config.
+pub fn info_field_key(input: &str, custom_fields: &[CustomField]) -> Option<&'static str> {
+    INFO_FIELD_KEYS
+        .iter()
+        .copied()
+        .chain(custom_fields.iter().map(|field| field.key))
+        .find(|key| *key == input)
 }
 
 fn order_fields(
@@ -516,32 +880,105 @@ fn order_fields(
 }
 
 pub fn generate_system_info_fields(options: &SystemInfoOptions) -> Vec<SystemInfoField> {
+    generate_system_info_report(options).fields
+}
+
+/// Fields plus how each background probe went, in the order they were joined.
+pub struct SystemInfoReport {
+    pub fields: Vec<SystemInfoField>,
+    pub probes: Vec<ProbeReport>,
+}
+
+pub fn generate_system_info_report(options: &SystemInfoOptions) -> SystemInfoReport {
     let selection = &options.fields;
+    if let Some(snapshot) = &options.snapshot {
+        let fields = snapshot
+            .fields
+            .iter()
+            .filter(|field| selection.includes_recorded(field.key))
+            .cloned()
+            .collect();
+        return SystemInfoReport {
+            fields: order_fields(fields, selection),
+            probes: Vec::new(),
+        };
+    }
+    let format = LineFormat {
+        overrides: &options.field_format,
+        lang: options.lang,
+        decimal_separator: options.decimal_separator,
+    };
+    let units = ByteUnits {
+        decimal_separator: options.decimal_separator,
+        ..options.byte_units
+    };
 
     let host_handle = selection
         .includes("host")
-        .then(|| thread::spawn(detect_host_model));
+        .then(|| spawn_probe("host", detect_host_model));
     let gpu_handle = (selection.includes("gpu") || selection.includes("resolution"))
-        .then(|| thread::spawn(detect_gpu_and_resolution));
+        .then(|| spawn_probe("gpu", detect_gpu_and_resolution));
     let battery_handle = selection
         .includes("battery")
-        .then(|| thread::spawn(detect_battery));
+        .then(|| spawn_probe("battery", detect_battery));
     let pkg_handle = selection
         .includes("packages")
-        .then(|| thread::spawn(detect_pkg_count));
+        .then(|| spawn_probe("packages", detect_pkg_count));
     let temp_handle = selection
         .includes("temperature")
-        .then(|| thread::spawn(detect_temperature));
+        .then(|| spawn_probe("temperature", detect_temperature));
+    let decimal_separator = options.decimal_separator;
+    let kernel_handle = (selection.includes("kernel") && options.kernel_details)
+        .then(|| spawn_probe("kernel_details", detect_kernel_details));
+    let boot_handle = selection
+        .includes("boot")
+        .then(|| spawn_probe("boot", detect_boot));
+    let users_handle = selection
+        .includes("users")
+        .then(|| spawn_probe("users", detect_users));
+    let last_login_handle = selection
+        .includes("last_login")
+        .then(|| spawn_probe("last_login", detect_last_login));
+    let containers_handle = selection
+        .includes("containers")
+        .then(|| spawn_probe("containers", detect_containers));
+    let git_handle = selection
+        .includes("git")
+        .then(|| spawn_probe("git", detect_git));
+    let storage_handle = selection
+        .includes("storage")
+        .then(|| spawn_probe("storage", detect_storage));
+    let custom_handles: Vec<_> = options
+        .custom_fields
+        .iter()
+        .filter(|field| selection.includes(field.key))
+        .map(|field| {
+            let field = field.clone();
+            (
+                field.key,
+                field.label.clone(),
+                spawn_probe(field.key, move || detect_custom_field(&field)),
+            )
+        })
+        .collect();
 
     let needs_sys = selection.includes("cpu")
         || selection.includes("cores")
         || selection.includes("memory")
         || selection.includes("swap");
+    let mut probes = Vec::new();
     let sys = needs_sys.then(|| {
+        let started = Instant::now();
         let sys_refreshes = RefreshKind::nothing()
             .with_cpu(CpuRefreshKind::nothing().with_frequency())
             .with_memory(MemoryRefreshKind::everything());
-        System::new_with_specifics(sys_refreshes)
+        let sys = System::new_with_specifics(sys_refreshes);
+        probes.push(ProbeReport {
+            name: "system",
+            duration: started.elapsed(),
+            status: ProbeStatus::Ok,
+        });
+        sys
     });
 
     let mut fields = Vec::new();
@@ -551,77 +988,128 @@ pub fn generate_system_info_fields(options: &SystemInfoOptions) -> Vec<SystemInf
             .or_else(|_| env::var("USERNAME"))
             .unwrap_or_else(|_| "user".to_string());
         let hostname = System::host_name().unwrap_or_else(|| "hostname".to_string());
-        fields.push(SystemInfoField {
-            key: "header",
-            line: format!("{}@{}\n-------", username, hostname),
-        });
+        fields.push(SystemInfoField::new(
+            "header",
+            format!("{}@{}\n-------", username, hostname),
+        ));
     }
 
     if selection.includes("os") {
-        let line = if let Some(os_name) = System::name() {
-            if let Some(os_version) = System::os_version() {
-                format!(
-                    "OS: {} {} ({})",
-                    os_name,
-                    os_version,
-                    std::env::consts::ARCH
-                )
-            } else {
-                format!("OS: {} ({})", os_name, std::env::consts::ARCH)
-            }
+        let arch = std::env::consts::ARCH;
+        let field = if let Some(os_name) = System::name() {
+            let os_version = System::os_version();
+            let value = match &os_version {
+                Some(os_version) => format!("{} {} ({})", os_name, os_version, arch),
+                None => format!("{} ({})", os_name, arch),
+            };
+            format.field(
+                "os",
+                "OS",
+                value,
+                vec![
+                    ("name", os_name.into()),
+                    ("version", os_version.unwrap_or_default().into()),
+                    ("arch", arch.into()),
+                ],
+            )
         } else {
-            "OS: Unknown".to_string()
+            format.field("os", "OS", format.tr("Unknown").to_string(), Vec::new())
         };
-        fields.push(SystemInfoField { key: "os", line });
+        fields.push(field);
     }
 
     if selection.includes("host") {
-        let host_line = host_handle
-            .and_then(|handle| handle.join().ok())
+        let field = match host_handle
+            .and_then(|probe| probe.join(&mut probes))
             .flatten()
-            .unwrap_or_else(|| "Unknown".to_string());
-        fields.push(SystemInfoField {
-            key: "host",
-            line: format!("Host: {}", host_line),
-        });
+        {
+            Some(model) => {
+                format.field("host", "Host", model.clone(), vec![("model", model.into())])
+            }
+            None => format.field("host", "Host", format.tr("Unknown").to_string(), Vec::new()),
+        };
+        fields.push(field);
     }
 
-    if selection.includes("kernel")
-        && let Some(kernel_version) = System::kernel_version()
-    {
-        fields.push(SystemInfoField {
-            key: "kernel",
-            line: format!("Kernel: {}", kernel_version),
-        });
+    if selection.includes("kernel") {
+        let details = if options.kernel_details {
+            kernel_handle
+                .and_then(|probe| probe.join(&mut probes))
+                .flatten()
+        } else {
+            None
+        };
+        let release =
+            System::kernel_version().or_else(|| details.as_ref().and_then(|d| d.release.clone()));
+        if let Some(kernel_version) = release {
+            let mut values = vec![("release", kernel_version.as_str().into())];
+            if let Some(details) = &details {
+                values.extend(kernel_detail_values(details));
+            }
+            let mut field = format.field("kernel", "Kernel", kernel_version, values);
+            if let Some(details) = &details {
+                for (key, label, value) in format_kernel_detail_lines(details, options.lang) {
+                    field.line.push('\n');
+                    field.line.push_str(&format.line(key, label, value, &[]));
+                }
+            }
+            fields.push(field);
+        }
     }
 
     if selection.includes("uptime") {
         let uptime = detect_uptime_secs();
         let hours = uptime / 3600;
         let minutes = (uptime % 3600) / 60;
-        fields.push(SystemInfoField {
-            key: "uptime",
-            line: format!("Uptime: {} hours, {} mins", hours, minutes),
-        });
+        fields.push(format.field(
+            "uptime",
+            "Uptime",
+            format_uptime(uptime, options.uptime_style, options.lang),
+            vec![
+                ("days", (uptime / 86_400).into()),
+                ("hours", (hours % 24).into()),
+                ("minutes", minutes.into()),
+                ("total_hours", hours.into()),
+                ("seconds", uptime.into()),
+            ],
+        ));
+    }
+
+    if selection.includes("boot")
+        && let Some(boot) = boot_handle
+            .and_then(|probe| probe.join(&mut probes))
+            .flatten()
+    {
+        let time = boot.boot_time.map(format_local_timestamp);
+        fields.push(format.field(
+            "boot",
+            "Boot",
+            format_boot_value(&boot, time.as_deref(), options.lang),
+            boot_values(&boot, time.as_deref()),
+        ));
     }
 
     if selection.includes("shell") {
-        let shell = env::var("SHELL").unwrap_or_else(|_| "unknown".to_string());
-        let shell_name = shell.split('/').next_back().unwrap_or("unknown");
-        fields.push(SystemInfoField {
-            key: "shell",
-            line: format!("Shell: {}", shell_name),
-        });
+        let shell = env::var("SHELL").unwrap_or_else(|_| format.tr("unknown").to_string());
+        let shell_name = shell.split('/').next_back().unwrap_or_default();
+        fields.push(format.field(
+            "shell",
+            "Shell",
+            shell_name.to_string(),
+            vec![("name", shell_name.into()), ("path", shell.as_str().into())],
+        ));
     }
 
     if selection.includes("terminal") {
         let terminal = env::var("TERM_PROGRAM")
             .or_else(|_| env::var("TERMINAL"))
-            .unwrap_or_else(|_| "unknown".to_string());
-        fields.push(SystemInfoField {
-            key: "terminal",
-            line: format!("Terminal: {}", terminal),
-        });
+            .unwrap_or_else(|_| format.tr("unknown").to_string());
+        fields.push(format.field(
+            "terminal",
+            "Terminal",
+            terminal.clone(),
+            vec![("name", terminal.into())],
+        ));
     }
 
     if let Some(sys) = &sys {
@@ -633,89 +1121,116 @@ pub fn generate_system_info_fields(options: &SystemInfoOptions) -> Vec<SystemInf
                 .find(|c| !c.brand().trim().is_empty())
                 .map(|c| c.brand().to_string())
                 .filter(|s| s.chars().any(|ch| ch.is_alphanumeric()))
-                .unwrap_or_else(|| "Unknown CPU".to_string());
+                .unwrap_or_else(|| format.tr("Unknown CPU").to_string());
             if selection.includes("cpu") {
                 let arch = std::env::consts::ARCH;
                 let freq_ghz = detect_cpu_base_freq_ghz(sys, &brand_primary);
                 let freq_part = freq_ghz
-                    .map(|v| format!(" @ {:.2} GHz", v))
+                    .map(|v| localize_decimal(format!(" @ {:.2} GHz", v), decimal_separator))
                     .unwrap_or_default();
-                fields.push(SystemInfoField {
-                    key: "cpu",
-                    line: format!(
-                        "CPU: {} ({} cores, {}){}",
-                        brand_primary.trim(),
+                let model = brand_primary.trim();
+                fields.push(format.field(
+                    "cpu",
+                    "CPU",
+                    format!(
+                        "{} ({} {}, {}){}",
+                        model,
                         cpu_count,
+                        format.tr("cores"),
                         arch,
                         freq_part
                     ),
-                });
+                    vec![
+                        ("model", model.into()),
+                        ("cores", cpu_count.into()),
+                        ("arch", arch.into()),
+                        ("ghz", freq_ghz.unwrap_or(0.0).into()),
+                    ],
+                ));
             }
             if selection.includes("cores") {
-                let line = if let Some(phys) = System::physical_core_count() {
-                    if phys != cpu_count {
-                        format!("Cores: {} physical / {} logical", phys, cpu_count)
-                    } else {
-                        format!("Cores: {} logical", cpu_count)
+                let physical = System::physical_core_count();
+                let value = match physical {
+                    Some(phys) if phys != cpu_count => {
+                        format!(
+                            "{} {} / {} {}",
+                            phys,
+                            format.tr("physical"),
+                            cpu_count,
+                            format.tr("logical")
+                        )
                     }
-                } else {
-                    format!("Cores: {} logical", cpu_count)
+                    _ => format!("{} {}", cpu_count, format.tr("logical")),
                 };
-                fields.push(SystemInfoField { key: "cores", line });
+                let field = format.field(
+                    "cores",
+                    "Cores",
+                    value,
+                    vec![
+                        ("physical", physical.unwrap_or(cpu_count).into()),
+                        ("logical", cpu_count.into()),
+                    ],
+                );
+                fields.push(field);
             }
         } else if selection.includes("cpu") {
-            fields.push(SystemInfoField {
-                key: "cpu",
-                line: "CPU: Unknown".to_string(),
-            });
+            fields.push(format.field("cpu", "CPU", format.tr("Unknown").to_string(), Vec::new()));
         }
     }
 
-    if let Some(handle) = gpu_handle {
-        let (gpu_info, resolution) = handle.join().ok().unwrap_or((None, None));
+    if let Some(probe) = gpu_handle {
+        let (gpu_info, resolution) = probe.join(&mut probes).unwrap_or((None, None));
         if selection.includes("gpu") {
-            fields.push(SystemInfoField {
-                key: "gpu",
-                line: format!("GPU: {}", gpu_info.unwrap_or_else(|| "Unknown".to_string())),
-            });
+            let field = match gpu_info {
+                Some(name) => format.field("gpu", "GPU", name.clone(), vec![("name", name.into())]),
+                None => format.field("gpu", "GPU", format.tr("Unknown").to_string(), Vec::new()),
+            };
+            fields.push(field);
         }
         if selection.includes("resolution")
             && let Some(res) = resolution
         {
-            fields.push(SystemInfoField {
-                key: "resolution",
-                line: format!("Resolution: {}", res),
-            });
+            fields.push(format.field("resolution", "Resolution", res, Vec::new()));
         }
     }
 
     if selection.includes("battery")
-        && let Some(batt) = battery_handle
-            .and_then(|handle| handle.join().ok())
+        && let Some((percent, status)) = battery_handle
+            .and_then(|probe| probe.join(&mut probes))
             .flatten()
     {
-        fields.push(SystemInfoField {
-            key: "battery",
-            line: format!("Battery: {}", batt),
-        });
+        fields.push(format.field(
+            "battery",
+            "Battery",
+            format!("{}% {}", percent, status),
+            vec![("percent", percent.into()), ("status", status.into())],
+        ));
     }
 
     if selection.includes("packages")
-        && let Some(pkgs) = pkg_handle.and_then(|handle| handle.join().ok()).flatten()
+        && let Some((manager, count)) = pkg_handle
+            .and_then(|probe| probe.join(&mut probes))
+            .flatten()
     {
-        fields.push(SystemInfoField {
-            key: "packages",
-            line: format!("Packages: {}", pkgs),
-        });
+        fields.push(format.field(
+            "packages",
+            "Packages",
+            format!("{} ({} {})", manager, count, format.tr("pkgs")),
+            vec![("manager", manager.into()), ("count", count.into())],
+        ));
     }
 
     if selection.includes("temperature")
-        && let Some(temp) = temp_handle.and_then(|handle| handle.join().ok()).flatten()
+        && let Some(temp) = temp_handle
+            .and_then(|probe| probe.join(&mut probes))
+            .flatten()
     {
-        fields.push(SystemInfoField {
-            key: "temperature",
-            line: format!("Temp: {}", temp),
-        });
+        fields.push(format.field(
+            "temperature",
+            "Temp",
+            localize_decimal(format!("{:.1}°C", temp), decimal_separator),
+            vec![("celsius", temp.into())],
+        ));
     }
 
     if selection.includes("memory")
@@ -728,37 +1243,68 @@ pub fn generate_system_info_fields(options: &SystemInfoOptions) -> Vec<SystemInf
         } else {
             0
         };
-        fields.push(SystemInfoField {
-            key: "memory",
-            line: format!(
-                "Memory: {:.2} GiB / {:.2} GiB ({}%)",
-                used_memory as f64 / 1024.0 / 1024.0 / 1024.0,
-                total_memory as f64 / 1024.0 / 1024.0 / 1024.0,
+        let used_gib = used_memory as f64 / 1024.0 / 1024.0 / 1024.0;
+        let total_gib = total_memory as f64 / 1024.0 / 1024.0 / 1024.0;
+        fields.push(format.field(
+            "memory",
+            "Memory",
+            format!(
+                "{} / {} ({}%)",
+                units.format(used_memory),
+                units.format(total_memory),
                 memory_percent
             ),
-        });
+            vec![
+                ("used", units.format(used_memory).into()),
+                ("total", units.format(total_memory).into()),
+                ("used_gib", used_gib.into()),
+                ("total_gib", total_gib.into()),
+                ("percent", memory_percent.into()),
+                ("used_bytes", used_memory.into()),
+                ("total_bytes", total_memory.into()),
+            ],
+        ));
     }
 
     if selection.includes("swap")
         && let Some(sys) = &sys
     {
         let total_swap = sys.total_swap();
-        let line = if total_swap > 0 {
+        let field = if total_swap > 0 {
             let used_swap = sys.used_swap();
-            format!(
-                "Swap: {:.2} GiB / {:.2} GiB",
-                used_swap as f64 / 1024.0 / 1024.0 / 1024.0,
-                total_swap as f64 / 1024.0 / 1024.0 / 1024.0
+            let used_gib = used_swap as f64 / 1024.0 / 1024.0 / 1024.0;
+            let total_gib = total_swap as f64 / 1024.0 / 1024.0 / 1024.0;
+            format.field(
+                "swap",
+                "Swap",
+                format!("{} / {}", units.format(used_swap), units.format(total_swap)),
+                vec![
+                    ("used", units.format(used_swap).into()),
+                    ("total", units.format(total_swap).into()),
+                    ("used_gib", used_gib.into()),
+                    ("total_gib", total_gib.into()),
+                    (
+                        "percent",
+                        ((used_swap as f64 / total_swap as f64 * 100.0) as u32).into(),
+                    ),
+                    ("used_bytes", used_swap.into()),
+                    ("total_bytes", total_swap.into()),
+                ],
             )
         } else {
-            "Swap: Disabled".to_string()
+            format.field(
+                "swap",
+                "Swap",
+                format.tr("Disabled").to_string(),
+                Vec::new(),
+            )
         };
-        fields.push(SystemInfoField { key: "swap", line });
+        fields.push(field);
     }
 
     if selection.includes("disk") {
         let disks = Disks::new_with_refreshed_list();
-        let line = if let Some(disk) = disks
+        let field = if let Some(disk) = disks
             .iter()
             .find(|d| {
                 let mount = d.mount_point().to_string_lossy();
@@ -776,17 +1322,54 @@ pub fn generate_system_info_fields(options: &SystemInfoOptions) -> Vec<SystemInf
             } else {
                 0
             };
-            format!(
-                "Disk ({}): {:.2} GiB / {:.2} GiB ({}%)",
-                disk.mount_point().to_string_lossy(),
-                used_gib,
-                total_gib,
-                pct
+            let mount = disk.mount_point().to_string_lossy().into_owned();
+            format.field(
+                "disk",
+                &format!("Disk ({})", mount),
+                format!(
+                    "{} / {} ({}%)",
+                    units.format(used as u64),
+                    units.format(total as u64),
+                    pct
+                ),
+                vec![
+                    ("mount", mount.into()),
+                    ("used", units.format(used as u64).into()),
+                    ("total", units.format(total as u64).into()),
+                    ("used_gib", used_gib.into()),
+                    ("total_gib", total_gib.into()),
+                    ("percent", pct.into()),
+                    ("used_bytes", (used as u64).into()),
+                    ("total_bytes", (total as u64).into()),
+                ],
             )
         } else {
-            "Disk: Unknown".to_string()
+            format.field("disk", "Disk", format.tr("Unknown").to_string(), Vec::new())
         };
-        fields.push(SystemInfoField { key: "disk", line });
+        fields.push(field);
+    }
+
+    if selection.includes("storage")
+        && let Some(storage) = storage_handle
+            .and_then(|probe| probe.join(&mut probes))
+            .flatten()
+    {
+        let lines: Vec<String> = storage
+            .iter()
+            .map(|device| {
+                format.line(
+                    "storage",
+                    &format!("Storage ({})", device.name),
+                    format_storage_value(device, units, options.lang),
+                    &storage_values(device, units),
+                )
+            })
+            .collect();
+        fields.push(SystemInfoField {
+            key: "storage",
+            line: lines.join("\n"),
+            values: storage_device_values(&storage, units),
+        });
     }
 
     if selection.includes("network") {
@@ -801,35 +1384,117 @@ pub fn generate_system_info_fields(options: &SystemInfoOptions) -> Vec<SystemInf
                 }
             })
         });
-        let line = if let Some((name, ip)) = local_ip {
-            format!("Local IP ({}): {}", name, ip)
+        let field = if let Some((name, ip)) = local_ip {
+            format.field(
+                "network",
+                &format!("Local IP ({})", name),
+                ip.to_string(),
+                vec![("interface", name.into()), ("ip", ip.to_string().into())],
+            )
         } else {
-            "Local IP: Unknown".to_string()
+            format.field(
+                "network",
+                "Local IP",
+                format.tr("Unknown").to_string(),
+                Vec::new(),
+            )
         };
-        fields.push(SystemInfoField {
-            key: "network",
-            line,
-        });
+        fields.push(field);
     }
 
     if selection.includes("locale") {
         let locale = env::var("LANG").unwrap_or_else(|_| "C".to_string());
-        fields.push(SystemInfoField {
-            key: "locale",
-            line: format!("Locale: {}", locale),
-        });
+        fields.push(format.field(
+            "locale",
+            "Locale",
+            locale.clone(),
+            vec![("lang", locale.into())],
+        ));
+    }
+
+    if selection.includes("users")
+        && let Some(users) = users_handle
+            .and_then(|probe| probe.join(&mut probes))
+            .flatten()
+    {
+        fields.push(format.field(
+            "users",
+            "Users",
+            format_users_value(&users, options.lang),
+            users_values(&users),
+        ));
+    }
+
+    if selection.includes("last_login")
+        && let Some(last_login) = last_login_handle
+            .and_then(|probe| probe.join(&mut probes))
+            .flatten()
+    {
+        let time = format_local_timestamp(last_login.time);
+        fields.push(format.field(
+            "last_login",
+            "Last Login",
+            format_last_login_value(&last_login, &time, options.lang),
+            last_login_values(&last_login, &time),
+        ));
+    }
+
+    if selection.includes("containers")
+        && let Some(containers) = containers_handle
+            .and_then(|probe| probe.join(&mut probes))
+            .and_then(Result::ok)
+            .flatten()
+    {
+        fields.push(format.field(
+            "containers",
+            "Containers",
+            format_containers_value(&containers, options.lang),
+            containers_values(&containers),
+        ));
+    }
+
+    if selection.includes("git")
+        && let Some(git) = git_handle
+            .and_then(|probe| probe.join(&mut probes))
+            .flatten()
+    {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        fields.push(format.field(
+            "git",
+            "Git",
+            format_git_value(&git, now, options.lang),
+            git_values(&git, now, options.lang),
+        ));
+    }
+
+    for (key, label, probe) in custom_handles {
+        if let Some(Ok(Some(value))) = probe.join(&mut probes) {
+            fields.push(format.field(key, &label, value, Vec::new()));
+        }
     }
 
-    order_fields(fields, selection)
+    SystemInfoReport {
+        fields: order_fields(fields, selection),
+        probes,
+    }
+}
+
+/// The printed logo-and-info lines, and where the logo landed in them.
+pub fn arrange_system_info(options: &SystemInfoOptions) -> Arrangement {
+    options.layout.arrange(&system_info_blocks(options))
 }
 
-pub fn generate_system_info(options: &SystemInfoOptions) -> Vec<String> {
+/// Logo and info lines, ready for [`Layout::arrange`].
+pub fn system_info_blocks(options: &SystemInfoOptions) -> Blocks {
     let fields = generate_system_info_fields(options);
 
-    let mut logo_lines: Vec<String> = if options.show_logo {
+    let mut logo: Vec<String> = if options.show_logo {
         match options.logo_override.as_deref() {
             Some(lines) => lines.to_vec(),
-            None => ascii_logo_with_distro(options.distro_id.as_deref())
+            None => ascii_logo_with_distro(options.logo_distro_id())
                 .into_iter()
                 .map(|s| s.to_string())
                 .collect(),
@@ -838,80 +1503,36 @@ pub fn generate_system_info(options: &SystemInfoOptions) -> Vec<String> {
         Vec::new()
     };
     if options.logo_override.is_some() {
-        pad_custom_logo_lines(&mut logo_lines);
+        pad_custom_logo_lines(&mut logo);
     }
-    let show_logo = options.show_logo && !logo_lines.is_empty();
-
-    let mut result = Vec::new();
-    let header_first = fields.first().is_some_and(|field| field.key == "header");
-    let info_lines = flatten_info_lines(&fields, show_logo && header_first);
-    let info_offset = if show_logo && header_first { 2 } else { 0 };
-    let max_lines = if show_logo {
-        logo_lines.len().max(info_lines.len() + info_offset)
+    let small_logo = if options.show_logo && options.logo_override.is_none() {
+        ascii_logo_small_with_distro(options.logo_distro_id())
+            .into_iter()
+            .map(|s| s.to_string())
+            .collect()
     } else {
-        info_lines.len()
+        Vec::new()
     };
-    for i in 0..max_lines {
-        if show_logo {
-            let ascii_part = if i < logo_lines.len() {
-                &logo_lines[i]
-            } else {
-                "                                  "
-            };
-            let info_part = if i >= info_offset && i - info_offset < info_lines.len() {
-                &info_lines[i - info_offset]
-            } else {
-                ""
-            };
-            result.push(format!("{}{}", ascii_part, info_part));
-        } else {
-            let info_part = if i < info_lines.len() {
-                &info_lines[i]
-            } else {
-                ""
-            };
-            result.push(info_part.to_string());
-        }
-    }
-    result
-}
 
-fn flatten_info_lines(
-    fields: &[SystemInfoField],
-    suppress_logo_header_divider: bool,
-) -> Vec<String> {
-    let mut lines = Vec::new();
-    for field in fields {
-        if suppress_logo_header_divider && field.key == "header" {
-            if let Some(header) = field.line.lines().next() {
-                lines.push(header.to_string());
-            }
-        } else {
-            lines.extend(field.line.lines().map(str::to_string));
-        }
+    let header_rows = fields
+        .first()
+        .filter(|field| field.key == "header")
+        .map_or(0, |field| field.line.lines().count());
+    Blocks {
+        logo,
+        small_logo,
+        info: fields
+            .iter()
+            .flat_map(|field| field.line.lines().map(str::to_string))
+            .collect(),
+        header_rows,
     }
-    lines
 }
 
 fn pad_custom_logo_lines(lines: &mut [String]) {
-    let width = lines
-        .iter()
-        .map(|line| line.chars().count())
-        .max()
-        .unwrap_or(0);
+    let width = lines.iter().map(|line| str_width(line)).max().unwrap_or(0);
     for line in lines {
-        let padding = width.saturating_sub(line.chars().count()) + 2;
+        let padding = width.saturating_sub(str_width(line)) + 2;
         line.push_str(&" ".repeat(padding));
     }
 }
-
-pub fn generate_system_info_json(options: &SystemInfoOptions) -> String {
-    let fields = generate_system_info_fields(options);
-    let mut parts = Vec::with_capacity(fields.len());
-    for field in fields {
-        let key = serde_json::to_string(field.key).unwrap_or_else(|_| "\"\"".to_string());
-        let value = serde_json::to_string(&field.line).unwrap_or_else(|_| "\"\"".to_string());
-        parts.push(format!("{}:{}", key, value));
-    }
-    format!("{{{}}}", parts.join(","))
-}
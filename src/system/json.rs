@@ -0,0 +1,83 @@
+//! Versioned `--json` document. The layout is described by
+//! `schema/neonfetch.schema.json`, which `--print-json-schema` prints.
+
+use super::info::{
+    SystemInfoField, SystemInfoOptions, SystemInfoReport, generate_system_info_report,
+};
+use super::output::display_lines;
+use crate::util::template::TemplateValue;
+use crate::util::time::format_rfc3339_utc;
+use serde_json::{Map, Value, json};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Bumped whenever a field is removed or changes type; additions keep it.
+pub const JSON_SCHEMA_VERSION: u64 = 1;
+
+pub const JSON_SCHEMA: &str = include_str!("../../schema/neonfetch.schema.json");
+
+pub fn generate_system_info_json(options: &SystemInfoOptions, pretty: bool) -> String {
+    let options = &options.locale_neutral();
+    let SystemInfoReport { fields, probes } = generate_system_info_report(options);
+    let separator = options.field_format.separator();
+    let hostname = options.reported_hostname();
+    let generated_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+    let probes: Vec<Value> = probes
+        .iter()
+        .map(|probe| {
+            json!({
+                "name": probe.name,
+                "duration_ms": (probe.duration.as_secs_f64() * 1_000_000.0).round() / 1_000.0,
+                "status": probe.status.name(),
+                "error": probe.status.error(),
+            })
+        })
+        .collect();
+    let document = json!({
+        "schema_version": JSON_SCHEMA_VERSION,
+        "generated_at": format_rfc3339_utc(generated_at),
+        "neonfetch_version": env!("CARGO_PKG_VERSION"),
+        "hostname": hostname,
+        // Record the logo so `--from-json` can show the same one elsewhere.
+        "distro_id": options.reported_distro_id(),
+        "fields": fields.iter().map(|field| field_json(field, separator)).collect::<Vec<_>>(),
+        "probes": probes,
+    });
+    let text = if pretty {
+        serde_json::to_string_pretty(&document)
+    } else {
+        serde_json::to_string(&document)
+    };
+    text.unwrap_or_default()
+}
+
+fn field_json(field: &SystemInfoField, separator: &str) -> Value {
+    let mut object = Map::new();
+    object.insert("key".to_string(), field.key.into());
+    let (label, value) = display_lines(field, separator)
+        .into_iter()
+        .next()
+        .unwrap_or((None, String::new()));
+    if let Some(label) = label {
+        object.insert("label".to_string(), label.into());
+    }
+    object.insert("value".to_string(), value.into());
+    object.insert("text".to_string(), field.line.clone().into());
+    let values: Map<String, Value> = field
+        .values
+        .iter()
+        .map(|(name, value)| {
+            let value = match value {
+                TemplateValue::Text(text) => Value::from(text.clone()),
+                TemplateValue::Integer(number) => Value::from(*number),
+                // Non-finite floats have no JSON form and become null.
+                TemplateValue::Float(number) => Value::from(*number),
+            };
+            (name.to_string(), value)
+        })
+        .collect();
+    object.insert("values".to_string(), Value::Object(values));
+    Value::Object(object)
+}
@@ -0,0 +1,273 @@
+use crate::util::i18n::Lang;
+use crate::util::template::TemplateValue;
+use std::fs;
+use std::path::Path;
+
+/// Taint bits from `Documentation/admin-guide/tainted-kernels.rst`, indexed by
+/// bit number: (flag letter, short reason).
+const TAINT_FLAGS: &[(char, &str)] = &[
+    ('P', "proprietary module"),
+    ('F', "module force loaded"),
+    ('S', "out-of-spec system"),
+    ('R', "module force unloaded"),
+    ('M', "machine check"),
+    ('B', "bad page"),
+    ('U', "user request"),
+    ('D', "kernel died recently"),
+    ('A', "ACPI table overridden"),
+    ('W', "kernel warning"),
+    ('C', "staging driver"),
+    ('I', "firmware workaround"),
+    ('O', "out-of-tree module"),
+    ('E', "unsigned module"),
+    ('L', "soft lockup"),
+    ('K', "live patched"),
+    ('X', "auxiliary"),
+    ('T', "randstruct build"),
+    ('N', "in-kernel test"),
+    ('J', "fwctl debug operation"),
+];
+
+const WEEKDAYS: &[&str] = &["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct KernelDetails {
+    pub release: Option<String>,
+    pub flavor: Option<String>,
+    pub build_date: Option<String>,
+    pub preemption: Option<&'static str>,
+    pub taint: Option<u64>,
+    pub module_count: Option<usize>,
+}
+
+pub fn detect_kernel_details() -> Option<KernelDetails> {
+    cfg!(target_os = "linux").then(|| read_kernel_details(Path::new("/proc")))
+}
+
+/// Collect kernel details from a `/proc`-shaped directory.
+pub fn read_kernel_details(proc_root: &Path) -> KernelDetails {
+    let mut details = fs::read_to_string(proc_root.join("version"))
+        .map(|text| parse_proc_version(&text))
+        .unwrap_or_default();
+    details.taint = fs::read_to_string(proc_root.join("sys/kernel/tainted"))
+        .ok()
+        .and_then(|s| s.trim().parse::<u64>().ok());
+    details.module_count = fs::read_to_string(proc_root.join("modules"))
+        .ok()
+        .map(|text| text.lines().filter(|line| !line.trim().is_empty()).count());
+    details
+}
+
+/// Parse `/proc/version`, e.g.
+/// `Linux version 6.8.0-45-generic (buildd@host) (gcc ...) #45-Ubuntu SMP PREEMPT_DYNAMIC Fri Aug 30 12:02:04 UTC 2024`.
+pub fn parse_proc_version(text: &str) -> KernelDetails {
+    let text = text.trim();
+    let release = text
+        .strip_prefix("Linux version ")
+        .and_then(|rest| rest.split_whitespace().next())
+        .map(str::to_string);
+    let flavor = release.as_deref().and_then(release_flavor);
+
+    // The build banner follows the last "#": "#45-Ubuntu SMP PREEMPT_DYNAMIC <date>".
+    let banner = text.rfind(" #").map(|pos| &text[pos + 1..]).unwrap_or("");
+    let tokens: Vec<&str> = banner.split_whitespace().collect();
+    let preemption = if tokens.contains(&"PREEMPT_RT") {
+        Some("PREEMPT_RT")
+    } else if tokens.contains(&"PREEMPT_DYNAMIC") {
+        Some("PREEMPT_DYNAMIC")
+    } else if tokens.contains(&"PREEMPT") {
+        Some("PREEMPT")
+    } else if banner.is_empty() {
+        None
+    } else {
+        Some("none")
+    };
+    let build_date = tokens
+        .iter()
+        .position(|token| WEEKDAYS.contains(&token.trim_end_matches(',')))
+        .map(|pos| tokens[pos..].join(" "));
+
+    KernelDetails {
+        release,
+        flavor,
+        build_date,
+        preemption,
+        taint: None,
+        module_count: None,
+    }
+}
+
+/// The last dash-separated component of a release that contains a letter,
+/// e.g. `generic` in `6.8.0-45-generic` or `arch1` in `6.10.10-arch1-1`.
+fn release_flavor(release: &str) -> Option<String> {
+    release
+        .split('-')
+        .skip(1)
+        .filter(|part| part.chars().any(|ch| ch.is_ascii_alphabetic()))
+        .last()
+        .map(str::to_string)
+}
+
+/// Decode a `/proc/sys/kernel/tainted` bitmask into (flag, reason) pairs.
+pub fn decode_taint(mask: u64) -> Vec<(char, &'static str)> {
+    let mut reasons: Vec<(char, &'static str)> = TAINT_FLAGS
+        .iter()
+        .enumerate()
+        .filter(|(bit, _)| mask & (1 << bit) != 0)
+        .map(|(_, flag)| *flag)
+        .collect();
+    if mask >> TAINT_FLAGS.len() != 0 {
+        reasons.push(('?', "unknown"));
+    }
+    reasons
+}
+
+/// The lines that follow `Kernel: <release>` in the extended form, as
+/// `(key, label, value)` so `[labels]` can rename them like other fields.
+pub fn format_kernel_detail_lines(
+    details: &KernelDetails,
+    lang: Lang,
+) -> Vec<(&'static str, &'static str, String)> {
+    let mut lines = Vec::new();
+
+    let build: Vec<&str> = [
+        details.flavor.as_deref(),
+        details.preemption,
+        details.build_date.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if !build.is_empty() {
+        lines.push(("kernel_build", "Kernel Build", build.join(", ")));
+    }
+
+    if let Some(mask) = details.taint {
+        let value = if mask == 0 {
+            lang.tr("Not tainted").to_string()
+        } else {
+            let reasons = decode_taint(mask);
+            let flags: String = reasons.iter().map(|(flag, _)| *flag).collect();
+            let names: Vec<&str> = reasons.iter().map(|(_, name)| *name).collect();
+            format!("{} ({})", flags, names.join(", "))
+        };
+        lines.push(("kernel_taint", "Kernel Taint", value));
+    }
+
+    if let Some(count) = details.module_count {
+        lines.push((
+            "kernel_modules",
+            "Kernel Modules",
+            format!("{} {}", count, lang.tr("loaded")),
+        ));
+    }
+
+    lines
+}
+
+/// Extra `kernel` template placeholders from `--kernel-details`.
+pub fn kernel_detail_values(details: &KernelDetails) -> Vec<(&'static str, TemplateValue)> {
+    let mut values = Vec::new();
+    if let Some(flavor) = &details.flavor {
+        values.push(("flavor", flavor.as_str().into()));
+    }
+    if let Some(preemption) = details.preemption {
+        values.push(("preemption", preemption.into()));
+    }
+    if let Some(date) = &details.build_date {
+        values.push(("build_date", date.as_str().into()));
+    }
+    if let Some(mask) = details.taint {
+        values.push(("taint", mask.into()));
+    }
+    if let Some(count) = details.module_count {
+        values.push(("modules", count.into()));
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::fixture::{temp_path, write};
+
+    const UBUNTU_VERSION: &str = "Linux version 6.8.0-45-generic (buildd@lcy02-amd64-075) \
+        (x86_64-linux-gnu-gcc-13 (Ubuntu 13.2.0-23ubuntu4) 13.2.0, GNU ld (GNU Binutils for Ubuntu) 2.42) \
+        #45-Ubuntu SMP PREEMPT_DYNAMIC Fri Aug 30 12:02:04 UTC 2024\n";
+
+    #[test]
+    fn parses_ubuntu_proc_version() {
+        let details = parse_proc_version(UBUNTU_VERSION);
+        assert_eq!(details.release.as_deref(), Some("6.8.0-45-generic"));
+        assert_eq!(details.flavor.as_deref(), Some("generic"));
+        assert_eq!(details.preemption, Some("PREEMPT_DYNAMIC"));
+        assert_eq!(
+            details.build_date.as_deref(),
+            Some("Fri Aug 30 12:02:04 UTC 2024")
+        );
+    }
+
+    #[test]
+    fn parses_rt_kernel_and_flavor_with_trailing_build_number() {
+        let details = parse_proc_version(
+            "Linux version 6.10.10-arch1-1 (linux@archlinux) (gcc (GCC) 14.2.1) #1 SMP PREEMPT_RT Wed, 11 Sep 2024\n",
+        );
+        assert_eq!(details.flavor.as_deref(), Some("arch1"));
+        assert_eq!(details.preemption, Some("PREEMPT_RT"));
+        assert_eq!(details.build_date.as_deref(), Some("Wed, 11 Sep 2024"));
+    }
+
+    #[test]
+    fn decodes_taint_bits() {
+        assert!(decode_taint(0).is_empty());
+        assert_eq!(
+            decode_taint(1 | (1 << 12) | (1 << 13)),
+            [
+                ('P', "proprietary module"),
+                ('O', "out-of-tree module"),
+                ('E', "unsigned module"),
+            ]
+        );
+        assert_eq!(decode_taint(1 << 40), [('?', "unknown")]);
+    }
+
+    #[test]
+    fn reads_proc_fixture_and_formats_lines() {
+        let root = temp_path("kernel-proc");
+        write(&root, "version", UBUNTU_VERSION);
+        write(&root, "sys/kernel/tainted", "4097\n");
+        write(
+            &root,
+            "modules",
+            "nvidia 54788096 2 - Live 0x0000000000000000 (POE)\nsnd 135168 1 - Live 0x0000000000000000\n",
+        );
+
+        let details = read_kernel_details(&root);
+        assert_eq!(
+            format_kernel_detail_lines(&details, Lang::En),
+            [
+                (
+                    "kernel_build",
+                    "Kernel Build",
+                    "generic, PREEMPT_DYNAMIC, Fri Aug 30 12:02:04 UTC 2024".to_string()
+                ),
+                (
+                    "kernel_taint",
+                    "Kernel Taint",
+                    "PO (proprietary module, out-of-tree module)".to_string()
+                ),
+                ("kernel_modules", "Kernel Modules", "2 loaded".to_string()),
+            ]
+        );
+        let names: Vec<&str> = kernel_detail_values(&details)
+            .iter()
+            .map(|(name, _)| *name)
+            .collect();
+        assert_eq!(
+            names,
+            ["flavor", "preemption", "build_date", "taint", "modules"]
+        );
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
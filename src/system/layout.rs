@@ -0,0 +1,482 @@
+//! Arranging the logo and the info list into printed lines: where the logo
+//! sits, the space around and between the two, wrapping the info into
+//! columns when the terminal is too short for one, and giving way to the
+//! info when it is too narrow.
+
+use crate::util::width::{char_width, str_width};
+
+/// Upper bound for `layout_gap` and `layout_padding`.
+pub const MAX_LAYOUT_SPACING: usize = 32;
+/// Default for `min_info_width`.
+pub const DEFAULT_MIN_INFO_WIDTH: usize = 40;
+
+/// Rows the info list starts below the top of a logo beside it when it
+/// opens with the `user@host` header, so the header lines up with the art.
+const HEADER_DROP: usize = 2;
+/// Blank columns between wrapped info columns.
+const INFO_COLUMN_GAP: usize = 3;
+
+/// `--logo-position` / `logo_position`: which side of the info the logo is on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogoPosition {
+    #[default]
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl LogoPosition {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "left" => Some(Self::Left),
+            "right" => Some(Self::Right),
+            "top" => Some(Self::Top),
+            "bottom" => Some(Self::Bottom),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Left => "left",
+            Self::Right => "right",
+            Self::Top => "top",
+            Self::Bottom => "bottom",
+        }
+    }
+
+    /// Logo and info share rows rather than being stacked.
+    pub fn is_beside(self) -> bool {
+        matches!(self, Self::Left | Self::Right)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Layout {
+    pub logo_position: LogoPosition,
+    /// Blank columns between logo and info, or rows when they are stacked,
+    /// on top of the margin logos already carry.
+    pub gap: usize,
+    /// Blank rows above and columns left of everything.
+    pub padding: usize,
+    /// Center the shorter of logo and info vertically beside the other.
+    pub center: bool,
+    /// Columns the info keeps beside the logo before the logo gives way.
+    pub min_info_width: usize,
+    /// Terminal size (columns, rows) to fit, when printing to one.
+    pub terminal: Option<(usize, usize)>,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self {
+            logo_position: LogoPosition::default(),
+            gap: 0,
+            padding: 0,
+            center: false,
+            min_info_width: DEFAULT_MIN_INFO_WIDTH,
+            terminal: None,
+        }
+    }
+}
+
+/// Logo and info lines before they are placed, kept so they can be placed
+/// again when the terminal is resized.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Blocks {
+    pub logo: Vec<String>,
+    /// Compact variant of `logo` for narrow terminals; empty if there is none.
+    pub small_logo: Vec<String>,
+    pub info: Vec<String>,
+    /// Rows the `user@host` header takes at the top of `info`. Beside a logo
+    /// only its first row is shown, without the divider.
+    pub header_rows: usize,
+}
+
+/// Printed lines, and the top-left cell the logo ended up at.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Arrangement {
+    pub lines: Vec<String>,
+    pub logo_origin: Option<(usize, usize)>,
+}
+
+impl Layout {
+    pub fn with_terminal(mut self, terminal: Option<(usize, usize)>) -> Self {
+        self.terminal = terminal;
+        self
+    }
+
+    /// Place `blocks`. In a terminal too narrow for the logo beside
+    /// `min_info_width` columns of info, the logo switches to its small
+    /// variant, then moves on top, then is hidden; info lines that still
+    /// don't fit are cut short with an ellipsis.
+    pub fn arrange(&self, blocks: &Blocks) -> Arrangement {
+        let Some((columns, rows)) = self.terminal else {
+            return self.place(&blocks.logo, blocks, None);
+        };
+        let room = columns.saturating_sub(self.padding);
+        let logos: Vec<&[String]> = [&blocks.logo, &blocks.small_logo]
+            .into_iter()
+            .filter(|logo| !logo.is_empty())
+            .map(Vec::as_slice)
+            .collect();
+
+        let beside = self.logo_position.is_beside();
+        if beside {
+            let wanted = block_width(&beside_info(blocks)).min(self.min_info_width);
+            if let Some(logo) = logos
+                .iter()
+                .find(|logo| block_width(logo) + self.gap + wanted <= room)
+            {
+                return self.place(logo, blocks, Some(room));
+            }
+        }
+
+        let stacked = Layout {
+            logo_position: if beside {
+                LogoPosition::Top
+            } else {
+                self.logo_position
+            },
+            ..*self
+        };
+        // A logo that had to shrink beside the info stays small on top.
+        let candidates = if beside {
+            &logos[logos.len().saturating_sub(1)..]
+        } else {
+            &logos[..]
+        };
+        let height = self.padding + self.gap + blocks.info.len();
+        match candidates
+            .iter()
+            .find(|logo| block_width(logo) <= room && height + logo.len() <= rows)
+        {
+            Some(logo) => stacked.place(logo, blocks, Some(room)),
+            None => self.place(&[], blocks, Some(room)),
+        }
+    }
+
+    /// Lay out `logo` (possibly none) and the info at `logo_position`,
+    /// keeping the info within `room` columns when given.
+    fn place(&self, logo: &[String], blocks: &Blocks, room: Option<usize>) -> Arrangement {
+        let pad_rows = self.padding;
+        let pad = " ".repeat(self.padding);
+        let mut lines = vec![String::new(); pad_rows];
+
+        if logo.is_empty() {
+            let info = fit_info(&blocks.info, self.info_rows(0), room);
+            lines.extend(info.iter().map(|line| format!("{pad}{line}")));
+            return Arrangement {
+                lines,
+                logo_origin: None,
+            };
+        }
+
+        let logo_width = block_width(logo);
+        if !self.logo_position.is_beside() {
+            let info = fit_info(&blocks.info, self.info_rows(logo.len() + self.gap), room);
+            let logo_top = match self.logo_position {
+                LogoPosition::Top => pad_rows,
+                _ => pad_rows + info.len() + self.gap,
+            };
+            let stack: [&[String]; 2] = match self.logo_position {
+                LogoPosition::Top => [logo, &info],
+                _ => [&info, logo],
+            };
+            lines.extend(stack[0].iter().map(|line| format!("{pad}{line}")));
+            lines.extend(std::iter::repeat_n(String::new(), self.gap));
+            lines.extend(stack[1].iter().map(|line| format!("{pad}{line}")));
+            return Arrangement {
+                lines,
+                logo_origin: Some((logo_top, self.padding)),
+            };
+        }
+
+        let drop = if blocks.header_rows > 0 && !self.center {
+            HEADER_DROP
+        } else {
+            0
+        };
+        let info_room = room.map(|room| room.saturating_sub(logo_width + self.gap));
+        let info = fit_info(&beside_info(blocks), self.info_rows(drop), info_room);
+        let height = logo.len().max(info.len() + drop);
+        let (logo_top, info_top) = if self.center {
+            ((height - logo.len()) / 2, (height - info.len()) / 2)
+        } else {
+            (0, drop)
+        };
+        let info_width = block_width(&info);
+        let gap = " ".repeat(self.gap);
+        for row in 0..height {
+            let logo_part = row
+                .checked_sub(logo_top)
+                .and_then(|i| logo.get(i))
+                .map_or("", String::as_str);
+            let info_part = row
+                .checked_sub(info_top)
+                .and_then(|i| info.get(i))
+                .map_or("", String::as_str);
+            let line = match self.logo_position {
+                LogoPosition::Right if logo_part.is_empty() => format!("{pad}{info_part}"),
+                LogoPosition::Right => format!(
+                    "{pad}{info_part}{}{gap}{logo_part}",
+                    " ".repeat(info_width - str_width(info_part))
+                ),
+                _ => format!(
+                    "{pad}{logo_part}{}{gap}{info_part}",
+                    " ".repeat(logo_width - str_width(logo_part))
+                ),
+            };
+            lines.push(line);
+        }
+        let logo_column = match self.logo_position {
+            LogoPosition::Right => self.padding + info_width + self.gap,
+            _ => self.padding,
+        };
+        Arrangement {
+            lines,
+            logo_origin: Some((pad_rows + logo_top, logo_column)),
+        }
+    }
+
+    /// Rows the info list may take before wrapping into columns, when the
+    /// terminal size is known and `reserved` rows go to something else.
+    fn info_rows(&self, reserved: usize) -> Option<usize> {
+        let (_, rows) = self.terminal?;
+        rows.checked_sub(self.padding + reserved)
+            .filter(|rows| *rows > 0)
+    }
+}
+
+/// The info as shown beside a logo: the header without its divider.
+fn beside_info(blocks: &Blocks) -> Vec<String> {
+    match blocks.header_rows {
+        0 | 1 => blocks.info.clone(),
+        rows => blocks.info[..1]
+            .iter()
+            .chain(blocks.info.iter().skip(rows))
+            .cloned()
+            .collect(),
+    }
+}
+
+fn block_width(lines: &[String]) -> usize {
+    lines.iter().map(|line| str_width(line)).max().unwrap_or(0)
+}
+
+/// Wrap `lines` into columns of at most `max_rows` rows if they fit in
+/// `room`, else cut each to `room`.
+fn fit_info(lines: &[String], max_rows: Option<usize>, room: Option<usize>) -> Vec<String> {
+    if let Some(max_rows) = max_rows
+        && lines.len() > max_rows
+    {
+        let wrapped = wrap_columns(lines, max_rows);
+        if room.is_none_or(|room| block_width(&wrapped) <= room) {
+            return wrapped;
+        }
+    }
+    match room {
+        Some(room) => lines.iter().map(|line| ellipsize(line, room)).collect(),
+        None => lines.to_vec(),
+    }
+}
+
+/// Split `lines` into side-by-side columns of at most `max_rows` rows,
+/// filled top to bottom, as evenly as the count allows.
+fn wrap_columns(lines: &[String], max_rows: usize) -> Vec<String> {
+    let columns = lines.len().div_ceil(max_rows);
+    let rows = lines.len().div_ceil(columns);
+    let chunks: Vec<&[String]> = lines.chunks(rows).collect();
+    let widths: Vec<usize> = chunks.iter().map(|chunk| block_width(chunk)).collect();
+    (0..rows)
+        .map(|row| {
+            let mut line = String::new();
+            let mut last_width = 0;
+            for (column, chunk) in chunks.iter().enumerate() {
+                let Some(cell) = chunk.get(row) else {
+                    break;
+                };
+                if column > 0 {
+                    let fill = widths[column - 1] - last_width + INFO_COLUMN_GAP;
+                    line.push_str(&" ".repeat(fill));
+                }
+                line.push_str(cell);
+                last_width = str_width(cell);
+            }
+            line
+        })
+        .collect()
+}
+
+/// `line` cut to `width` columns, ending in `…` when anything was cut.
+fn ellipsize(line: &str, width: usize) -> String {
+    if str_width(line) <= width {
+        return line.to_string();
+    }
+    let mut out = String::new();
+    let mut used = 0;
+    for ch in line.chars() {
+        let ch_width = char_width(ch);
+        if used + ch_width >= width {
+            break;
+        }
+        used += ch_width;
+        out.push(ch);
+    }
+    if width > 0 {
+        out.push('…');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    fn blocks(logo: &[&str], info: &[&str], header_rows: usize) -> Blocks {
+        Blocks {
+            logo: strings(logo),
+            small_logo: Vec::new(),
+            info: strings(info),
+            header_rows,
+        }
+    }
+
+    #[test]
+    fn default_layout_puts_info_beside_the_logo() {
+        let logo = ["/\\  ", "\\/  ", "    ", "    "];
+        let layout = Layout::default();
+        assert_eq!(
+            layout.arrange(&blocks(&logo, &["me@box", "------", "OS: x"], 2)),
+            Arrangement {
+                lines: strings(&["/\\  ", "\\/  ", "    me@box", "    OS: x"]),
+                logo_origin: Some((0, 0)),
+            }
+        );
+        assert_eq!(
+            layout
+                .arrange(&blocks(&logo, &["me@box", "OS: x"], 0))
+                .lines,
+            strings(&["/\\  me@box", "\\/  OS: x", "    ", "    "])
+        );
+        // Without a logo the header keeps its divider.
+        assert_eq!(
+            layout.arrange(&blocks(&[], &["me@box", "------"], 2)).lines,
+            strings(&["me@box", "------"])
+        );
+    }
+
+    #[test]
+    fn right_and_centered_layouts_pad_the_shorter_block() {
+        let layout = Layout {
+            logo_position: LogoPosition::Right,
+            gap: 1,
+            padding: 1,
+            center: true,
+            ..Layout::default()
+        };
+        let arranged = layout.arrange(&blocks(&["##", "##"], &["a", "bbb", "cc", "d"], 0));
+        assert_eq!(
+            arranged.lines,
+            strings(&["", " a", " bbb ##", " cc  ##", " d"])
+        );
+        assert_eq!(arranged.logo_origin, Some((2, 5)));
+    }
+
+    #[test]
+    fn stacked_layouts_keep_the_gap_between_blocks() {
+        let blocks = blocks(&["<>"], &["a", "b"], 1);
+        let top = Layout {
+            logo_position: LogoPosition::Top,
+            gap: 1,
+            ..Layout::default()
+        };
+        assert_eq!(
+            top.arrange(&blocks),
+            Arrangement {
+                lines: strings(&["<>", "", "a", "b"]),
+                logo_origin: Some((0, 0)),
+            }
+        );
+        let bottom = Layout {
+            logo_position: LogoPosition::Bottom,
+            ..top
+        };
+        assert_eq!(
+            bottom.arrange(&blocks),
+            Arrangement {
+                lines: strings(&["a", "b", "", "<>"]),
+                logo_origin: Some((3, 0)),
+            }
+        );
+    }
+
+    #[test]
+    fn short_terminals_wrap_info_into_columns() {
+        let info = ["one", "two", "three", "four", "five"];
+        let layout = Layout::default().with_terminal(Some((80, 2)));
+        assert_eq!(
+            layout.arrange(&blocks(&[], &info, 0)).lines,
+            strings(&["one   three   five", "two   four"])
+        );
+
+        let layout = Layout::default().with_terminal(Some((80, 4)));
+        assert_eq!(
+            layout.arrange(&blocks(&["@@ ", "@@ "], &info, 0)).lines,
+            strings(&["@@ one     four", "@@ two     five", "   three"])
+        );
+
+        // Columns that wouldn't fit the width aren't used.
+        let layout = Layout::default().with_terminal(Some((10, 2)));
+        assert_eq!(layout.arrange(&blocks(&[], &info, 0)).lines, strings(&info));
+    }
+
+    #[test]
+    fn narrow_terminals_shrink_move_then_hide_the_logo() {
+        let blocks = Blocks {
+            logo: strings(&["LLLLLL  ", "LLLLLL  ", "LLLLLL  "]),
+            small_logo: strings(&["ss  "]),
+            info: strings(&["me@box", "------", "OS: long value"]),
+            header_rows: 2,
+        };
+        let layout = Layout {
+            min_info_width: 10,
+            ..Layout::default()
+        };
+        let at = |columns: usize, rows: usize| layout.with_terminal(Some((columns, rows)));
+
+        assert_eq!(
+            at(24, 10).arrange(&blocks).lines,
+            strings(&[
+                "LLLLLL  ",
+                "LLLLLL  ",
+                "LLLLLL  me@box",
+                "        OS: long value"
+            ])
+        );
+        // The big logo would leave 8 columns, the small one leaves 12.
+        assert_eq!(
+            at(16, 10).arrange(&blocks).lines,
+            strings(&["ss  ", "    ", "    me@box", "    OS: long va…"])
+        );
+        assert_eq!(
+            at(12, 10).arrange(&blocks),
+            Arrangement {
+                lines: strings(&["ss  ", "me@box", "------", "OS: long va…"]),
+                logo_origin: Some((0, 0)),
+            }
+        );
+        assert_eq!(
+            at(12, 3).arrange(&blocks),
+            Arrangement {
+                lines: strings(&["me@box", "------", "OS: long va…"]),
+                logo_origin: None,
+            }
+        );
+    }
+}
@@ -11,3 +11,12 @@ pub fn ascii_logo() -> Vec<&'static str> {
         "    ████████████████    ",
     ]
 }
+
+pub fn ascii_logo_small() -> Vec<&'static str> {
+    vec![
+        "  ██████    ",
+        "██████████  ",
+        "██████████  ",
+        "  ██████    ",
+    ]
+}
@@ -265,6 +265,125 @@ const VOID: &[&str] = &[
     "                          ",
 ];
 
+const ARCH_SMALL: &[&str] = &[
+    "      /\\        ",
+    "     /  \\       ",
+    "    /\\   \\      ",
+    "   /      \\     ",
+    "  /   ,,   \\    ",
+    " /   |  |  -\\   ",
+    "/_-''    ''-_\\  ",
+];
+
+const UBUNTU_SMALL: &[&str] = &[
+    "         _   ",
+    "     ---(_)  ",
+    " _/  ---  \\  ",
+    "(_) |   |    ",
+    "  \\  --- _/  ",
+    "     ---(_)  ",
+];
+
+const DEBIAN_SMALL: &[&str] = &[
+    "  _____    ",
+    " /  __ \\   ",
+    "|  /    |  ",
+    "|  \\___-   ",
+    "-_         ",
+    "  --_      ",
+];
+
+const FEDORA_SMALL: &[&str] = &[
+    "      _____     ",
+    "     /   __)\\   ",
+    "     |  /  \\ \\  ",
+    "  ___|  |__/ /  ",
+    " / (_    _)_/   ",
+    "/ /  |  |       ",
+    "\\ \\__/  |       ",
+    " \\(_____/       ",
+];
+
+const ALPINE_SMALL: &[&str] = &[
+    "   /\\ /\\      ",
+    "  // \\  \\     ",
+    " //   \\  \\    ",
+    "///    \\  \\   ",
+    "//      \\  \\  ",
+    "         \\    ",
+];
+
+const NIXOS_SMALL: &[&str] = &[
+    "  \\\\  \\\\ //    ",
+    " ==\\\\__\\\\/ //  ",
+    "   //   \\\\//   ",
+    "==//     //==  ",
+    " //\\\\___//     ",
+    "// /\\\\  \\\\==   ",
+    "  // \\\\  \\\\    ",
+];
+
+const MANJARO_SMALL: &[&str] = &[
+    "||||||||| ||||  ",
+    "||||||||| ||||  ",
+    "||||      ||||  ",
+    "|||| |||| ||||  ",
+    "|||| |||| ||||  ",
+    "|||| |||| ||||  ",
+    "|||| |||| ||||  ",
+];
+
+const OPENSUSE_SMALL: &[&str] = &[
+    "  _______    ",
+    "__|   __ \\   ",
+    "     / .\\ \\  ",
+    "     \\__/ |  ",
+    "   _______|  ",
+    "   \\_______  ",
+    "__________/  ",
+];
+
+const GENTOO_SMALL: &[&str] = &[
+    " _-----_     ",
+    "(       \\    ",
+    "\\    0   \\   ",
+    " \\        )  ",
+    " /      _/   ",
+    "(     _-     ",
+    "\\____-       ",
+];
+
+const LINUXMINT_SMALL: &[&str] = &[
+    " ___________   ",
+    "|_          \\  ",
+    "  | | _____ |  ",
+    "  | | | | | |  ",
+    "  | | | | | |  ",
+    "  | \\_____/ |  ",
+    "  \\_________/  ",
+];
+
+const KALI_SMALL: &[&str] = &[
+    "  ..,;:ccc,.       ",
+    " ......''';lxO.    ",
+    "     .;;;:;,,.x,   ",
+    " ..''.     0Xxoc:  ",
+    "           ,ONkc;  ",
+    "           OMo     ",
+    "            dMc    ",
+    "             ;Wd   ",
+];
+
+const VOID_SMALL: &[&str] = &[
+    "    _______    ",
+    " _ \\______ -   ",
+    "| \\  ___  \\ |  ",
+    "| | /   \\ | |  ",
+    "| | \\___/ | |  ",
+    "| \\______ \\_|  ",
+    " -_______\\     ",
+];
+
 pub fn supported_distro_ids() -> &'static [&'static str] {
     SUPPORTED_DISTRO_IDS
 }
@@ -324,6 +443,28 @@ pub fn logo_for_distro(id: &str) -> Option<Vec<&'static str>> {
     )
 }
 
+/// Compact variant of [`logo_for_distro`] for narrow terminals.
+pub fn small_logo_for_distro(id: &str) -> Option<Vec<&'static str>> {
+    Some(
+        match canonical_distro_id(id)? {
+            "arch" => ARCH_SMALL,
+            "ubuntu" => UBUNTU_SMALL,
+            "debian" => DEBIAN_SMALL,
+            "fedora" => FEDORA_SMALL,
+            "alpine" => ALPINE_SMALL,
+            "nixos" => NIXOS_SMALL,
+            "manjaro" => MANJARO_SMALL,
+            "opensuse" => OPENSUSE_SMALL,
+            "gentoo" => GENTOO_SMALL,
+            "linuxmint" => LINUXMINT_SMALL,
+            "kali" => KALI_SMALL,
+            "void" => VOID_SMALL,
+            _ => return None,
+        }
+        .to_vec(),
+    )
+}
+
 fn canonical_distro_id(id: &str) -> Option<&'static str> {
     let id = id.trim().trim_matches('"').trim_matches('\'');
     let normalized = id.to_ascii_lowercase();
@@ -362,7 +503,10 @@ fn parse_os_release_value(value: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::{distro_id_from_os_release, logo_for_distro, supported_distro_ids};
+    use super::{
+        distro_id_from_os_release, logo_for_distro, small_logo_for_distro, supported_distro_ids,
+    };
+    use crate::util::width::str_width;
 
     #[test]
     fn parses_known_distro_ids() {
@@ -436,4 +580,25 @@ ID_LIKE="other base"
             }
         }
     }
+
+    #[test]
+    fn small_logos_are_narrower_and_rectangular() {
+        for id in supported_distro_ids() {
+            let logo = logo_for_distro(id).expect("known distro should have a logo");
+            let small = small_logo_for_distro(id).expect("known distro should have a small logo");
+            let width = str_width(small[0]);
+            assert!(small.len() < logo.len(), "{id} small logo must be shorter");
+            assert!(
+                width < str_width(logo[0]),
+                "{id} small logo must be narrower"
+            );
+            for line in small {
+                assert_eq!(
+                    str_width(line),
+                    width,
+                    "{id} small logo lines must be padded"
+                );
+            }
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use super::logo_distro::{distro_id_from_os_release, logo_for_distro};
+use super::logo_distro::{distro_id_from_os_release, logo_for_distro, small_logo_for_distro};
 use std::fs;
 
 fn logo_fallback() -> Vec<&'static str> {
@@ -18,10 +18,32 @@ fn logo_fallback() -> Vec<&'static str> {
     ]
 }
 
-pub fn ascii_logo() -> Vec<&'static str> {
+fn small_logo_fallback() -> Vec<&'static str> {
+    vec![
+        "    ___     ",
+        "   (.. |    ",
+        "   (<> |    ",
+        "  / __  \\   ",
+        " ( /  \\ /|  ",
+        "_/\\ __)/_)  ",
+        "\\/-____\\/   ",
+    ]
+}
+
+pub fn distro_id() -> Option<String> {
     fs::read_to_string("/etc/os-release")
         .ok()
         .and_then(|content| distro_id_from_os_release(&content))
+}
+
+pub fn ascii_logo() -> Vec<&'static str> {
+    distro_id()
         .and_then(|id| logo_for_distro(&id))
         .unwrap_or_else(logo_fallback)
 }
+
+pub fn ascii_logo_small() -> Vec<&'static str> {
+    distro_id()
+        .and_then(|id| small_logo_for_distro(&id))
+        .unwrap_or_else(small_logo_fallback)
+}
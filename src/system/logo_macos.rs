@@ -24,3 +24,15 @@ pub fn ascii_logo() -> Vec<&'static str> {
         "                                  ",
     ]
 }
+
+pub fn ascii_logo_small() -> Vec<&'static str> {
+    vec![
+        "        .:'     ",
+        "    __ :'__     ",
+        " .'`  `-'  ``.  ",
+        ":          .-'  ",
+        ":         :     ",
+        " :         `-;  ",
+        "  `.__.-.__.'   ",
+    ]
+}
@@ -1,4 +1,11 @@
+mod boot;
+mod containers;
+mod custom;
+mod git;
 pub mod info;
+mod json;
+mod kernel;
+mod layout;
 #[cfg(not(any(target_os = "macos", target_os = "linux")))]
 mod logo_default;
 mod logo_distro;
@@ -6,12 +13,23 @@ mod logo_distro;
 mod logo_linux;
 #[cfg(target_os = "macos")]
 mod logo_macos;
+mod output;
+mod probe;
+mod prometheus;
+mod snapshot;
+mod storage;
+mod users;
 
+pub use custom::{CustomField, CustomFieldSource, DEFAULT_CUSTOM_TIMEOUT};
 pub use info::{
-    INFO_FIELD_KEYS, InfoFieldSelection, SystemInfoOptions, generate_system_info,
-    generate_system_info_json, info_field_key,
+    FieldFormat, INFO_FIELD_KEYS, InfoFieldSelection, SystemInfoOptions, arrange_system_info,
+    field_placeholders, info_field_key, system_info_blocks,
 };
-pub use logo_distro::{logo_for_distro, supported_distro_ids};
+pub use json::{JSON_SCHEMA, generate_system_info_json};
+pub use layout::{Blocks, DEFAULT_MIN_INFO_WIDTH, Layout, LogoPosition, MAX_LAYOUT_SPACING};
+pub use logo_distro::{logo_for_distro, small_logo_for_distro, supported_distro_ids};
+pub use output::{OutputFormat, generate_system_info_output};
+pub use snapshot::{SNAPSHOT_DISTRO_KEY, Snapshot};
 
 #[cfg(target_os = "macos")]
 pub fn ascii_logo() -> Vec<&'static str> {
@@ -26,6 +44,29 @@ pub fn ascii_logo() -> Vec<&'static str> {
     logo_default::ascii_logo()
 }
 
+#[cfg(target_os = "macos")]
+pub fn ascii_logo_small() -> Vec<&'static str> {
+    logo_macos::ascii_logo_small()
+}
+#[cfg(target_os = "linux")]
+pub fn ascii_logo_small() -> Vec<&'static str> {
+    logo_linux::ascii_logo_small()
+}
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+pub fn ascii_logo_small() -> Vec<&'static str> {
+    logo_default::ascii_logo_small()
+}
+
+/// Logo id of the running distro, from `/etc/os-release`.
+#[cfg(target_os = "linux")]
+pub fn detect_distro_id() -> Option<String> {
+    logo_linux::distro_id()
+}
+#[cfg(not(target_os = "linux"))]
+pub fn detect_distro_id() -> Option<String> {
+    None
+}
+
 pub fn ascii_logo_with_distro(distro_id: Option<&str>) -> Vec<&'static str> {
     if let Some(id) = distro_id {
         if let Some(logo) = logo_for_distro(id) {
@@ -36,3 +77,11 @@ pub fn ascii_logo_with_distro(distro_id: Option<&str>) -> Vec<&'static str> {
 
     ascii_logo()
 }
+
+/// Compact variant of [`ascii_logo_with_distro`]; unknown ids fall back
+/// quietly, having been warned about already.
+pub fn ascii_logo_small_with_distro(distro_id: Option<&str>) -> Vec<&'static str> {
+    distro_id
+        .and_then(small_logo_for_distro)
+        .unwrap_or_else(ascii_logo_small)
+}
@@ -0,0 +1,393 @@
+//! Machine-readable outputs selected with `--format`.
+//!
+//! YAML, TOML, env, Markdown and CSV serialize the same document: one section
+//! per field holding its `label`, display `value` and full `text`, followed by
+//! the typed readings behind it (`used_bytes`, `percent`, ...). JSON is the
+//! versioned `--json` document and Prometheus has its own metric layout.
+
+use super::info::{SystemInfoField, SystemInfoOptions, generate_system_info_fields};
+use super::json::generate_system_info_json;
+use super::prometheus::generate_system_info_prometheus;
+use super::snapshot::SNAPSHOT_DISTRO_KEY;
+use crate::util::template::TemplateValue;
+use std::fmt::Write;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    Json,
+    Yaml,
+    Toml,
+    Env,
+    Markdown,
+    Csv,
+    Prometheus,
+}
+
+impl OutputFormat {
+    pub const NAMES: &'static [&'static str] = &[
+        "json",
+        "yaml",
+        "toml",
+        "env",
+        "markdown",
+        "csv",
+        "prometheus",
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "toml" => Some(Self::Toml),
+            "env" => Some(Self::Env),
+            "markdown" | "md" => Some(Self::Markdown),
+            "csv" => Some(Self::Csv),
+            "prometheus" => Some(Self::Prometheus),
+            _ => None,
+        }
+    }
+}
+
+pub fn generate_system_info_output(options: &SystemInfoOptions, format: OutputFormat) -> String {
+    let options = &options.locale_neutral();
+    let document = match format {
+        OutputFormat::Json => return generate_system_info_json(options, false) + "\n",
+        OutputFormat::Prometheus => return generate_system_info_prometheus(options),
+        _ => Document::build(options),
+    };
+    match format {
+        OutputFormat::Yaml => document.to_yaml(),
+        OutputFormat::Toml => document.to_toml(),
+        OutputFormat::Env => document.to_env(),
+        OutputFormat::Markdown => document.to_markdown(),
+        OutputFormat::Csv => document.to_csv(),
+        OutputFormat::Json | OutputFormat::Prometheus => unreachable!("handled above"),
+    }
+}
+
+type Entries = Vec<(&'static str, TemplateValue)>;
+
+struct Document {
+    /// Top-level scalars (the distro id); written before any section so TOML
+    /// keeps them out of the last table.
+    meta: Entries,
+    sections: Vec<(&'static str, Entries)>,
+    /// `(label, value)` per display line, for the Markdown table.
+    rows: Vec<(String, String)>,
+}
+
+impl Document {
+    fn build(options: &SystemInfoOptions) -> Self {
+        let meta = match options.reported_distro_id() {
+            Some(distro_id) => vec![(SNAPSHOT_DISTRO_KEY, TemplateValue::Text(distro_id))],
+            None => Vec::new(),
+        };
+        Self::from_fields(
+            meta,
+            generate_system_info_fields(options),
+            options.field_format.separator(),
+        )
+    }
+
+    fn from_fields(meta: Entries, fields: Vec<SystemInfoField>, separator: &str) -> Self {
+        let mut document = Self {
+            meta,
+            sections: Vec::new(),
+            rows: Vec::new(),
+        };
+        for field in fields {
+            let lines = display_lines(&field, separator);
+            let mut entries: Entries = Vec::new();
+            if let Some((label, value)) = lines.first() {
+                if let Some(label) = label {
+                    entries.push(("label", TemplateValue::Text(label.clone())));
+                }
+                entries.push(("value", TemplateValue::Text(value.clone())));
+            }
+            entries.push(("text", TemplateValue::Text(field.line)));
+            entries.extend(field.values);
+            document.rows.extend(
+                lines
+                    .into_iter()
+                    .map(|(label, value)| (label.unwrap_or_else(|| field.key.to_string()), value)),
+            );
+            document.sections.push((field.key, entries));
+        }
+        document
+    }
+
+    fn to_yaml(&self) -> String {
+        let mut out = String::new();
+        for (name, value) in &self.meta {
+            let _ = writeln!(out, "{}: {}", name, yaml_value(value));
+        }
+        for (key, entries) in &self.sections {
+            let _ = writeln!(
+                out,
+                "{}:",
+                bare_key(key).unwrap_or_else(|| json_string(key))
+            );
+            for (name, value) in entries {
+                let _ = writeln!(out, "  {}: {}", name, yaml_value(value));
+            }
+        }
+        out
+    }
+
+    fn to_toml(&self) -> String {
+        let mut out = String::new();
+        for (name, value) in &self.meta {
+            let _ = writeln!(out, "{} = {}", name, toml_value(value));
+        }
+        for (key, entries) in &self.sections {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            let _ = writeln!(
+                out,
+                "[{}]",
+                bare_key(key).unwrap_or_else(|| toml_string(key))
+            );
+            for (name, value) in entries {
+                let _ = writeln!(out, "{} = {}", name, toml_value(value));
+            }
+        }
+        out
+    }
+
+    /// `NEONFETCH_<FIELD>_<NAME>=value` lines that a POSIX shell can source.
+    fn to_env(&self) -> String {
+        let mut out = String::new();
+        for (name, value) in &self.meta {
+            let _ = writeln!(
+                out,
+                "NEONFETCH_{}={}",
+                name.to_ascii_uppercase(),
+                shell_quote(&plain_value(value))
+            );
+        }
+        for (key, entries) in &self.sections {
+            for (name, value) in entries {
+                let _ = writeln!(
+                    out,
+                    "NEONFETCH_{}_{}={}",
+                    env_name(key),
+                    name.to_ascii_uppercase(),
+                    shell_quote(&plain_value(value))
+                );
+            }
+        }
+        out
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut out = String::from("| Field | Value |\n| --- | --- |\n");
+        for (label, value) in &self.rows {
+            let _ = writeln!(
+                out,
+                "| {} | {} |",
+                markdown_cell(label),
+                markdown_cell(value)
+            );
+        }
+        out
+    }
+
+    /// Long format, one `field,name,value` row per entry (RFC 4180 quoting).
+    fn to_csv(&self) -> String {
+        let mut out = String::from("field,name,value\n");
+        for (name, value) in &self.meta {
+            let _ = writeln!(out, ",{},{}", name, csv_cell(&plain_value(value)));
+        }
+        for (key, entries) in &self.sections {
+            for (name, value) in entries {
+                let _ = writeln!(out, "{},{},{}", key, name, csv_cell(&plain_value(value)));
+            }
+        }
+        out
+    }
+}
+
+/// Split a field's display lines into `(label, value)` on the configured
+/// separator. The header's divider row is dropped; lines without a label
+/// (the `user@host` header) keep `None`.
+pub(super) fn display_lines(
+    field: &SystemInfoField,
+    separator: &str,
+) -> Vec<(Option<String>, String)> {
+    field
+        .line
+        .lines()
+        .filter(|line| !line.is_empty() && !line.chars().all(|ch| ch == '-'))
+        .map(|line| match line.split_once(separator) {
+            Some((label, value)) if !separator.is_empty() => {
+                (Some(label.to_string()), value.to_string())
+            }
+            _ => (None, line.to_string()),
+        })
+        .collect()
+}
+
+/// Field keys are `[a-z0-9_]` unless a snapshot brought in something else,
+/// which YAML and TOML then get quoted.
+fn bare_key(key: &str) -> Option<String> {
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '_');
+    bare.then(|| key.to_string())
+}
+
+fn env_name(key: &str) -> String {
+    key.chars()
+        .map(|ch| match ch.is_ascii_alphanumeric() {
+            true => ch.to_ascii_uppercase(),
+            false => '_',
+        })
+        .collect()
+}
+
+fn plain_value(value: &TemplateValue) -> String {
+    match value {
+        TemplateValue::Text(text) => text.clone(),
+        TemplateValue::Integer(number) => number.to_string(),
+        TemplateValue::Float(number) => number.to_string(),
+    }
+}
+
+fn json_string(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+/// JSON strings are valid YAML double-quoted scalars, which sidesteps YAML's
+/// implicit typing of bare words like `yes` or `1.10`.
+fn yaml_value(value: &TemplateValue) -> String {
+    match value {
+        TemplateValue::Text(text) => json_string(text),
+        TemplateValue::Integer(number) => number.to_string(),
+        TemplateValue::Float(number) if number.is_nan() => ".nan".to_string(),
+        TemplateValue::Float(number) if number.is_infinite() => {
+            if *number > 0.0 { ".inf" } else { "-.inf" }.to_string()
+        }
+        TemplateValue::Float(number) => number.to_string(),
+    }
+}
+
+fn toml_value(value: &TemplateValue) -> String {
+    match value {
+        TemplateValue::Text(text) => toml_string(text),
+        TemplateValue::Integer(number) => number.to_string(),
+        TemplateValue::Float(number) if number.is_nan() => "nan".to_string(),
+        TemplateValue::Float(number) if number.is_infinite() => {
+            if *number > 0.0 { "inf" } else { "-inf" }.to_string()
+        }
+        // `{:?}` keeps the `.0` that tells TOML this is a float.
+        TemplateValue::Float(number) => format!("{:?}", number),
+    }
+}
+
+fn toml_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            ch if ch.is_control() => {
+                let _ = write!(out, "\\u{:04X}", ch as u32);
+            }
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Single-quote unless the value is made only of characters no shell treats
+/// specially.
+fn shell_quote(text: &str) -> String {
+    let safe = !text.is_empty()
+        && text
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || "_-.,:/@%+".contains(ch));
+    if safe {
+        text.to_string()
+    } else {
+        format!("'{}'", text.replace('\'', "'\\''"))
+    }
+}
+
+fn markdown_cell(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('|', "\\|")
+}
+
+fn csv_cell(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Document {
+        let mut memory = SystemInfoField::new("memory", "Memory: 1.00 GiB / 4.00 GiB".to_string());
+        memory.values = vec![
+            ("used_bytes", TemplateValue::Integer(1 << 30)),
+            ("percent", TemplateValue::Float(25.0)),
+        ];
+        let header = SystemInfoField::new("header", "me@box\n------".to_string());
+        Document::from_fields(
+            vec![("distro_id", TemplateValue::Text("arch".to_string()))],
+            vec![header, memory],
+            ": ",
+        )
+    }
+
+    #[test]
+    fn serializes_sections_with_typed_values() {
+        let document = sample();
+        assert!(document.to_toml().starts_with(
+            "distro_id = \"arch\"\n\n[header]\nvalue = \"me@box\"\ntext = \"me@box\\n------\"\n"
+        ));
+        assert!(
+            document
+                .to_toml()
+                .contains("used_bytes = 1073741824\npercent = 25.0\n")
+        );
+        assert!(
+            document
+                .to_yaml()
+                .contains("memory:\n  label: \"Memory\"\n  value: \"1.00 GiB / 4.00 GiB\"\n")
+        );
+        assert!(document.to_yaml().contains("  text: \"me@box\\n------\"\n"));
+    }
+
+    #[test]
+    fn quotes_env_markdown_and_csv_cells() {
+        let document = sample();
+        let env = document.to_env();
+        assert!(env.starts_with("NEONFETCH_DISTRO_ID=arch\n"));
+        assert!(env.contains("NEONFETCH_MEMORY_VALUE='1.00 GiB / 4.00 GiB'\n"));
+        assert!(env.contains("NEONFETCH_HEADER_TEXT='me@box\n------'\n"));
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(env_name("my-field"), "MY_FIELD");
+        assert_eq!(
+            document.to_markdown(),
+            "| Field | Value |\n| --- | --- |\n| header | me@box |\n| Memory | 1.00 GiB / 4.00 GiB |\n"
+        );
+        assert_eq!(markdown_cell("a|b"), "a\\|b");
+        assert_eq!(csv_cell("say \"hi\", bye"), "\"say \"\"hi\"\", bye\"");
+        assert!(
+            document
+                .to_csv()
+                .contains("\nmemory,used_bytes,1073741824\n")
+        );
+    }
+}
@@ -0,0 +1,149 @@
+//! Background probe threads that record how long they ran and how they
+//! ended, for the `probes` section of `--json`.
+
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProbeReport {
+    pub name: &'static str,
+    pub duration: Duration,
+    pub status: ProbeStatus,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ProbeStatus {
+    Ok,
+    /// Nothing to read on this machine: no battery, not in a repository.
+    NotApplicable,
+    Error(String),
+}
+
+impl ProbeStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::NotApplicable => "not_applicable",
+            Self::Error(_) => "error",
+        }
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        match self {
+            Self::Error(reason) => Some(reason),
+            _ => None,
+        }
+    }
+}
+
+/// Probe results that can come back empty. `None` means the probe does not
+/// apply here; probes that can fail return `Err` with the reason. Either
+/// way the field itself is just left out (or shown as unknown).
+pub trait Reading {
+    fn status(&self) -> ProbeStatus;
+}
+
+impl<T> Reading for Option<T> {
+    fn status(&self) -> ProbeStatus {
+        match self {
+            Some(_) => ProbeStatus::Ok,
+            None => ProbeStatus::NotApplicable,
+        }
+    }
+}
+
+impl<T> Reading for Result<Option<T>, String> {
+    fn status(&self) -> ProbeStatus {
+        match self {
+            Ok(reading) => reading.status(),
+            Err(reason) => ProbeStatus::Error(reason.clone()),
+        }
+    }
+}
+
+impl<A, B> Reading for (Option<A>, Option<B>) {
+    fn status(&self) -> ProbeStatus {
+        if self.0.is_none() && self.1.is_none() {
+            ProbeStatus::NotApplicable
+        } else {
+            ProbeStatus::Ok
+        }
+    }
+}
+
+pub struct ProbeHandle<T> {
+    name: &'static str,
+    handle: JoinHandle<(T, Duration)>,
+}
+
+pub fn spawn_probe<T, F>(name: &'static str, probe: F) -> ProbeHandle<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let handle = thread::spawn(move || {
+        let started = Instant::now();
+        let reading = probe();
+        (reading, started.elapsed())
+    });
+    ProbeHandle { name, handle }
+}
+
+impl<T: Reading> ProbeHandle<T> {
+    /// Wait for the probe and append its report; `None` if it panicked.
+    pub fn join(self, reports: &mut Vec<ProbeReport>) -> Option<T> {
+        let joined = self.handle.join().ok();
+        let (duration, status) = match &joined {
+            Some((reading, duration)) => (*duration, reading.status()),
+            None => (
+                Duration::ZERO,
+                ProbeStatus::Error("probe panicked".to_string()),
+            ),
+        };
+        reports.push(ProbeReport {
+            name: self.name,
+            duration,
+            status,
+        });
+        joined.map(|(reading, _)| reading)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_missing_readings_failures_and_panics() {
+        let mut reports = Vec::new();
+        assert_eq!(
+            spawn_probe("found", || Some(3)).join(&mut reports),
+            Some(Some(3))
+        );
+        assert_eq!(
+            spawn_probe("empty", || None::<u8>).join(&mut reports),
+            Some(None)
+        );
+        assert_eq!(
+            spawn_probe("failed", || Err::<Option<u8>, _>("timed out".to_string()))
+                .join(&mut reports),
+            Some(Err("timed out".to_string()))
+        );
+        let panicked = spawn_probe("broken", || -> Option<u8> { panic!("probe failed") });
+        assert_eq!(panicked.join(&mut reports), None);
+
+        let statuses: Vec<(&str, &str, Option<&str>)> = reports
+            .iter()
+            .map(|report| (report.name, report.status.name(), report.status.error()))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                ("found", "ok", None),
+                ("empty", "not_applicable", None),
+                ("failed", "error", Some("timed out")),
+                ("broken", "error", Some("probe panicked"))
+            ]
+        );
+    }
+}
@@ -0,0 +1,229 @@
+//! Prometheus text exposition output (`--format prometheus`), laid out for
+//! node_exporter's textfile collector.
+
+use super::info::{SystemInfoField, SystemInfoOptions, generate_system_info_fields};
+use crate::util::template::TemplateValue;
+use std::fmt::Write;
+use sysinfo::Disks;
+
+type Labels = Vec<(&'static str, String)>;
+
+pub fn generate_system_info_prometheus(options: &SystemInfoOptions) -> String {
+    let fields = generate_system_info_fields(options);
+    let field = |key: &str| fields.iter().find(|field| field.key == key);
+    let number = |key: &str, name: &str| field(key).and_then(|field| number(field, name));
+    let mut out = Exposition::default();
+
+    let mut info: Labels = Vec::new();
+    if let Some(hostname) = options.reported_hostname() {
+        info.push(("hostname", hostname));
+    }
+    for (label, key, name) in [
+        ("os", "os", "name"),
+        ("os_version", "os", "version"),
+        ("arch", "os", "arch"),
+        ("kernel", "kernel", "release"),
+        ("host", "host", "model"),
+        ("cpu", "cpu", "model"),
+        ("shell", "shell", "name"),
+    ] {
+        if let Some(TemplateValue::Text(text)) = field(key).and_then(|field| field.value(name))
+            && !text.is_empty()
+        {
+            info.push((label, text.clone()));
+        }
+    }
+    if let Some(distro_id) = options.reported_distro_id() {
+        info.push(("distro_id", distro_id));
+    }
+    out.gauge(
+        "neonfetch_info",
+        "Static host metadata; the value is always 1.",
+        vec![(info, 1.0)],
+    );
+
+    if let Some(seconds) = number("uptime", "seconds") {
+        out.gauge(
+            "neonfetch_uptime_seconds",
+            "Time since boot.",
+            vec![(Vec::new(), seconds)],
+        );
+    }
+    for (name, help, key) in [
+        (
+            "neonfetch_memory_used_bytes",
+            "Memory in use.",
+            "used_bytes",
+        ),
+        (
+            "neonfetch_memory_total_bytes",
+            "Installed memory.",
+            "total_bytes",
+        ),
+    ] {
+        if let Some(bytes) = number("memory", key) {
+            out.gauge(name, help, vec![(Vec::new(), bytes)]);
+        }
+    }
+    if field("swap").is_some() {
+        // A swap field without readings means swap is disabled.
+        for (name, help, key) in [
+            ("neonfetch_swap_used_bytes", "Swap in use.", "used_bytes"),
+            (
+                "neonfetch_swap_total_bytes",
+                "Configured swap.",
+                "total_bytes",
+            ),
+        ] {
+            let bytes = number("swap", key).unwrap_or(0.0);
+            out.gauge(name, help, vec![(Vec::new(), bytes)]);
+        }
+    }
+    let disks = disk_samples(options, field("disk"));
+    if !disks.is_empty() {
+        out.gauge(
+            "neonfetch_disk_used_bytes",
+            "Space used per mounted filesystem.",
+            disks
+                .iter()
+                .map(|(mount, used, _)| (vec![("mount", mount.clone())], *used))
+                .collect(),
+        );
+        out.gauge(
+            "neonfetch_disk_total_bytes",
+            "Size per mounted filesystem.",
+            disks
+                .iter()
+                .map(|(mount, _, total)| (vec![("mount", mount.clone())], *total))
+                .collect(),
+        );
+    }
+    if let Some(celsius) = number("temperature", "celsius") {
+        out.gauge(
+            "neonfetch_temperature_celsius",
+            "Average thermal sensor reading.",
+            vec![(Vec::new(), celsius)],
+        );
+    }
+    if let Some(percent) = number("battery", "percent") {
+        out.gauge(
+            "neonfetch_battery_percent",
+            "Battery charge.",
+            vec![(Vec::new(), percent)],
+        );
+    }
+    if let (Some(TemplateValue::Text(manager)), Some(count)) = (
+        field("packages").and_then(|field| field.value("manager")),
+        number("packages", "count"),
+    ) {
+        out.gauge(
+            "neonfetch_packages",
+            "Installed packages per package manager.",
+            vec![(vec![("manager", manager.clone())], count)],
+        );
+    }
+    out.text
+}
+
+fn number(field: &SystemInfoField, name: &str) -> Option<f64> {
+    match field.value(name)? {
+        TemplateValue::Integer(value) => Some(*value as f64),
+        TemplateValue::Float(value) => Some(*value),
+        TemplateValue::Text(_) => None,
+    }
+}
+
+/// `(mount, used, total)` for every mounted filesystem when probing locally;
+/// a snapshot only has the one mount its disk field recorded.
+fn disk_samples(
+    options: &SystemInfoOptions,
+    disk: Option<&SystemInfoField>,
+) -> Vec<(String, f64, f64)> {
+    let Some(disk) = disk else {
+        return Vec::new();
+    };
+    if options.snapshot.is_some() {
+        return match (
+            disk.value("mount"),
+            number(disk, "used_bytes"),
+            number(disk, "total_bytes"),
+        ) {
+            (Some(TemplateValue::Text(mount)), Some(used), Some(total)) => {
+                vec![(mount.clone(), used, total)]
+            }
+            _ => Vec::new(),
+        };
+    }
+    let mut samples: Vec<(String, f64, f64)> = Vec::new();
+    for disk in Disks::new_with_refreshed_list().iter() {
+        let mount = disk.mount_point().to_string_lossy().into_owned();
+        let total = disk.total_space();
+        if total == 0 || samples.iter().any(|(seen, _, _)| *seen == mount) {
+            continue;
+        }
+        let used = total.saturating_sub(disk.available_space());
+        samples.push((mount, used as f64, total as f64));
+    }
+    samples
+}
+
+#[derive(Default)]
+struct Exposition {
+    text: String,
+}
+
+impl Exposition {
+    fn gauge(&mut self, name: &str, help: &str, samples: Vec<(Labels, f64)>) {
+        let _ = writeln!(self.text, "# HELP {} {}", name, help);
+        let _ = writeln!(self.text, "# TYPE {} gauge", name);
+        for (labels, value) in samples {
+            self.text.push_str(name);
+            if !labels.is_empty() {
+                let labels: Vec<String> = labels
+                    .iter()
+                    .map(|(label, value)| format!("{}=\"{}\"", label, escape_label(value)))
+                    .collect();
+                let _ = write!(self.text, "{{{}}}", labels.join(","));
+            }
+            let _ = writeln!(self.text, " {}", value);
+        }
+    }
+}
+
+/// Escape a label value per the exposition format: backslash, double quote
+/// and newline.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_gauges_with_escaped_labels() {
+        let mut out = Exposition::default();
+        out.gauge(
+            "neonfetch_info",
+            "Static host metadata.",
+            vec![(vec![("cpu", "Intel(R) \"Core\" i7\\x".to_string())], 1.0)],
+        );
+        out.gauge(
+            "neonfetch_temperature_celsius",
+            "Average.",
+            vec![(Vec::new(), 41.5)],
+        );
+        assert_eq!(
+            out.text,
+            "# HELP neonfetch_info Static host metadata.\n\
+             # TYPE neonfetch_info gauge\n\
+             neonfetch_info{cpu=\"Intel(R) \\\"Core\\\" i7\\\\x\"} 1\n\
+             # HELP neonfetch_temperature_celsius Average.\n\
+             # TYPE neonfetch_temperature_celsius gauge\n\
+             neonfetch_temperature_celsius 41.5\n"
+        );
+    }
+}
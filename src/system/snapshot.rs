@@ -0,0 +1,188 @@
+//! Field lists read back from `--json` output, for rendering a machine's info
+//! somewhere else (`--from-json`). Both the versioned document and the flat
+//! `{"key": "line"}` object of earlier releases are accepted.
+
+use super::info::{SystemInfoField, field_placeholders, info_field_key};
+use super::json::JSON_SCHEMA_VERSION;
+use crate::util::template::TemplateValue;
+use serde_json::{Map, Value};
+use std::fs;
+use std::io::{self, Read};
+
+/// Top-level JSON key holding the distro id used to pick the logo; it is
+/// metadata, not a field.
+pub const SNAPSHOT_DISTRO_KEY: &str = "distro_id";
+
+#[derive(Clone, Debug, Default)]
+pub struct Snapshot {
+    pub fields: Vec<SystemInfoField>,
+    pub distro_id: Option<String>,
+    pub hostname: Option<String>,
+}
+
+impl Snapshot {
+    /// Read a snapshot from `path`, or from stdin when `path` is `-`.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = if path == "-" {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .map_err(|err| format!("could not read snapshot from stdin: {err}"))?;
+            text
+        } else {
+            fs::read_to_string(path)
+                .map_err(|err| format!("could not read snapshot '{path}': {err}"))?
+        };
+        Self::parse(&text).map_err(|err| format!("invalid snapshot '{path}': {err}"))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let value: serde_json::Value = serde_json::from_str(text).map_err(|err| err.to_string())?;
+        let object = value
+            .as_object()
+            .ok_or_else(|| "expected a JSON object of field lines".to_string())?;
+        if let Some(version) = object.get("schema_version") {
+            return Self::from_document(object, version);
+        }
+
+        let mut snapshot = Self::default();
+        // `preserve_order` keeps the map in document order, which is the
+        // snapshot's field order.
+        for (key, line) in object {
+            let line = line
+                .as_str()
+                .ok_or_else(|| format!("field `{key}` must be a string"))?;
+            if key == SNAPSHOT_DISTRO_KEY {
+                snapshot.distro_id = Some(line.to_string());
+                continue;
+            }
+            snapshot.fields.push(SystemInfoField::new(
+                static_key(key.clone()),
+                line.to_string(),
+            ));
+        }
+        Ok(snapshot)
+    }
+
+    fn from_document(object: &Map<String, Value>, version: &Value) -> Result<Self, String> {
+        match version.as_u64() {
+            Some(version) if (1..=JSON_SCHEMA_VERSION).contains(&version) => {}
+            _ => {
+                return Err(format!(
+                    "unsupported schema_version {version}; expected 1 to {JSON_SCHEMA_VERSION}"
+                ));
+            }
+        }
+        let optional_text = |name: &str| match object.get(name) {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::String(text)) => Ok(Some(text.clone())),
+            Some(_) => Err(format!("`{name}` must be a string or null")),
+        };
+        let mut snapshot = Self {
+            distro_id: optional_text(SNAPSHOT_DISTRO_KEY)?,
+            hostname: optional_text("hostname")?,
+            ..Self::default()
+        };
+        let entries = object
+            .get("fields")
+            .and_then(Value::as_array)
+            .ok_or_else(|| "`fields` must be an array".to_string())?;
+        for (index, entry) in entries.iter().enumerate() {
+            let text = |name: &str| {
+                entry
+                    .get(name)
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| format!("fields[{index}] needs a string `{name}`"))
+            };
+            let (key, line) = (text("key")?, text("text")?);
+            if snapshot.fields.iter().any(|field| field.key == key) {
+                continue;
+            }
+            let mut field = SystemInfoField::new(static_key(key.to_string()), line.to_string());
+            // Keep the typed readings this build knows placeholders for, so
+            // templates and `--format prometheus` work on the snapshot too.
+            let names = field_placeholders(field.key, &[]).unwrap_or(&[]);
+            if let Some(values) = entry.get("values").and_then(Value::as_object) {
+                for name in names {
+                    let value = match values.get(*name) {
+                        Some(Value::String(text)) => TemplateValue::Text(text.clone()),
+                        Some(Value::Number(number)) => match number.as_i64() {
+                            Some(integer) => TemplateValue::Integer(integer),
+                            None => TemplateValue::Float(number.as_f64().unwrap_or(f64::NAN)),
+                        },
+                        _ => continue,
+                    };
+                    field.values.push((name, value));
+                }
+            }
+            snapshot.fields.push(field);
+        }
+        Ok(snapshot)
+    }
+
+    /// Resolve a `--show`/`--hide` key against the snapshot's fields.
+    pub fn field_key(&self, input: &str) -> Option<&'static str> {
+        self.fields
+            .iter()
+            .map(|field| field.key)
+            .find(|key| *key == input)
+    }
+}
+
+/// Built-in keys keep their static name; others (custom fields from the
+/// remote config) are leaked once, like config keys.
+fn static_key(key: String) -> &'static str {
+    info_field_key(&key, &[]).unwrap_or_else(|| Box::leak(key.into_boxed_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_field_order_and_reads_distro_id() {
+        let snapshot = Snapshot::parse(
+            r#"{"memory":"Memory: 1 / 2 (50%)", "header":"me@box\n-------",
+                "weather":"Weather: \"sunny\", {warm}","distro_id":"arch"}"#,
+        )
+        .expect("valid snapshot");
+        let keys: Vec<&str> = snapshot.fields.iter().map(|field| field.key).collect();
+        assert_eq!(keys, ["memory", "header", "weather"]);
+        assert_eq!(snapshot.fields[2].line, "Weather: \"sunny\", {warm}");
+        assert_eq!(snapshot.distro_id.as_deref(), Some("arch"));
+        assert_eq!(snapshot.field_key("weather"), Some("weather"));
+        assert_eq!(snapshot.field_key("gpu"), None);
+    }
+
+    #[test]
+    fn reads_versioned_documents_with_typed_values() {
+        let snapshot = Snapshot::parse(
+            r#"{"schema_version":1,"hostname":"far-away","distro_id":null,"fields":[
+                {"key":"memory","text":"Memory: 1 / 4","values":{"used_bytes":1024,"percent":25.5,"bogus":1}},
+                {"key":"weather","text":"Weather: sunny","values":{}}],"probes":[]}"#,
+        )
+        .expect("valid document");
+        assert_eq!(snapshot.hostname.as_deref(), Some("far-away"));
+        assert_eq!(snapshot.distro_id, None);
+        assert_eq!(snapshot.fields[0].line, "Memory: 1 / 4");
+        assert_eq!(
+            snapshot.fields[0].values,
+            [
+                ("percent", TemplateValue::Float(25.5)),
+                ("used_bytes", TemplateValue::Integer(1024))
+            ]
+        );
+        assert_eq!(snapshot.field_key("weather"), Some("weather"));
+
+        let err = Snapshot::parse(r#"{"schema_version":9,"fields":[]}"#).unwrap_err();
+        assert!(err.contains("unsupported schema_version 9"), "{err}");
+        assert!(Snapshot::parse(r#"{"schema_version":1,"fields":[{"key":"os"}]}"#).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_snapshots() {
+        assert!(Snapshot::parse("[1, 2]").is_err());
+        assert!(Snapshot::parse("{\"os\": 3}").is_err());
+        assert!(Snapshot::parse("{\"os\": ").is_err());
+    }
+}
@@ -0,0 +1,260 @@
+use crate::util::i18n::{Lang, localize_decimal};
+use crate::util::template::TemplateValue;
+use crate::util::units::ByteUnits;
+use std::fs;
+use std::path::Path;
+
+const SECTOR_BYTES: u64 = 512;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StorageDevice {
+    pub name: String,
+    pub model: Option<String>,
+    pub size_bytes: u64,
+    pub kind: StorageKind,
+    pub temperature_c: Option<f64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorageKind {
+    Hdd,
+    Ssd,
+    Nvme,
+}
+
+impl StorageKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            StorageKind::Hdd => "HDD",
+            StorageKind::Ssd => "SSD",
+            StorageKind::Nvme => "NVMe",
+        }
+    }
+}
+
+pub fn detect_storage() -> Option<Vec<StorageDevice>> {
+    #[cfg(target_os = "linux")]
+    {
+        let devices = read_block_devices(Path::new("/sys/block"));
+        if !devices.is_empty() {
+            return Some(devices);
+        }
+    }
+    None
+}
+
+/// Enumerate whole-disk block devices below a `/sys/block`-shaped directory.
+/// Virtual devices (loop, ram, zram, device-mapper) and empty drives are
+/// skipped; results are sorted by device name.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub fn read_block_devices(sys_block: &Path) -> Vec<StorageDevice> {
+    let Ok(entries) = fs::read_dir(sys_block) else {
+        return Vec::new();
+    };
+    let mut devices = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if is_virtual_block_device(&name) {
+            continue;
+        }
+        let base = entry.path();
+        let size_bytes = read_trimmed(&base.join("size"))
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(0)
+            .saturating_mul(SECTOR_BYTES);
+        if size_bytes == 0 {
+            continue;
+        }
+        let kind = if name.starts_with("nvme") {
+            StorageKind::Nvme
+        } else if read_trimmed(&base.join("queue/rotational")).as_deref() == Some("1") {
+            StorageKind::Hdd
+        } else {
+            StorageKind::Ssd
+        };
+        let model = read_trimmed(&base.join("device/model")).filter(|s| !s.is_empty());
+        let temperature_c = if kind == StorageKind::Nvme {
+            read_hwmon_temperature(&base.join("device"))
+        } else {
+            None
+        };
+        devices.push(StorageDevice {
+            name,
+            model,
+            size_bytes,
+            kind,
+            temperature_c,
+        });
+    }
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    devices
+}
+
+fn is_virtual_block_device(name: &str) -> bool {
+    ["loop", "ram", "zram", "dm-"]
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+}
+
+/// NVMe controllers expose a `hwmonN` directory whose first sensor is the
+/// composite drive temperature in millidegrees.
+fn read_hwmon_temperature(device: &Path) -> Option<f64> {
+    let entries = fs::read_dir(device).ok()?;
+    for entry in entries.flatten() {
+        if !entry.file_name().to_string_lossy().starts_with("hwmon") {
+            continue;
+        }
+        if let Some(milli) =
+            read_trimmed(&entry.path().join("temp1_input")).and_then(|s| s.parse::<i64>().ok())
+            && milli > 0
+        {
+            return Some(milli as f64 / 1000.0);
+        }
+    }
+    None
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+/// The `Storage ({name})` value for one device.
+pub fn format_storage_value(device: &StorageDevice, units: ByteUnits, lang: Lang) -> String {
+    let mut details = vec![
+        units.format(device.size_bytes),
+        device.kind.label().to_string(),
+    ];
+    if let Some(temp) = device.temperature_c {
+        details.push(localize_decimal(
+            format!("{:.1}°C", temp),
+            units.decimal_separator,
+        ));
+    }
+    format!(
+        "{} ({})",
+        device.model.as_deref().unwrap_or(lang.tr("Unknown")),
+        details.join(", ")
+    )
+}
+
+/// Template placeholders for one `storage` line.
+pub fn storage_values(
+    device: &StorageDevice,
+    units: ByteUnits,
+) -> Vec<(&'static str, TemplateValue)> {
+    let mut values = vec![
+        ("name", device.name.as_str().into()),
+        ("model", device.model.clone().unwrap_or_default().into()),
+        ("size", units.format(device.size_bytes).into()),
+        ("size_bytes", device.size_bytes.into()),
+        ("kind", device.kind.label().into()),
+    ];
+    if let Some(temp) = device.temperature_c {
+        values.push(("celsius", temp.into()));
+    }
+    values
+}
+
+/// Machine-output readings for every device: their `count`, then each
+/// device's placeholders suffixed with its index (`name_0`, `size_bytes_1`).
+pub fn storage_device_values(
+    devices: &[StorageDevice],
+    units: ByteUnits,
+) -> Vec<(&'static str, TemplateValue)> {
+    let mut values = vec![("count", devices.len().into())];
+    for (index, device) in devices.iter().enumerate() {
+        for (name, value) in storage_values(device, units) {
+            // Leaked once per run, like custom field keys.
+            let name: &'static str = Box::leak(format!("{name}_{index}").into_boxed_str());
+            values.push((name, value));
+        }
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::fixture::{temp_path, write};
+    use crate::util::units::UnitSystem;
+
+    #[test]
+    fn reads_devices_and_skips_virtual_ones() {
+        let root = temp_path("storage-sys-block");
+        write(&root, "sda/size", "1953525168\n");
+        write(&root, "sda/queue/rotational", "1\n");
+        write(&root, "sda/device/model", "WDC WD10EZEX-08W\n");
+        write(&root, "sdb/size", "976773168\n");
+        write(&root, "sdb/queue/rotational", "0\n");
+        write(&root, "nvme0n1/size", "1000215216\n");
+        write(&root, "nvme0n1/queue/rotational", "0\n");
+        write(&root, "nvme0n1/device/model", "Samsung SSD 980 1TB   \n");
+        write(&root, "nvme0n1/device/hwmon3/temp1_input", "41900\n");
+        write(&root, "loop0/size", "2048\n");
+        write(&root, "ram0/size", "8192\n");
+        write(&root, "dm-0/size", "8192\n");
+        write(&root, "sr0/size", "0\n");
+
+        let devices = read_block_devices(&root);
+        let names: Vec<&str> = devices.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["nvme0n1", "sda", "sdb"]);
+
+        assert_eq!(devices[0].kind, StorageKind::Nvme);
+        assert_eq!(devices[0].model.as_deref(), Some("Samsung SSD 980 1TB"));
+        assert_eq!(devices[0].temperature_c, Some(41.9));
+        assert_eq!(devices[1].kind, StorageKind::Hdd);
+        assert_eq!(devices[1].size_bytes, 1_000_204_886_016);
+        assert_eq!(devices[2].kind, StorageKind::Ssd);
+        assert_eq!(devices[2].model, None);
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn formats_one_line_per_device() {
+        let devices = [
+            StorageDevice {
+                name: "nvme0n1".to_string(),
+                model: Some("Samsung SSD 980 1TB".to_string()),
+                size_bytes: 1024 * 1024 * 1024 * 512,
+                kind: StorageKind::Nvme,
+                temperature_c: Some(41.9),
+            },
+            StorageDevice {
+                name: "sda".to_string(),
+                model: None,
+                size_bytes: 1024 * 1024 * 1024,
+                kind: StorageKind::Hdd,
+                temperature_c: None,
+            },
+        ];
+        let units = ByteUnits::default();
+        assert_eq!(
+            format_storage_value(&devices[0], units, Lang::En),
+            "Samsung SSD 980 1TB (512.00 GiB, NVMe, 41.9°C)"
+        );
+        assert_eq!(
+            format_storage_value(&devices[1], units, Lang::En),
+            "Unknown (1.00 GiB, HDD)"
+        );
+        let names: Vec<&str> = storage_values(&devices[1], units)
+            .iter()
+            .map(|(name, _)| *name)
+            .collect();
+        assert_eq!(names, ["name", "model", "size", "size_bytes", "kind"]);
+        let values = storage_device_values(&devices, units);
+        assert_eq!(values[0], ("count", TemplateValue::Integer(2)));
+        assert_eq!(values[1], ("name_0", TemplateValue::from("nvme0n1")));
+        assert_eq!(values[7], ("name_1", TemplateValue::from("sda")));
+        assert_eq!(values.len(), 1 + 6 + 5);
+        let decimal = ByteUnits {
+            system: UnitSystem::Decimal,
+            precision: 0,
+            decimal_separator: ',',
+        };
+        assert_eq!(
+            format_storage_value(&devices[0], decimal, Lang::En),
+            "Samsung SSD 980 1TB (550 GB, NVMe, 41,9°C)"
+        );
+    }
+}
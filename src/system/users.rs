@@ -0,0 +1,292 @@
+use crate::util::i18n::Lang;
+use crate::util::template::TemplateValue;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Size of a glibc `struct utmp` record on Linux (utmp, wtmp and btmp share it).
+const UTMP_RECORD_SIZE: usize = 384;
+/// Size of a `struct lastlog` record, one per uid.
+const LASTLOG_RECORD_SIZE: usize = 292;
+const USER_PROCESS: i16 = 7;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoginRecord {
+    pub user: String,
+    pub line: String,
+    pub host: String,
+    pub time: u64,
+}
+
+pub fn detect_users() -> Option<Vec<LoginRecord>> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    let data = read_utmp()?;
+    Some(parse_utmp_records(&data))
+}
+
+pub fn detect_last_login() -> Option<LoginRecord> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    let user = env::var("USER").or_else(|_| env::var("USERNAME")).ok()?;
+    let active = read_utmp()
+        .map(|data| parse_utmp_records(&data))
+        .unwrap_or_default();
+    fs::read("/var/log/wtmp")
+        .ok()
+        .and_then(|data| previous_login(&parse_utmp_records(&data), &user, &active))
+        .or_else(|| {
+            let uid = current_uid(Path::new("/proc/self/status"))?;
+            let data = fs::read("/var/log/lastlog").ok()?;
+            previous_lastlog_login(&data, uid, &user, &active)
+        })
+}
+
+/// Decode `USER_PROCESS` entries from raw utmp/wtmp bytes. Trailing partial
+/// records are ignored.
+pub fn parse_utmp_records(data: &[u8]) -> Vec<LoginRecord> {
+    data.chunks_exact(UTMP_RECORD_SIZE)
+        .filter(|record| i16::from_ne_bytes([record[0], record[1]]) == USER_PROCESS)
+        .map(|record| LoginRecord {
+            line: c_string(&record[8..40]),
+            user: c_string(&record[44..76]),
+            host: c_string(&record[76..332]),
+            time: u32::from_ne_bytes([record[340], record[341], record[342], record[343]]) as u64,
+        })
+        .filter(|record| !record.user.is_empty())
+        .collect()
+}
+
+fn read_utmp() -> Option<Vec<u8>> {
+    fs::read("/var/run/utmp")
+        .or_else(|_| fs::read("/run/utmp"))
+        .ok()
+}
+
+/// Most recent wtmp login for `user`, skipping the session that is still open
+/// in utmp (normally the one running neonfetch).
+fn previous_login(wtmp: &[LoginRecord], user: &str, active: &[LoginRecord]) -> Option<LoginRecord> {
+    let mut logins: Vec<&LoginRecord> = wtmp.iter().filter(|r| r.user == user).collect();
+    logins.sort_by_key(|r| r.time);
+    logins
+        .into_iter()
+        .rev()
+        .find(|r| !active.contains(r))
+        .cloned()
+}
+
+/// The lastlog entry for `uid`, unless it is the session still open in utmp:
+/// lastlog only keeps the latest login, so there is no earlier one to show.
+fn previous_lastlog_login(
+    data: &[u8],
+    uid: u32,
+    user: &str,
+    active: &[LoginRecord],
+) -> Option<LoginRecord> {
+    parse_lastlog_entry(data, uid, user).filter(|entry| !active.contains(entry))
+}
+
+fn parse_lastlog_entry(data: &[u8], uid: u32, user: &str) -> Option<LoginRecord> {
+    let start = uid as usize * LASTLOG_RECORD_SIZE;
+    let record = data.get(start..start + LASTLOG_RECORD_SIZE)?;
+    let time = u32::from_ne_bytes([record[0], record[1], record[2], record[3]]) as u64;
+    (time > 0).then(|| LoginRecord {
+        user: user.to_string(),
+        line: c_string(&record[4..36]),
+        host: c_string(&record[36..292]),
+        time,
+    })
+}
+
+fn current_uid(status_path: &Path) -> Option<u32> {
+    fs::read_to_string(status_path)
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("Uid:"))
+        .and_then(|ids| ids.split_whitespace().next())
+        .and_then(|uid| uid.parse().ok())
+}
+
+fn c_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).trim().to_string()
+}
+
+/// Logged-in user names in first-seen order, with their session counts.
+fn group_sessions(sessions: &[LoginRecord]) -> Vec<(&str, usize)> {
+    let mut users: Vec<(&str, usize)> = Vec::new();
+    for session in sessions {
+        match users.iter_mut().find(|(name, _)| *name == session.user) {
+            Some((_, count)) => *count += 1,
+            None => users.push((&session.user, 1)),
+        }
+    }
+    users
+}
+
+pub fn format_users_value(sessions: &[LoginRecord], lang: Lang) -> String {
+    let users = group_sessions(sessions);
+    if users.is_empty() {
+        return "0".to_string();
+    }
+    let names: Vec<String> = users
+        .iter()
+        .map(|(name, count)| {
+            if *count > 1 {
+                format!("{} x{}", name, count)
+            } else {
+                name.to_string()
+            }
+        })
+        .collect();
+    format!(
+        "{} ({} {}: {})",
+        users.len(),
+        sessions.len(),
+        lang.tr(if sessions.len() == 1 {
+            "session"
+        } else {
+            "sessions"
+        }),
+        names.join(", ")
+    )
+}
+
+/// Template placeholders for the `users` field.
+pub fn users_values(sessions: &[LoginRecord]) -> Vec<(&'static str, TemplateValue)> {
+    let users = group_sessions(sessions);
+    let names: Vec<&str> = users.iter().map(|(name, _)| *name).collect();
+    vec![
+        ("count", users.len().into()),
+        ("sessions", sessions.len().into()),
+        ("names", names.join(", ").into()),
+    ]
+}
+
+/// The `Last Login` value; `time` is `record.time` formatted for display.
+pub fn format_last_login_value(record: &LoginRecord, time: &str, lang: Lang) -> String {
+    let mut value = time.to_string();
+    if !record.line.is_empty() {
+        value.push_str(&format!(" {} {}", lang.tr("on"), record.line));
+    }
+    if !record.host.is_empty() {
+        value.push_str(&format!(" {} {}", lang.tr("from"), record.host));
+    }
+    value
+}
+
+/// Template placeholders for the `last_login` field.
+pub fn last_login_values(record: &LoginRecord, time: &str) -> Vec<(&'static str, TemplateValue)> {
+    vec![
+        ("time", time.into()),
+        ("timestamp", record.time.into()),
+        ("tty", record.line.as_str().into()),
+        ("host", record.host.as_str().into()),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utmp_record(kind: i16, user: &str, line: &str, host: &str, time: u32) -> Vec<u8> {
+        let mut record = vec![0u8; UTMP_RECORD_SIZE];
+        record[0..2].copy_from_slice(&kind.to_ne_bytes());
+        record[8..8 + line.len()].copy_from_slice(line.as_bytes());
+        record[44..44 + user.len()].copy_from_slice(user.as_bytes());
+        record[76..76 + host.len()].copy_from_slice(host.as_bytes());
+        record[340..344].copy_from_slice(&time.to_ne_bytes());
+        record
+    }
+
+    #[test]
+    fn counts_users_and_sessions_from_utmp() {
+        let mut data = utmp_record(2, "reboot", "~", "6.8.0", 100);
+        data.extend(utmp_record(USER_PROCESS, "alice", "pts/0", "10.0.0.5", 200));
+        data.extend(utmp_record(USER_PROCESS, "bob", "tty1", "", 300));
+        data.extend(utmp_record(USER_PROCESS, "alice", "pts/1", "", 400));
+        data.extend([0u8; 10]);
+
+        let sessions = parse_utmp_records(&data);
+        assert_eq!(sessions.len(), 3);
+        assert_eq!(
+            sessions[0],
+            LoginRecord {
+                user: "alice".to_string(),
+                line: "pts/0".to_string(),
+                host: "10.0.0.5".to_string(),
+                time: 200,
+            }
+        );
+        assert_eq!(
+            format_users_value(&sessions, Lang::En),
+            "2 (3 sessions: alice x2, bob)"
+        );
+        assert_eq!(
+            users_values(&sessions),
+            [
+                ("count", TemplateValue::Integer(2)),
+                ("sessions", TemplateValue::Integer(3)),
+                ("names", TemplateValue::Text("alice, bob".to_string())),
+            ]
+        );
+        assert_eq!(format_users_value(&[], Lang::En), "0");
+        assert_eq!(
+            format_users_value(&sessions[..1], Lang::ZhCn),
+            "1 (1 会话: alice)"
+        );
+    }
+
+    #[test]
+    fn previous_login_skips_the_active_session() {
+        let wtmp = parse_utmp_records(
+            &[
+                utmp_record(USER_PROCESS, "alice", "pts/0", "10.0.0.5", 100),
+                utmp_record(USER_PROCESS, "bob", "pts/1", "10.0.0.9", 150),
+                utmp_record(USER_PROCESS, "alice", "pts/2", "10.0.0.7", 200),
+            ]
+            .concat(),
+        );
+        let active = vec![wtmp[2].clone()];
+
+        let previous = previous_login(&wtmp, "alice", &active).expect("previous login");
+        assert_eq!(previous.time, 100);
+        assert_eq!(
+            format_last_login_value(&previous, "1970-01-01 00:01 UTC", Lang::En),
+            "1970-01-01 00:01 UTC on pts/0 from 10.0.0.5"
+        );
+
+        assert_eq!(
+            previous_login(&wtmp, "alice", &[]).map(|r| r.time),
+            Some(200)
+        );
+        assert_eq!(previous_login(&wtmp, "carol", &[]), None);
+    }
+
+    #[test]
+    fn reads_lastlog_entry_by_uid() {
+        let mut data = vec![0u8; LASTLOG_RECORD_SIZE * 2];
+        let record = &mut data[LASTLOG_RECORD_SIZE..];
+        record[0..4].copy_from_slice(&1_728_047_580u32.to_ne_bytes());
+        record[4..8].copy_from_slice(b"tty1");
+
+        let entry = parse_lastlog_entry(&data, 1, "alice").expect("lastlog entry");
+        assert_eq!(entry.line, "tty1");
+        assert_eq!(entry.host, "");
+        assert_eq!(entry.time, 1_728_047_580);
+        assert_eq!(parse_lastlog_entry(&data, 0, "root"), None);
+        assert_eq!(parse_lastlog_entry(&data, 5, "nobody"), None);
+
+        let current = parse_utmp_records(&utmp_record(
+            USER_PROCESS,
+            "alice",
+            "tty1",
+            "",
+            1_728_047_580,
+        ));
+        assert_eq!(previous_lastlog_login(&data, 1, "alice", &current), None);
+        assert_eq!(previous_lastlog_login(&data, 1, "alice", &[]), Some(entry));
+    }
+}
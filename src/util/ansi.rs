@@ -1,3 +1,5 @@
+use crate::util::color::ansi256_rgb;
+
 /// Parse text containing ANSI escape sequences.
 /// Returns a vector of (ansi_sequence, char); ansi_sequence is empty for printable chars; char='\0' for raw ANSI parts.
 pub fn parse_ansi_text(text: &str) -> Vec<(String, char)> {
@@ -68,6 +70,211 @@ pub fn parse_ansi_text(text: &str) -> Vec<(String, char)> {
     out
 }
 
+/// Text attributes set by SGR sequences, as a bitset.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Attrs(u8);
+
+impl Attrs {
+    pub const NONE: Attrs = Attrs(0);
+    pub const BOLD: Attrs = Attrs(1);
+    pub const DIM: Attrs = Attrs(1 << 1);
+    pub const ITALIC: Attrs = Attrs(1 << 2);
+    pub const UNDERLINE: Attrs = Attrs(1 << 3);
+    pub const BLINK: Attrs = Attrs(1 << 4);
+    pub const REVERSE: Attrs = Attrs(1 << 5);
+    pub const STRIKE: Attrs = Attrs(1 << 6);
+
+    /// Each attribute with the SGR parameters that turn it on and off.
+    pub const CODES: [(Attrs, u8, u8); 7] = [
+        (Attrs::BOLD, 1, 22),
+        (Attrs::DIM, 2, 22),
+        (Attrs::ITALIC, 3, 23),
+        (Attrs::UNDERLINE, 4, 24),
+        (Attrs::BLINK, 5, 25),
+        (Attrs::REVERSE, 7, 27),
+        (Attrs::STRIKE, 9, 29),
+    ];
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn union(self, other: Attrs) -> Attrs {
+        Attrs(self.0 | other.0)
+    }
+
+    pub const fn intersection(self, other: Attrs) -> Attrs {
+        Attrs(self.0 & other.0)
+    }
+
+    pub fn intersects(self, other: Attrs) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    pub fn insert(&mut self, other: Attrs) {
+        self.0 |= other.0;
+    }
+
+    pub fn remove(&mut self, other: Attrs) {
+        self.0 &= !other.0;
+    }
+}
+
+impl std::ops::BitOr for Attrs {
+    type Output = Attrs;
+
+    fn bitor(self, other: Attrs) -> Attrs {
+        self.union(other)
+    }
+}
+
+/// A color named by an SGR sequence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SgrColor {
+    /// xterm-256 palette index; 0-15 are the ANSI colors.
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+impl SgrColor {
+    /// The color's RGB, taking xterm's defaults for palette entries.
+    pub fn rgb(self) -> (u8, u8, u8) {
+        match self {
+            Self::Indexed(index) => ansi256_rgb(index),
+            Self::Rgb(r, g, b) => (r, g, b),
+        }
+    }
+}
+
+/// Colors and attributes in effect after a run of SGR sequences; `None`
+/// colors are the terminal defaults.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SgrState {
+    pub fg: Option<SgrColor>,
+    pub bg: Option<SgrColor>,
+    pub attrs: Attrs,
+}
+
+impl SgrState {
+    /// Apply every SGR sequence in `seq`; other escape sequences are ignored.
+    pub fn apply(&mut self, seq: &str) {
+        for part in seq.split('\x1b').skip(1) {
+            if let Some(params) = part.strip_prefix('[').and_then(|p| p.strip_suffix('m')) {
+                self.apply_params(params);
+            }
+        }
+    }
+
+    /// One SGR sequence that sets this state from the defaults; empty for
+    /// the default state itself.
+    pub fn sequence(&self) -> String {
+        let mut params: Vec<String> = Attrs::CODES
+            .iter()
+            .filter(|(attr, _, _)| self.attrs.intersects(*attr))
+            .map(|(_, on, _)| on.to_string())
+            .collect();
+        for (color, base) in [(self.fg, 30), (self.bg, 40)] {
+            match color {
+                Some(SgrColor::Indexed(index @ 0..8)) => params.push((base + index).to_string()),
+                Some(SgrColor::Indexed(index @ 8..16)) => {
+                    params.push((base + 60 + index - 8).to_string())
+                }
+                Some(SgrColor::Indexed(index)) => params.push(format!("{};5;{}", base + 8, index)),
+                Some(SgrColor::Rgb(r, g, b)) => {
+                    params.push(format!("{};2;{};{};{}", base + 8, r, g, b))
+                }
+                None => {}
+            }
+        }
+        if params.is_empty() {
+            String::new()
+        } else {
+            format!("\x1b[{}m", params.join(";"))
+        }
+    }
+
+    fn apply_params(&mut self, params: &str) {
+        if params.is_empty() {
+            *self = SgrState::default();
+            return;
+        }
+        let mut groups = params.split(';');
+        while let Some(group) = groups.next() {
+            // Colon sub-parameters (38:2::r:g:b, 4:0) belong to their code
+            // alone; semicolon forms (38;2;r;g;b) take the following groups.
+            let mut subs = group.split(':');
+            let code = subs.next().map_or(0, parse_param);
+            let subs: Vec<u16> = subs.map(parse_param).collect();
+            let colon = group.contains(':');
+            match code {
+                38 | 48 => {
+                    let color = if colon {
+                        colon_color(&subs)
+                    } else {
+                        extended_color(&mut groups.by_ref().map(parse_param))
+                    };
+                    if code == 38 {
+                        self.fg = color;
+                    } else {
+                        self.bg = color;
+                    }
+                }
+                // `4:0` turns underline off; other styles (curly, dotted) are
+                // still underlines.
+                4 if subs.first() == Some(&0) => self.apply_code(24),
+                _ => self.apply_code(code),
+            }
+        }
+    }
+
+    fn apply_code(&mut self, code: u16) {
+        match code {
+            0 => *self = SgrState::default(),
+            30..=37 => self.fg = Some(SgrColor::Indexed(code as u8 - 30)),
+            90..=97 => self.fg = Some(SgrColor::Indexed(code as u8 - 90 + 8)),
+            40..=47 => self.bg = Some(SgrColor::Indexed(code as u8 - 40)),
+            100..=107 => self.bg = Some(SgrColor::Indexed(code as u8 - 100 + 8)),
+            39 => self.fg = None,
+            49 => self.bg = None,
+            _ => {
+                for (attr, on, off) in Attrs::CODES {
+                    if code == on as u16 {
+                        self.attrs.insert(attr);
+                    } else if code == off as u16 {
+                        self.attrs.remove(attr);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn parse_param(param: &str) -> u16 {
+    param.parse().unwrap_or(0)
+}
+
+/// The color after a 38/48 code: `5;n` or `2;r;g;b`.
+fn extended_color(codes: &mut impl Iterator<Item = u16>) -> Option<SgrColor> {
+    let mut next = || codes.next().map(|code| code.min(255) as u8);
+    match next()? {
+        5 => Some(SgrColor::Indexed(next()?)),
+        2 => Some(SgrColor::Rgb(next()?, next()?, next()?)),
+        _ => None,
+    }
+}
+
+/// The color from 38/48 colon sub-parameters: `5:n`, `2:cs:r:g:b` with an
+/// optional (often empty) colorspace id, or the common `2:r:g:b` shorthand.
+fn colon_color(subs: &[u16]) -> Option<SgrColor> {
+    let channel = |index: usize| subs.get(index).map(|&value| value.min(255) as u8);
+    match subs.first()? {
+        5 => Some(SgrColor::Indexed(channel(1)?)),
+        2 if subs.len() >= 5 => Some(SgrColor::Rgb(channel(2)?, channel(3)?, channel(4)?)),
+        2 => Some(SgrColor::Rgb(channel(1)?, channel(2)?, channel(3)?)),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -119,4 +326,59 @@ mod tests {
         assert_eq!(result[1].0, "\x1b[1m");
         assert_eq!(result[2].1, 'B');
     }
+
+    #[test]
+    fn sgr_state_tracks_colors_and_attributes() {
+        let mut state = SgrState::default();
+        state.apply("\x1b[1;4;31m\x1b[48;5;236m");
+        assert_eq!(state.fg, Some(SgrColor::Indexed(1)));
+        assert_eq!(state.bg, Some(SgrColor::Indexed(236)));
+        assert_eq!(state.attrs, Attrs::BOLD | Attrs::UNDERLINE);
+
+        state.apply("\x1b[22;38;2;10;20;30m\x1b[2K\x1b[102m");
+        assert_eq!(state.fg, Some(SgrColor::Rgb(10, 20, 30)));
+        assert_eq!(state.bg, Some(SgrColor::Indexed(10)));
+        assert_eq!(state.attrs, Attrs::UNDERLINE);
+
+        state.apply("\x1b[m");
+        assert_eq!(state, SgrState::default());
+    }
+
+    #[test]
+    fn sgr_state_round_trips_through_one_sequence() {
+        let mut state = SgrState::default();
+        assert_eq!(state.sequence(), "");
+        state.apply("\x1b[1;31m\x1b[38;2;1;2;3m\x1b[4m\x1b[103m\x1b[48;5;236m\x1b[24m");
+        assert_eq!(state.sequence(), "\x1b[1;38;2;1;2;3;48;5;236m");
+
+        let mut replayed = SgrState::default();
+        replayed.apply(&state.sequence());
+        assert_eq!(replayed, state);
+
+        let state = SgrState {
+            fg: Some(SgrColor::Indexed(9)),
+            bg: Some(SgrColor::Indexed(4)),
+            attrs: Attrs::NONE,
+        };
+        assert_eq!(state.sequence(), "\x1b[91;44m");
+    }
+
+    #[test]
+    fn sgr_state_reads_colon_sub_parameters() {
+        let mut state = SgrState::default();
+        state.apply("\x1b[38:2::255:0:0m");
+        assert_eq!(state.fg, Some(SgrColor::Rgb(255, 0, 0)));
+
+        state.apply("\x1b[1;48:2:0:10:20:30m");
+        assert_eq!(state.bg, Some(SgrColor::Rgb(10, 20, 30)));
+        assert_eq!(state.attrs, Attrs::BOLD);
+
+        state.apply("\x1b[38:5:208;4:3m");
+        assert_eq!(state.fg, Some(SgrColor::Indexed(208)));
+        assert_eq!(state.attrs, Attrs::BOLD | Attrs::UNDERLINE);
+
+        state.apply("\x1b[48:2:1:2:3;4:0m");
+        assert_eq!(state.bg, Some(SgrColor::Rgb(1, 2, 3)));
+        assert_eq!(state.attrs, Attrs::BOLD);
+    }
 }
@@ -0,0 +1,154 @@
+//! Display-width cell model for the animation renderers.
+//!
+//! Each line is laid out as one `Cell` per terminal column: wide glyphs take
+//! their own column plus a continuation cell, and zero-width marks ride along
+//! with the glyph before them, so column math never has to count `char`s.
+
+use crate::util::ansi::{SgrColor, SgrState, parse_ansi_text};
+use crate::util::width::char_width;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Cell {
+    /// Escape sequences that preceded this cell in the source line.
+    pub ansi: String,
+    /// Base character; `'\0'` for the right half of a wide glyph.
+    pub ch: char,
+    /// Zero-width marks (combining accents, joiners, variation selectors)
+    /// drawn together with `ch`.
+    pub marks: String,
+    /// Columns the glyph covers: 1 or 2, and 0 for a continuation cell.
+    pub width: usize,
+    /// Foreground color the source line's escapes gave this cell, if any.
+    /// Palette colors stay indexed so they can follow the terminal theme.
+    pub base: Option<SgrColor>,
+    /// Background color the source line's escapes gave this cell, if any.
+    pub base_bg: Option<SgrColor>,
+    /// Logo palette slot (`${c1}` is 0) a logo file's marker put this cell
+    /// in, if any.
+    pub slot: Option<usize>,
+}
+
+impl Cell {
+    fn glyph(ansi: String, ch: char, width: usize) -> Self {
+        Self {
+            ansi,
+            ch,
+            marks: String::new(),
+            width,
+            base: None,
+            base_bg: None,
+            slot: None,
+        }
+    }
+
+    /// The right half of the wide glyph in the previous column.
+    pub fn is_continuation(&self) -> bool {
+        self.width == 0
+    }
+
+    /// A space; continuation cells belong to their glyph and are not blank.
+    pub fn is_blank(&self) -> bool {
+        self.width > 0 && self.ch == ' '
+    }
+}
+
+/// Lay out one line of text (which may contain ANSI escapes) as cells.
+/// Control characters and marks with nothing to attach to are dropped, as are
+/// escapes after the last glyph.
+pub fn layout_line(text: &str) -> Vec<Cell> {
+    let mut cells: Vec<Cell> = Vec::new();
+    let mut pending_ansi = String::new();
+    let mut sgr = SgrState::default();
+    for (ansi, ch) in parse_ansi_text(text) {
+        if !ansi.is_empty() {
+            sgr.apply(&ansi);
+            pending_ansi.push_str(&ansi);
+            continue;
+        }
+        if ch.is_control() {
+            continue;
+        }
+        match char_width(ch) {
+            0 => {
+                if let Some(owner) = cells.iter_mut().rev().find(|cell| !cell.is_continuation()) {
+                    owner.marks.push(ch);
+                }
+            }
+            width => {
+                let mut cell = Cell::glyph(std::mem::take(&mut pending_ansi), ch, width);
+                cell.base = sgr.fg;
+                cell.base_bg = sgr.bg;
+                cells.push(cell);
+                if width == 2 {
+                    cells.push(Cell::glyph(String::new(), '\0', 0));
+                }
+            }
+        }
+    }
+    cells
+}
+
+pub fn layout_lines(lines: &[String]) -> Vec<Vec<Cell>> {
+    lines.iter().map(|line| layout_line(line)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns(cells: &[Cell]) -> Vec<(char, usize)> {
+        cells.iter().map(|cell| (cell.ch, cell.width)).collect()
+    }
+
+    #[test]
+    fn wide_glyphs_take_two_cells_and_marks_attach() {
+        let cells = layout_line("a内e\u{301}🦀");
+        assert_eq!(
+            columns(&cells),
+            [
+                ('a', 1),
+                ('内', 2),
+                ('\0', 0),
+                ('e', 1),
+                ('🦀', 2),
+                ('\0', 0)
+            ]
+        );
+        assert_eq!(cells[3].marks, "\u{301}");
+        assert!(cells[2].is_continuation() && !cells[2].is_blank());
+
+        // A mark after a wide glyph joins the glyph, not its right half.
+        let cells = layout_line("👍\u{1F3FD}x");
+        assert_eq!(cells[0].marks, "\u{1F3FD}");
+        assert_eq!(columns(&cells), [('👍', 2), ('\0', 0), ('x', 1)]);
+    }
+
+    #[test]
+    fn escapes_stay_with_the_following_cell() {
+        let cells = layout_line("\x1b[1mA\x1b[0m \x1b[31m");
+        assert_eq!(columns(&cells), [('A', 1), (' ', 1)]);
+        assert_eq!(cells[0].ansi, "\x1b[1m");
+        assert_eq!(cells[1].ansi, "\x1b[0m");
+        assert!(cells[1].is_blank());
+        assert!(layout_line("\u{301}\t").is_empty());
+    }
+
+    #[test]
+    fn cells_record_their_source_color() {
+        let cells = layout_line("\x1b[31mA\x1b[38;5;21mB\x1b[39mC\x1b[38;2;1;2;3mD");
+        let bases: Vec<_> = cells.iter().map(|cell| cell.base).collect();
+        assert_eq!(
+            bases,
+            [
+                Some(SgrColor::Indexed(1)),
+                Some(SgrColor::Indexed(21)),
+                None,
+                Some(SgrColor::Rgb(1, 2, 3))
+            ]
+        );
+
+        let cells = layout_line("\x1b[48;2;9;8;7m▀\x1b[49m▀");
+        assert_eq!(cells[0].base_bg, Some(SgrColor::Rgb(9, 8, 7)));
+        assert_eq!(cells[1].base_bg, None);
+    }
+}
@@ -0,0 +1,528 @@
+//! Terminal color depth: detection from the environment and terminfo, and
+//! quantization of 24-bit colors to the xterm 256-color and 16-color palettes.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// `--color-mode` / `color_mode` setting; `Auto` detects the depth.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorMode {
+    #[default]
+    Auto,
+    TrueColor,
+    Ansi256,
+    Ansi16,
+    None,
+}
+
+impl ColorMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "auto" => Some(Self::Auto),
+            "truecolor" | "24bit" => Some(Self::TrueColor),
+            "256" | "ansi256" => Some(Self::Ansi256),
+            "16" | "ansi16" => Some(Self::Ansi16),
+            "none" | "off" => Some(Self::None),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::TrueColor => "truecolor",
+            Self::Ansi256 => "256",
+            Self::Ansi16 => "16",
+            Self::None => "none",
+        }
+    }
+
+    pub fn resolve(self) -> ColorDepth {
+        match self {
+            Self::Auto => detect_color_depth(),
+            Self::TrueColor => ColorDepth::TrueColor,
+            Self::Ansi256 => ColorDepth::Ansi256,
+            Self::Ansi16 => ColorDepth::Ansi16,
+            Self::None => ColorDepth::None,
+        }
+    }
+}
+
+/// `--logo-colors` / `logo_colors`: what happens to colors a logo file
+/// brings along.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogoColors {
+    /// Strip them; the animation colors every cell.
+    #[default]
+    Replace,
+    /// Show them as they are; only uncolored cells animate.
+    Keep,
+    /// Mix each cell's own color halfway with the animation color.
+    Blend,
+    /// Keep each cell's hue and let the animation drive its brightness.
+    Modulate,
+}
+
+impl LogoColors {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "replace" => Some(Self::Replace),
+            "keep" => Some(Self::Keep),
+            "blend" => Some(Self::Blend),
+            "modulate" => Some(Self::Modulate),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Replace => "replace",
+            Self::Keep => "keep",
+            Self::Blend => "blend",
+            Self::Modulate => "modulate",
+        }
+    }
+
+    /// Combine a cell's own color with the animation's color for it.
+    pub fn apply(self, base: (u8, u8, u8), animated: (u8, u8, u8)) -> (u8, u8, u8) {
+        let channels = |f: &dyn Fn(u8, u8) -> f32| {
+            let mix = |a: u8, b: u8| f(a, b).round().clamp(0.0, 255.0) as u8;
+            (
+                mix(base.0, animated.0),
+                mix(base.1, animated.1),
+                mix(base.2, animated.2),
+            )
+        };
+        match self {
+            Self::Replace => animated,
+            Self::Keep => base,
+            Self::Blend => channels(&|a, b| (a as f32 + b as f32) / 2.0),
+            Self::Modulate => {
+                let luma = (0.299 * animated.0 as f32
+                    + 0.587 * animated.1 as f32
+                    + 0.114 * animated.2 as f32)
+                    / 255.0;
+                let gain = 0.35 + 0.9 * luma;
+                channels(&|a, _| a as f32 * gain)
+            }
+        }
+    }
+}
+
+/// Colors the output terminal can show.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorDepth {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+    None,
+}
+
+/// A color as it will be emitted, as foreground or background.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TermColor {
+    Rgb(u8, u8, u8),
+    /// xterm-256 palette index.
+    Indexed(u8),
+    /// One of the 16 ANSI colors, 0-15.
+    Basic(u8),
+}
+
+impl ColorDepth {
+    /// Map a 24-bit color to this depth; `None` means no color at all.
+    pub fn color(self, rgb: (u8, u8, u8)) -> Option<TermColor> {
+        match self {
+            Self::TrueColor => Some(TermColor::Rgb(rgb.0, rgb.1, rgb.2)),
+            Self::Ansi256 => Some(TermColor::Indexed(nearest_ansi256(rgb))),
+            Self::Ansi16 => Some(TermColor::Basic(nearest_ansi16(rgb))),
+            Self::None => None,
+        }
+    }
+
+    /// Dither amplitude that spreads a gradient across neighbouring palette
+    /// entries: about one step of the 256-color cube, a coarser one for 16.
+    pub fn dither_step(self) -> f32 {
+        match self {
+            Self::Ansi256 => 40.0,
+            Self::Ansi16 => 96.0,
+            Self::TrueColor | Self::None => 0.0,
+        }
+    }
+}
+
+/// Detect the depth from the environment: `NO_COLOR`, `COLORTERM`,
+/// `TERM_PROGRAM`, `TERM`, then the terminfo `colors` capability.
+pub fn detect_color_depth() -> ColorDepth {
+    detect_from(&|name| env::var(name).ok(), &terminfo_max_colors)
+}
+
+fn detect_from(
+    var: &dyn Fn(&str) -> Option<String>,
+    terminfo_colors: &dyn Fn(&str) -> Option<i32>,
+) -> ColorDepth {
+    let var = |name: &str| var(name).filter(|value| !value.is_empty());
+    if var("NO_COLOR").is_some() {
+        return ColorDepth::None;
+    }
+    if let Some(colorterm) = var("COLORTERM")
+        && matches!(
+            colorterm.to_ascii_lowercase().as_str(),
+            "truecolor" | "24bit"
+        )
+    {
+        return ColorDepth::TrueColor;
+    }
+    match var("TERM_PROGRAM").as_deref() {
+        Some("iTerm.app" | "WezTerm" | "vscode" | "ghostty") => return ColorDepth::TrueColor,
+        Some("Apple_Terminal") => return ColorDepth::Ansi256,
+        _ => {}
+    }
+    let Some(term) = var("TERM") else {
+        // Windows consoles set no TERM and have rendered 24-bit color since
+        // Windows 10. Elsewhere no TERM says nothing about the terminal, so
+        // stay with the 16 colors nearly everything understands.
+        return if cfg!(windows) {
+            ColorDepth::TrueColor
+        } else {
+            ColorDepth::Ansi16
+        };
+    };
+    if term == "dumb" {
+        return ColorDepth::None;
+    }
+    if term.ends_with("-direct")
+        || term.contains("truecolor")
+        || term.contains("24bit")
+        || matches!(
+            term.as_str(),
+            "xterm-kitty" | "xterm-ghostty" | "alacritty" | "foot" | "wezterm"
+        )
+    {
+        return ColorDepth::TrueColor;
+    }
+    match terminfo_colors(&term) {
+        Some(colors) if colors >= 1 << 24 => ColorDepth::TrueColor,
+        Some(colors) if colors >= 256 => ColorDepth::Ansi256,
+        Some(colors) if colors >= 8 => ColorDepth::Ansi16,
+        Some(_) => ColorDepth::None,
+        None if term.contains("256color") => ColorDepth::Ansi256,
+        None => ColorDepth::Ansi16,
+    }
+}
+
+/// `colors` (max_colors) from the compiled terminfo entry for `term`.
+fn terminfo_max_colors(term: &str) -> Option<i32> {
+    let first = term.chars().next()?;
+    if term.contains('/') {
+        return None;
+    }
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    if let Some(dir) = env::var_os("TERMINFO") {
+        dirs.push(dir.into());
+    }
+    if let Some(home) = env::var_os("HOME") {
+        dirs.push(PathBuf::from(home).join(".terminfo"));
+    }
+    if let Some(list) = env::var_os("TERMINFO_DIRS") {
+        dirs.extend(env::split_paths(&list).filter(|dir| !dir.as_os_str().is_empty()));
+    }
+    for dir in [
+        "/etc/terminfo",
+        "/lib/terminfo",
+        "/usr/share/terminfo",
+        "/usr/lib/terminfo",
+    ] {
+        dirs.push(dir.into());
+    }
+    dirs.iter()
+        .flat_map(|dir| {
+            // Linux uses the first letter; macOS uses its hex code.
+            [
+                dir.join(first.to_string()).join(term),
+                dir.join(format!("{:x}", first as u32)).join(term),
+            ]
+        })
+        .find_map(|path| fs::read(path).ok())
+        .and_then(|data| parse_terminfo_colors(&data))
+}
+
+/// Index of `max_colors` in the terminfo numbers section.
+const MAX_COLORS_INDEX: usize = 13;
+
+/// Read `max_colors` from a compiled terminfo file (legacy 16-bit or the
+/// extended 32-bit number format). Absent capabilities read as `-1`.
+fn parse_terminfo_colors(data: &[u8]) -> Option<i32> {
+    let short = |offset: usize| -> Option<i32> {
+        let bytes = data.get(offset..offset + 2)?;
+        Some(i16::from_le_bytes([bytes[0], bytes[1]]) as i32)
+    };
+    let number_size = match short(0)? {
+        0o432 => 2,
+        0o1036 => 4,
+        _ => return None,
+    };
+    let names_size = short(2)? as usize;
+    let bool_count = short(4)? as usize;
+    let number_count = short(6)? as usize;
+    if MAX_COLORS_INDEX >= number_count {
+        return Some(-1);
+    }
+    let mut offset = 12 + names_size + bool_count;
+    offset += offset % 2; // numbers start on an even byte
+    let at = offset + MAX_COLORS_INDEX * number_size;
+    let bytes = data.get(at..at + number_size)?;
+    Some(match number_size {
+        2 => i16::from_le_bytes([bytes[0], bytes[1]]) as i32,
+        _ => i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+    })
+}
+
+/// xterm's defaults for the 16 ANSI colors; themes vary, but these are what
+/// the nearest-color match assumes.
+const ANSI16: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Nearest xterm-256 entry: the closest 6x6x6 cube color or the closest of
+/// the 24 grays, whichever is perceptually nearer. Indices 0-15 are skipped
+/// because themes redefine them.
+pub fn nearest_ansi256(rgb: (u8, u8, u8)) -> u8 {
+    let level = |channel: u8| {
+        CUBE_LEVELS
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, level)| (**level as i32 - channel as i32).abs())
+            .map(|(index, _)| index)
+            .unwrap_or(0)
+    };
+    let (r, g, b) = (level(rgb.0), level(rgb.1), level(rgb.2));
+    let cube = (16 + 36 * r + 6 * g + b) as u8;
+    let cube_rgb = (CUBE_LEVELS[r], CUBE_LEVELS[g], CUBE_LEVELS[b]);
+
+    let average = (rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3;
+    let gray_step = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray = 232 + gray_step;
+    let gray_level = 8 + 10 * gray_step;
+
+    let target = oklab(rgb);
+    if distance(target, oklab((gray_level, gray_level, gray_level)))
+        < distance(target, oklab(cube_rgb))
+    {
+        gray
+    } else {
+        cube
+    }
+}
+
+/// RGB of an xterm-256 palette entry, assuming xterm's defaults for 0-15.
+pub fn ansi256_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI16[index as usize],
+        16..=231 => {
+            let cube = index - 16;
+            (
+                CUBE_LEVELS[(cube / 36) as usize],
+                CUBE_LEVELS[(cube / 6 % 6) as usize],
+                CUBE_LEVELS[(cube % 6) as usize],
+            )
+        }
+        _ => {
+            let level = 8 + 10 * (index - 232);
+            (level, level, level)
+        }
+    }
+}
+
+/// Parse `#rrggbb` (the `#` is optional).
+pub fn parse_hex_rgb(text: &str) -> Option<(u8, u8, u8)> {
+    let hex = text.trim();
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |at: usize| u8::from_str_radix(&hex[at..at + 2], 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Perceptually nearest of the 16 ANSI colors.
+pub fn nearest_ansi16(rgb: (u8, u8, u8)) -> u8 {
+    static PALETTE: OnceLock<[[f32; 3]; 16]> = OnceLock::new();
+    let palette = PALETTE.get_or_init(|| ANSI16.map(oklab));
+    let target = oklab(rgb);
+    palette
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| distance(target, **a).total_cmp(&distance(target, **b)))
+        .map(|(index, _)| index as u8)
+        .unwrap_or(7)
+}
+
+/// 4x4 Bayer threshold matrix for ordered dithering.
+const BAYER4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Nudge `rgb` by the Bayer threshold at column `x`, row `y`, so quantizing
+/// a smooth gradient alternates between neighbouring palette entries instead
+/// of banding.
+pub fn ordered_dither(rgb: (u8, u8, u8), x: usize, y: usize, step: f32) -> (u8, u8, u8) {
+    let threshold = (BAYER4[y % 4][x % 4] as f32 + 0.5) / 16.0 - 0.5;
+    let offset = threshold * step;
+    let channel = |value: u8| (value as f32 + offset).round().clamp(0.0, 255.0) as u8;
+    (channel(rgb.0), channel(rgb.1), channel(rgb.2))
+}
+
+fn oklab((r, g, b): (u8, u8, u8)) -> [f32; 3] {
+    let linear = |channel: u8| {
+        let c = channel as f32 / 255.0;
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    let (r, g, b) = (linear(r), linear(g), linear(b));
+    let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+    let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+    let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+    [
+        0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+        1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+        0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+    ]
+}
+
+fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(vars: &[(&str, &str)], colors: Option<i32>) -> ColorDepth {
+        let lookup = |name: &str| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        };
+        detect_from(&lookup, &|_| colors)
+    }
+
+    #[test]
+    fn detects_depth_from_environment_and_terminfo() {
+        let truecolor = [("TERM", "xterm-256color"), ("COLORTERM", "truecolor")];
+        assert_eq!(detect(&truecolor, Some(256)), ColorDepth::TrueColor);
+        let no_color = [("NO_COLOR", "1"), ("COLORTERM", "truecolor")];
+        assert_eq!(detect(&no_color, None), ColorDepth::None);
+        assert_eq!(
+            detect(&[("TERM", "xterm-256color")], Some(256)),
+            ColorDepth::Ansi256
+        );
+        assert_eq!(detect(&[("TERM", "linux")], Some(8)), ColorDepth::Ansi16);
+        assert_eq!(
+            detect(&[("TERM", "screen-256color")], None),
+            ColorDepth::Ansi256
+        );
+        assert_eq!(detect(&[("TERM", "dumb")], None), ColorDepth::None);
+        let unset = if cfg!(windows) {
+            ColorDepth::TrueColor
+        } else {
+            ColorDepth::Ansi16
+        };
+        assert_eq!(detect(&[], None), unset);
+        assert_eq!(detect(&[("TERM", "vt52")], Some(-1)), ColorDepth::None);
+        assert_eq!(
+            detect(&[("TERM", "xterm-kitty")], Some(256)),
+            ColorDepth::TrueColor
+        );
+    }
+
+    #[test]
+    fn reads_max_colors_from_compiled_terminfo() {
+        // Legacy header, 2 name bytes and 1 bool, so one pad byte before the
+        // 14 numbers.
+        let mut data = Vec::new();
+        for value in [0o432i16, 2, 1, 14, 0, 0] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(b"x\0\x01\0");
+        for index in 0..14i16 {
+            let value: i16 = if index == 13 { 256 } else { -1 };
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        assert_eq!(parse_terminfo_colors(&data), Some(256));
+
+        let mut extended = vec![0x1e, 0x02, 2, 0, 0, 0, 14, 0, 0, 0, 0, 0, b'x', 0];
+        for index in 0..14i32 {
+            let value: i32 = if index == 13 { 1 << 24 } else { -1 };
+            extended.extend_from_slice(&value.to_le_bytes());
+        }
+        assert_eq!(parse_terminfo_colors(&extended), Some(1 << 24));
+        assert_eq!(parse_terminfo_colors(b"garbage"), None);
+    }
+
+    #[test]
+    fn quantizes_to_nearest_palette_entries() {
+        assert_eq!(nearest_ansi256((255, 0, 0)), 196);
+        assert_eq!(nearest_ansi256((0, 0, 0)), 16);
+        assert_eq!(nearest_ansi256((128, 128, 128)), 244);
+        assert_eq!(nearest_ansi256((95, 135, 255)), 69);
+        assert_eq!(nearest_ansi16((250, 10, 10)), 9);
+        assert_eq!(nearest_ansi16((10, 10, 180)), 4);
+        assert_eq!(nearest_ansi16((200, 200, 200)), 7);
+        assert_eq!(ColorDepth::None.color((1, 2, 3)), None);
+        for index in [16, 69, 196, 244] {
+            assert_eq!(nearest_ansi256(ansi256_rgb(index)), index);
+        }
+        assert_eq!(nearest_ansi16(ansi256_rgb(12)), 12);
+        assert_eq!(parse_hex_rgb("#5277C3"), Some((0x52, 0x77, 0xc3)));
+        assert_eq!(parse_hex_rgb("ff8000"), Some((255, 128, 0)));
+        assert_eq!(parse_hex_rgb("#fff"), None);
+        assert_eq!(parse_hex_rgb("#12345g"), None);
+    }
+
+    #[test]
+    fn logo_colors_combine_base_and_animation() {
+        let (base, animated) = ((200, 40, 0), (0, 100, 255));
+        assert_eq!(LogoColors::Replace.apply(base, animated), animated);
+        assert_eq!(LogoColors::Keep.apply(base, animated), base);
+        assert_eq!(LogoColors::Blend.apply(base, animated), (100, 70, 128));
+        // Modulate keeps the hue: brighter animation, brighter base.
+        assert_eq!(LogoColors::Modulate.apply(base, (0, 0, 0)), (70, 14, 0));
+        assert_eq!(
+            LogoColors::Modulate.apply(base, (255, 255, 255)),
+            (250, 50, 0)
+        );
+        assert_eq!(LogoColors::parse(" Blend "), Some(LogoColors::Blend));
+        assert_eq!(LogoColors::parse("tint"), None);
+    }
+
+    #[test]
+    fn dithering_spreads_a_flat_color_over_neighbours() {
+        let rgb = (115, 115, 115);
+        let indices: std::collections::BTreeSet<u8> = (0..4)
+            .flat_map(|y| (0..4).map(move |x| (x, y)))
+            .map(|(x, y)| nearest_ansi256(ordered_dither(rgb, x, y, 40.0)))
+            .collect();
+        assert!(indices.len() > 1, "{indices:?}");
+        assert_eq!(ordered_dither(rgb, 0, 0, 0.0), rgb);
+    }
+}
@@ -0,0 +1,22 @@
+//! Scratch files for tests that read fixtures from disk.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A fresh path in the system temp directory, unique to this process and
+/// moment. Nothing is created there.
+pub fn temp_path(name: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before unix epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!("neonfetch-{name}-{}-{nanos}", std::process::id()))
+}
+
+/// Write `contents` to `root/rel`, creating its parent directories.
+pub fn write(root: &Path, rel: &str, contents: impl AsRef<[u8]>) {
+    let path = root.join(rel);
+    fs::create_dir_all(path.parent().expect("fixture path has a parent")).unwrap();
+    fs::write(path, contents).unwrap();
+}
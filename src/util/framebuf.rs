@@ -1,81 +1,660 @@
+use crate::util::ansi::{Attrs, SgrColor, SgrState};
+use crate::util::cell::Cell;
+use crate::util::color::{ColorDepth, LogoColors, TermColor, ansi256_rgb, ordered_dither};
+use crate::util::term::{SYNC_BEGIN, SYNC_END};
 use std::io::{self, Write};
+use std::mem;
 
-/// Reusable per-frame output buffer.
+/// Changed runs separated by at most this many unchanged cells are sent as
+/// one run: rewriting a few cells is cheaper than another cursor move.
+const MERGE_GAP: usize = 3;
+
+/// Double-buffered frame grid.
 ///
-/// Collects one frame of ANSI output, then writes it to stdout in a single
-/// syscall. Tracks the last emitted foreground color so runs of identically
-/// colored cells produce only one escape sequence, and applies the global
-/// `--mono` / `--no-color` transforms in one place.
+/// Renderers draw a frame cell by cell; `write_to` then diffs it against the
+/// previous frame and writes only the changed runs, with minimal cursor
+/// movement, to stdout in a single syscall. Foreground, background and
+/// attribute runs are deduplicated separately, and the global `--mono`
+/// transform and the terminal's color depth are applied in one place.
 pub struct FrameBuf {
+    next: Vec<Vec<GridCell>>,
+    prev: Vec<Vec<GridCell>>,
     buf: String,
-    cur: Option<(u8, u8, u8)>,
     mono: bool,
-    no_color: bool,
+    depth: ColorDepth,
+    dither: bool,
+    /// Grid position of the next cell.
+    row: usize,
+    col: usize,
+    /// Background and attributes set by the source text on the current row.
+    /// Its foreground is left to the animation.
+    pen: SgrState,
+    stats: Option<FrameStats>,
+    sync: bool,
+    /// Cursor position within the frame when drawing inline (rows relative
+    /// to wherever the frame starts); `None` addresses absolute screen rows.
+    inline: Option<(usize, usize)>,
+    logo_colors: LogoColors,
+    /// Colors of the logo palette slots, by `Cell::slot`.
+    logo_slots: Vec<(u8, u8, u8)>,
+}
+
+/// One screen column of a frame.
+#[derive(Clone, Debug, PartialEq)]
+struct GridCell {
+    /// Base character; `'\0'` for the right half of a wide glyph.
+    ch: char,
+    marks: String,
+    fg: Option<TermColor>,
+    bg: Option<TermColor>,
+    attrs: Attrs,
+    width: usize,
+}
+
+static BLANK: GridCell = GridCell {
+    ch: ' ',
+    marks: String::new(),
+    fg: None,
+    bg: None,
+    attrs: Attrs::NONE,
+    width: 1,
+};
+
+/// Attributes that draw on a space, so its foreground color matters.
+const INK_ON_BLANKS: Attrs = Attrs::UNDERLINE.union(Attrs::REVERSE).union(Attrs::STRIKE);
+
+impl GridCell {
+    fn is_continuation(&self) -> bool {
+        self.width == 0
+    }
+
+    /// Looks like an erased cell: a space with no background or attributes.
+    fn is_blank(&self) -> bool {
+        self.ch == ' ' && self.bg.is_none() && self.attrs.is_empty()
+    }
+}
+
+/// Bytes written per frame, for `--stats`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FrameStats {
+    pub frames: usize,
+    pub bytes: usize,
+    pub peak: usize,
+    /// What redrawing every frame in full would have written.
+    pub full_bytes: usize,
+}
+
+impl FrameStats {
+    pub fn summary(&self) -> String {
+        let per_frame = |bytes: usize| bytes / self.frames.max(1);
+        format!(
+            "frames: {}, bytes: {} ({} B/frame avg, {} B peak); full redraw: {} B/frame avg",
+            self.frames,
+            self.bytes,
+            per_frame(self.bytes),
+            self.peak,
+            per_frame(self.full_bytes)
+        )
+    }
 }
 
 impl FrameBuf {
-    pub fn new(mono: bool, no_color: bool) -> Self {
+    pub fn new(mono: bool, depth: ColorDepth) -> Self {
         FrameBuf {
+            next: Vec::new(),
+            prev: Vec::new(),
             buf: String::with_capacity(64 * 1024),
-            cur: None,
             mono,
-            no_color,
+            depth,
+            dither: false,
+            row: 0,
+            col: 0,
+            pen: SgrState::default(),
+            stats: None,
+            sync: false,
+            inline: None,
+            logo_colors: LogoColors::Replace,
+            logo_slots: Vec::new(),
         }
     }
 
-    /// Start a new frame, keeping the allocation from the previous one.
+    /// Ordered-dither colors before quantizing them to a 256- or 16-color
+    /// palette, trading banding in gradients for a fine pattern.
+    pub fn with_dither(mut self, dither: bool) -> Self {
+        self.dither = dither;
+        self
+    }
+
+    /// Count bytes per frame (and what a full redraw would cost) for
+    /// `--stats`.
+    pub fn with_stats(mut self, stats: bool) -> Self {
+        self.stats = stats.then(FrameStats::default);
+        self
+    }
+
+    /// Wrap each frame in synchronized-update markers so the terminal shows
+    /// it all at once. Only for terminals that reported DEC mode 2026.
+    pub fn with_sync(mut self, sync: bool) -> Self {
+        self.sync = sync;
+        self
+    }
+
+    /// Draw at the cursor's row instead of the top of the screen, moving
+    /// only relative to it. The cursor must start at column 0 of the first
+    /// row of a region tall enough for the frame.
+    pub fn with_inline(mut self, inline: bool) -> Self {
+        self.inline = inline.then_some((0, 0));
+        self
+    }
+
+    /// How cells that carry their own color (`Cell::base`) combine it with
+    /// the animation color.
+    pub fn with_logo_colors(mut self, logo_colors: LogoColors) -> Self {
+        self.logo_colors = logo_colors;
+        self
+    }
+
+    /// Colors for cells in a logo palette slot. In replace mode they keep
+    /// their slot's hue and the animation drives their brightness, so
+    /// two-tone art stays two-tone.
+    pub fn with_logo_slots(mut self, logo_slots: &[(u8, u8, u8)]) -> Self {
+        self.logo_slots = logo_slots.to_vec();
+        self
+    }
+
+    pub fn stats(&self) -> Option<FrameStats> {
+        self.stats
+    }
+
+    /// Start a new frame, keeping the allocations from the previous one.
     pub fn begin(&mut self) {
+        for row in &mut self.next {
+            row.clear();
+        }
+        self.row = 0;
+        self.col = 0;
+        self.pen = SgrState::default();
+    }
+
+    /// Wipe the terminal before this frame (e.g. after a resize). Nothing on
+    /// screen can be reused, so the next write starts from a blank grid.
+    /// Inline frames only erase from their first row down.
+    pub fn clear_screen(&mut self) {
         self.buf.clear();
-        self.cur = None;
+        match self.inline {
+            Some(cursor) => {
+                let mut emitter = Emitter::new(mem::take(&mut self.buf), Some(cursor), true);
+                emitter.move_to(0, 0);
+                emitter.buf.push_str("\x1b[0m\x1b[J");
+                self.buf = emitter.buf;
+                self.inline = Some((0, 0));
+            }
+            None => self.buf.push_str("\x1b[0m\x1b[2J"),
+        }
+        self.prev.clear();
+    }
+
+    /// Make room for `rows` inline rows from the frame's first row down,
+    /// scrolling the screen when they run past its bottom, e.g. after a
+    /// resize arranges a taller frame. No-op outside inline mode.
+    pub fn reserve_rows(&mut self, rows: usize) {
+        let Some(cursor) = self.inline else {
+            return;
+        };
+        let mut emitter = Emitter::new(mem::take(&mut self.buf), Some(cursor), true);
+        emitter.move_to(0, 0);
+        // Line feeds scroll; cursor-down would stop at the last row.
+        let below = rows.saturating_sub(1);
+        emitter.buf.push_str(&"\n".repeat(below));
+        if below > 0 {
+            emitter.buf.push_str(&format!("\x1b[{}A", below));
+        }
+        self.buf = emitter.buf;
+        self.inline = Some((0, 0));
     }
 
-    /// Move the cursor to the start of a 1-based terminal row.
+    /// Move to the start of a 1-based terminal row, which is redrawn from
+    /// scratch.
     pub fn goto_line(&mut self, row1: usize) {
-        self.buf.push_str("\x1b[");
-        push_usize(&mut self.buf, row1);
-        self.buf.push_str(";1H");
+        self.row = row1.saturating_sub(1);
+        self.col = 0;
+        self.pen = SgrState::default();
+        if let Some(row) = self.next.get_mut(self.row) {
+            row.clear();
+        }
     }
 
-    /// Emit a raw escape sequence taken from the source text. The sequence may
-    /// change color state, so color-run tracking is invalidated.
+    /// Apply escape sequences taken from the source text to the cells that
+    /// follow them on this row. Only SGR background and attributes are kept.
     pub fn push_ansi(&mut self, seq: &str) {
-        self.buf.push_str(seq);
-        self.cur = None;
+        self.pen.apply(seq);
     }
 
-    /// Emit one printable cell with the given foreground color.
+    /// Draw one printable cell with the given foreground color.
     pub fn put(&mut self, ch: char, rgb: (u8, u8, u8)) {
-        if ch == ' ' {
-            // Foreground color is invisible on blanks; skip the escape.
-            self.buf.push(' ');
+        self.put_styled(ch, rgb, None, Attrs::NONE);
+    }
+
+    /// Draw one printable cell with a foreground, an optional background and
+    /// attributes, on top of whatever the source text set on this row.
+    pub fn put_styled(
+        &mut self,
+        ch: char,
+        rgb: (u8, u8, u8),
+        bg: Option<(u8, u8, u8)>,
+        attrs: Attrs,
+    ) {
+        let fg = self.color(rgb);
+        self.put_colored(ch, fg, bg, attrs);
+    }
+
+    /// `put_styled` with the foreground already mapped to the color depth.
+    fn put_colored(
+        &mut self,
+        ch: char,
+        fg: Option<TermColor>,
+        bg: Option<(u8, u8, u8)>,
+        attrs: Attrs,
+    ) {
+        let attrs = attrs | self.pen.attrs;
+        // Foreground color is invisible on plain blanks; don't let it force
+        // a redraw.
+        let fg = if ch == ' ' && !attrs.intersects(INK_ON_BLANKS) {
+            None
+        } else {
+            fg
+        };
+        let bg = match bg {
+            Some(rgb) => self.color(rgb),
+            None => self.pen.bg.and_then(|color| self.sgr_color(color)),
+        };
+        let cell = GridCell {
+            ch,
+            marks: String::new(),
+            fg,
+            bg,
+            attrs,
+            width: 1,
+        };
+        self.place(cell);
+    }
+
+    /// Draw a laid-out cell: its glyph plus any attached zero-width marks.
+    /// Continuation cells are skipped; the wide glyph before them already
+    /// covers their column. A cell's own color is combined with `rgb` as
+    /// set by `with_logo_colors`; kept palette colors stay palette colors.
+    pub fn put_cell(&mut self, cell: &Cell, rgb: (u8, u8, u8)) {
+        self.put_cell_styled(cell, rgb, None, Attrs::NONE);
+    }
+
+    /// `put_cell` with a background and attributes, as for `put_styled`.
+    /// Without an explicit background, one from the source text is blended
+    /// or modulated like the foreground; other modes leave it as written.
+    pub fn put_cell_styled(
+        &mut self,
+        cell: &Cell,
+        rgb: (u8, u8, u8),
+        bg: Option<(u8, u8, u8)>,
+        attrs: Attrs,
+    ) {
+        if cell.is_continuation() {
             return;
         }
-        if !self.no_color {
-            let rgb = if self.mono { grayscale(rgb) } else { rgb };
-            if self.cur != Some(rgb) {
-                self.buf.push_str("\x1b[38;2;");
-                push_u8(&mut self.buf, rgb.0);
-                self.buf.push(';');
-                push_u8(&mut self.buf, rgb.1);
-                self.buf.push(';');
-                push_u8(&mut self.buf, rgb.2);
-                self.buf.push('m');
-                self.cur = Some(rgb);
+        let bg = bg.or_else(|| match (cell.base_bg, self.logo_colors) {
+            (Some(base), LogoColors::Blend | LogoColors::Modulate) => {
+                Some(self.logo_colors.apply(base.rgb(), rgb))
             }
+            _ => None,
+        });
+        let fg = match (cell.base, self.logo_colors) {
+            (Some(base), LogoColors::Keep) => self.sgr_color(base),
+            (Some(base), mode) => self.color(mode.apply(base.rgb(), rgb)),
+            (None, LogoColors::Replace) => match cell.slot.and_then(|n| self.logo_slots.get(n)) {
+                Some(&slot) => self.color(LogoColors::Modulate.apply(slot, rgb)),
+                None => self.color(rgb),
+            },
+            (None, _) => self.color(rgb),
+        };
+        self.put_colored(cell.ch, fg, bg, attrs);
+        let row = &mut self.next[self.row];
+        if let Some(last) = row.last_mut() {
+            last.marks.push_str(&cell.marks);
+            last.width = cell.width.max(1);
+        }
+        for _ in 1..cell.width {
+            let continuation = GridCell {
+                ch: '\0',
+                width: 0,
+                ..BLANK.clone()
+            };
+            self.place(continuation);
         }
-        self.buf.push(ch);
     }
 
-    /// Reset attributes and erase to end of line, clearing stale cells from
-    /// previous frames or from whatever was on screen before we started.
+    /// Draw `width` blank cells, e.g. to stand in for a hidden wide glyph.
+    pub fn put_blank(&mut self, width: usize) {
+        for _ in 0..width {
+            self.put(' ', (0, 0, 0));
+        }
+    }
+
+    /// Finish the current row; the rest of it is blank in this frame.
     pub fn end_line(&mut self) {
-        self.buf.push_str("\x1b[0m\x1b[K");
-        self.cur = None;
+        self.pen = SgrState::default();
+    }
+
+    /// Apply `--mono`, dithering and the color depth to an animation color.
+    fn color(&self, rgb: (u8, u8, u8)) -> Option<TermColor> {
+        let rgb = if self.mono { grayscale(rgb) } else { rgb };
+        let rgb = if self.dither {
+            ordered_dither(rgb, self.col, self.row + 1, self.depth.dither_step())
+        } else {
+            rgb
+        };
+        self.depth.color(rgb)
+    }
+
+    /// Palette colors from the source text stay palette colors when the
+    /// terminal has them, so they follow its theme.
+    fn sgr_color(&self, color: SgrColor) -> Option<TermColor> {
+        match (color, self.depth) {
+            (_, ColorDepth::None) => None,
+            (SgrColor::Indexed(index), _) if self.mono => {
+                self.depth.color(grayscale(ansi256_rgb(index)))
+            }
+            (SgrColor::Indexed(index), _) if index < 16 => Some(TermColor::Basic(index)),
+            (SgrColor::Indexed(index), ColorDepth::TrueColor | ColorDepth::Ansi256) => {
+                Some(TermColor::Indexed(index))
+            }
+            (SgrColor::Indexed(index), _) => self.depth.color(ansi256_rgb(index)),
+            (SgrColor::Rgb(r, g, b), _) => {
+                let rgb = if self.mono {
+                    grayscale((r, g, b))
+                } else {
+                    (r, g, b)
+                };
+                self.depth.color(rgb)
+            }
+        }
+    }
+
+    fn place(&mut self, cell: GridCell) {
+        if self.next.len() <= self.row {
+            self.next.resize_with(self.row + 1, Vec::new);
+        }
+        let row = &mut self.next[self.row];
+        if row.len() < self.col {
+            row.resize(self.col, BLANK.clone());
+        }
+        if self.col < row.len() {
+            row[self.col] = cell;
+        } else {
+            row.push(cell);
+        }
+        self.col += 1;
+    }
+
+    /// Write what changed since the previous frame, then keep this frame as
+    /// the base for the next diff.
+    pub fn write_to(&mut self, out: &mut impl Write) -> io::Result<()> {
+        let mut emitter =
+            Emitter::new(mem::take(&mut self.buf), self.inline, self.inline.is_some());
+        let rows = self.next.len().max(self.prev.len());
+        for row in 0..rows {
+            let next = self.next.get(row).map(Vec::as_slice).unwrap_or(&[]);
+            let prev = self.prev.get(row).map(Vec::as_slice).unwrap_or(&[]);
+            emitter.diff_row(row, next, prev);
+        }
+        emitter.finish();
+        if let (Some(cursor), Some(moved)) = (&mut self.inline, emitter.cursor) {
+            *cursor = moved;
+        }
+        if let Some(stats) = &mut self.stats {
+            stats.frames += 1;
+            stats.bytes += emitter.buf.len();
+            stats.peak = stats.peak.max(emitter.buf.len());
+            stats.full_bytes += full_redraw_len(&self.next);
+        }
+        self.buf = emitter.buf;
+        if self.sync && !self.buf.is_empty() {
+            self.buf.insert_str(0, SYNC_BEGIN);
+            self.buf.push_str(SYNC_END);
+        }
+        let written = out.write_all(self.buf.as_bytes()).and_then(|_| out.flush());
+        self.buf.clear();
+        mem::swap(&mut self.prev, &mut self.next);
+        written
+    }
+
+    /// Write `payload` (e.g. a Sixel or kitty image) with the cursor at
+    /// `(row, col)` of the frame, then put the cursor back where the frame
+    /// left it. The grid doesn't know about it; blank cells that stay blank
+    /// aren't redrawn, so it survives until the screen is cleared.
+    pub fn write_overlay(
+        &mut self,
+        out: &mut impl Write,
+        payload: &str,
+        (row, col): (usize, usize),
+    ) -> io::Result<()> {
+        let mut emitter = Emitter::new(String::from("\x1b7"), self.inline, self.inline.is_some());
+        emitter.move_to(row, col);
+        emitter.buf.push_str(payload);
+        emitter.buf.push_str("\x1b8");
+        out.write_all(emitter.buf.as_bytes())
+            .and_then(|_| out.flush())
+    }
+}
+
+/// Serializes grid cells, tracking the cursor and the SGR state the
+/// terminal is in so only differences are written.
+struct Emitter {
+    buf: String,
+    cursor: Option<(usize, usize)>,
+    /// Move with CUU/CUD from the known cursor position instead of CUP.
+    relative: bool,
+    fg: Option<TermColor>,
+    bg: Option<TermColor>,
+    attrs: Attrs,
+}
+
+impl Emitter {
+    fn new(buf: String, cursor: Option<(usize, usize)>, relative: bool) -> Self {
+        Emitter {
+            buf,
+            cursor,
+            relative,
+            fg: None,
+            bg: None,
+            attrs: Attrs::NONE,
+        }
+    }
+
+    fn diff_row(&mut self, row: usize, next: &[GridCell], prev: &[GridCell]) {
+        let changed =
+            |col: usize| next.get(col).unwrap_or(&BLANK) != prev.get(col).unwrap_or(&BLANK);
+        let mut col = 0;
+        while col < next.len() {
+            if !changed(col) {
+                col += 1;
+                continue;
+            }
+            let mut start = col;
+            while start > 0 && next[start].is_continuation() {
+                start -= 1;
+            }
+            let mut end = col + 1;
+            let mut probe = end;
+            while probe < next.len() && probe - end <= MERGE_GAP {
+                if changed(probe) {
+                    end = probe + 1;
+                }
+                probe += 1;
+            }
+            while end < next.len() && next[end].is_continuation() {
+                end += 1;
+            }
+            self.move_to(row, start);
+            for cell in &next[start..end] {
+                self.cell(cell);
+            }
+            col = end;
+        }
+        if prev.iter().skip(next.len()).any(|cell| !cell.is_blank()) {
+            self.move_to(row, next.len());
+            self.erase_line();
+        }
+    }
+
+    fn move_to(&mut self, row: usize, col: usize) {
+        match self.cursor {
+            Some(cursor) if cursor == (row, col) => {}
+            Some((cursor_row, cursor_col)) if cursor_row == row && cursor_col < col => {
+                self.buf.push_str("\x1b[");
+                push_usize(&mut self.buf, col - cursor_col);
+                self.buf.push('C');
+            }
+            Some((cursor_row, _)) if self.relative => {
+                if row != cursor_row {
+                    self.buf.push_str("\x1b[");
+                    push_usize(&mut self.buf, row.abs_diff(cursor_row));
+                    self.buf.push(if row < cursor_row { 'A' } else { 'B' });
+                }
+                if col == 0 {
+                    self.buf.push('\r');
+                } else {
+                    self.buf.push_str("\x1b[");
+                    push_usize(&mut self.buf, col + 1);
+                    self.buf.push('G');
+                }
+            }
+            _ => {
+                self.buf.push_str("\x1b[");
+                push_usize(&mut self.buf, row + 1);
+                self.buf.push(';');
+                push_usize(&mut self.buf, col + 1);
+                self.buf.push('H');
+            }
+        }
+        self.cursor = Some((row, col));
+    }
+
+    fn cell(&mut self, cell: &GridCell) {
+        if cell.is_continuation() {
+            return;
+        }
+        let mut params = String::new();
+        if cell.attrs != self.attrs {
+            let mut removed = self.attrs;
+            removed.remove(cell.attrs);
+            let mut added = cell.attrs;
+            added.remove(self.attrs);
+            // SGR 22 clears bold and dim together; restore whichever stays.
+            if removed.intersects(Attrs::BOLD | Attrs::DIM) {
+                added.insert(cell.attrs.intersection(Attrs::BOLD | Attrs::DIM));
+            }
+            let mut off_sent = 0u8;
+            for (attr, _, off) in Attrs::CODES {
+                if removed.intersects(attr) && off != off_sent {
+                    push_param(&mut params, off);
+                    off_sent = off;
+                }
+            }
+            for (attr, on, _) in Attrs::CODES {
+                if added.intersects(attr) {
+                    push_param(&mut params, on);
+                }
+            }
+            self.attrs = cell.attrs;
+        }
+        if cell.bg != self.bg {
+            match cell.bg {
+                Some(bg) => push_color(&mut params, bg, true),
+                None => push_param(&mut params, 49),
+            }
+            self.bg = cell.bg;
+        }
+        if let Some(fg) = cell.fg
+            && self.fg != Some(fg)
+        {
+            push_color(&mut params, fg, false);
+            self.fg = Some(fg);
+        }
+        if !params.is_empty() {
+            self.buf.push_str("\x1b[");
+            self.buf.push_str(&params);
+            self.buf.push('m');
+        }
+        self.buf.push(cell.ch);
+        self.buf.push_str(&cell.marks);
+        if let Some((_, col)) = &mut self.cursor {
+            *col += cell.width;
+        }
+    }
+
+    /// Erase from the cursor to the end of the line with the default
+    /// background.
+    fn erase_line(&mut self) {
+        self.reset();
+        self.buf.push_str("\x1b[K");
+    }
+
+    fn reset(&mut self) {
+        if self.fg.is_some() || self.bg.is_some() || !self.attrs.is_empty() {
+            self.buf.push_str("\x1b[0m");
+            self.fg = None;
+            self.bg = None;
+            self.attrs = Attrs::NONE;
+        }
     }
 
-    pub fn write_to(&self, out: &mut impl Write) -> io::Result<()> {
-        out.write_all(self.buf.as_bytes())?;
-        out.flush()
+    fn finish(&mut self) {
+        self.reset();
+    }
+}
+
+/// Bytes a frame would take redrawn in full: every row positioned, drawn
+/// and erased to the end of the line.
+fn full_redraw_len(grid: &[Vec<GridCell>]) -> usize {
+    let mut emitter = Emitter::new(String::new(), None, false);
+    for (row, cells) in grid.iter().enumerate() {
+        emitter.cursor = None;
+        emitter.move_to(row, 0);
+        for cell in cells {
+            emitter.cell(cell);
+        }
+        emitter.erase_line();
+    }
+    emitter.buf.len()
+}
+
+/// Append one SGR parameter to a `;`-separated list.
+fn push_param(params: &mut String, code: u8) {
+    if !params.is_empty() {
+        params.push(';');
+    }
+    push_u8(params, code);
+}
+
+fn push_color(params: &mut String, color: TermColor, background: bool) {
+    let (extended, basic, bright) = if background {
+        (48, 40, 100)
+    } else {
+        (38, 30, 90)
+    };
+    match color {
+        TermColor::Rgb(r, g, b) => {
+            push_param(params, extended);
+            for code in [2, r, g, b] {
+                push_param(params, code);
+            }
+        }
+        TermColor::Indexed(index) => {
+            push_param(params, extended);
+            push_param(params, 5);
+            push_param(params, index);
+        }
+        TermColor::Basic(index) if index < 8 => push_param(params, basic + index),
+        TermColor::Basic(index) => push_param(params, bright + index - 8),
     }
 }
 
@@ -117,43 +696,306 @@ fn push_usize(buf: &mut String, mut v: usize) {
 mod tests {
     use super::*;
 
-    fn contents(fb: &FrameBuf) -> &str {
-        &fb.buf
+    /// Write the frame drawn so far and return the bytes sent.
+    fn flush(fb: &mut FrameBuf) -> String {
+        let mut out = Vec::new();
+        fb.write_to(&mut out).expect("write to memory");
+        String::from_utf8(out).expect("utf-8 frame")
+    }
+
+    fn draw(fb: &mut FrameBuf, rows: &[&str]) -> String {
+        fb.begin();
+        for (index, text) in rows.iter().enumerate() {
+            fb.goto_line(index + 1);
+            for ch in text.chars() {
+                fb.put(ch, (200, 100, 50));
+            }
+            fb.end_line();
+        }
+        flush(fb)
     }
 
     #[test]
     fn color_runs_deduplicated() {
-        let mut fb = FrameBuf::new(false, false);
+        let mut fb = FrameBuf::new(false, ColorDepth::TrueColor);
         fb.begin();
         fb.put('a', (10, 20, 30));
         fb.put('b', (10, 20, 30));
         fb.put('c', (11, 20, 30));
-        let s = contents(&fb);
+        let s = flush(&mut fb);
         assert_eq!(s.matches("\x1b[38;2;").count(), 2);
         assert!(s.contains("\x1b[38;2;10;20;30mab"));
     }
 
     #[test]
     fn no_color_emits_plain_text() {
-        let mut fb = FrameBuf::new(false, true);
+        let mut fb = FrameBuf::new(false, ColorDepth::None);
         fb.begin();
         fb.put('x', (1, 2, 3));
-        assert_eq!(contents(&fb), "x");
+        assert_eq!(flush(&mut fb), "\x1b[1;1Hx");
     }
 
     #[test]
     fn mono_collapses_to_gray() {
-        let mut fb = FrameBuf::new(true, false);
+        let mut fb = FrameBuf::new(true, ColorDepth::TrueColor);
         fb.begin();
         fb.put('x', (255, 0, 0));
-        assert!(contents(&fb).starts_with("\x1b[38;2;76;76;76m"));
+        assert!(flush(&mut fb).contains("\x1b[38;2;76;76;76mx"));
+    }
+
+    #[test]
+    fn reduced_depths_emit_palette_escapes() {
+        let mut fb = FrameBuf::new(false, ColorDepth::Ansi256);
+        fb.begin();
+        fb.put('a', (255, 0, 0));
+        fb.put('b', (250, 5, 5));
+        assert!(flush(&mut fb).contains("\x1b[38;5;196mab"));
+
+        let mut fb = FrameBuf::new(false, ColorDepth::Ansi16);
+        fb.begin();
+        fb.put('a', (0, 205, 0));
+        fb.put('b', (255, 255, 255));
+        assert!(flush(&mut fb).contains("\x1b[32ma\x1b[97mb"));
+    }
+
+    #[test]
+    fn dither_varies_by_screen_position() {
+        let mut fb = FrameBuf::new(false, ColorDepth::Ansi256).with_dither(true);
+        fb.begin();
+        fb.goto_line(1);
+        for _ in 0..4 {
+            fb.put('x', (115, 115, 115));
+        }
+        assert!(flush(&mut fb).matches("\x1b[38;5;").count() > 1);
+    }
+
+    #[test]
+    fn cells_carry_marks_and_skip_continuations() {
+        let mut fb = FrameBuf::new(false, ColorDepth::None);
+        fb.begin();
+        for cell in crate::util::cell::layout_line("内e\u{301}") {
+            fb.put_cell(&cell, (1, 2, 3));
+        }
+        assert_eq!(flush(&mut fb), "\x1b[1;1H内e\u{301}");
     }
 
     #[test]
     fn spaces_skip_color_escapes() {
-        let mut fb = FrameBuf::new(false, false);
+        let mut fb = FrameBuf::new(false, ColorDepth::TrueColor);
         fb.begin();
+        fb.put('a', (1, 2, 3));
         fb.put(' ', (1, 2, 3));
-        assert_eq!(contents(&fb), " ");
+        fb.put(' ', (4, 5, 6));
+        fb.put('b', (1, 2, 3));
+        assert_eq!(flush(&mut fb), "\x1b[1;1H\x1b[38;2;1;2;3ma  b\x1b[0m");
+    }
+
+    #[test]
+    fn unchanged_frames_write_nothing_and_changes_write_runs() {
+        let mut fb = FrameBuf::new(false, ColorDepth::None);
+        // Leading blanks match the cleared screen and are skipped.
+        assert_eq!(
+            draw(&mut fb, &["  hello there", "world"]),
+            "\x1b[1;3Hhello there\x1b[2;1Hworld"
+        );
+        assert_eq!(draw(&mut fb, &["  hello there", "world"]), "");
+        // Nearby changes merge into one run; distant ones hop with CUF.
+        assert_eq!(draw(&mut fb, &["  jelly there", "world"]), "\x1b[1;3Hjelly");
+        assert_eq!(
+            draw(&mut fb, &["  Jelly therE", "world"]),
+            "\x1b[1;3HJ\x1b[9CE"
+        );
+        // A shorter row erases what it no longer covers; a dropped row is
+        // erased too.
+        assert_eq!(draw(&mut fb, &["  Je"]), "\x1b[1;5H\x1b[K\x1b[2;1H\x1b[K");
+        assert_eq!(draw(&mut fb, &["  Je"]), "");
+    }
+
+    #[test]
+    fn wide_glyph_changes_redraw_from_the_glyph_start() {
+        let mut fb = FrameBuf::new(false, ColorDepth::None);
+        let mut frame = |text: &str| {
+            fb.begin();
+            fb.goto_line(1);
+            for cell in crate::util::cell::layout_line(text) {
+                fb.put_cell(&cell, (1, 2, 3));
+            }
+            flush(&mut fb)
+        };
+        assert_eq!(frame("a内b"), "\x1b[1;1Ha内b");
+        assert_eq!(frame("a内c"), "\x1b[1;4Hc");
+        assert_eq!(frame("a字c"), "\x1b[1;2H字");
+    }
+
+    #[test]
+    fn source_escapes_apply_to_following_cells() {
+        let mut fb = FrameBuf::new(false, ColorDepth::None);
+        fb.begin();
+        fb.goto_line(1);
+        fb.push_ansi("\x1b[1m");
+        fb.put('A', (0, 0, 0));
+        fb.push_ansi("\x1b[0m");
+        fb.put('b', (0, 0, 0));
+        fb.end_line();
+        assert_eq!(flush(&mut fb), "\x1b[1;1H\x1b[1mA\x1b[22mb");
+    }
+
+    #[test]
+    fn cell_colors_blend_with_the_animation() {
+        let mut fb =
+            FrameBuf::new(false, ColorDepth::TrueColor).with_logo_colors(LogoColors::Blend);
+        fb.begin();
+        for cell in crate::util::cell::layout_line("\x1b[38;2;200;0;0mA\x1b[0mB") {
+            fb.push_ansi(&cell.ansi);
+            fb.put_cell(&cell, (0, 0, 100));
+        }
+        assert_eq!(
+            flush(&mut fb),
+            "\x1b[1;1H\x1b[38;2;100;0;50mA\x1b[38;2;0;0;100mB\x1b[0m"
+        );
+
+        // Half-block image cells tint both halves.
+        fb.begin();
+        for cell in crate::util::cell::layout_line("\x1b[38;2;200;0;0;48;2;0;200;0m▀") {
+            fb.push_ansi(&cell.ansi);
+            fb.put_cell(&cell, (0, 0, 100));
+        }
+        assert_eq!(
+            flush(&mut fb),
+            "\x1b[1;1H\x1b[48;2;0;100;50;38;2;100;0;50m▀\x1b[0m\x1b[K"
+        );
+    }
+
+    #[test]
+    fn kept_palette_colors_stay_indexed() {
+        let mut fb = FrameBuf::new(false, ColorDepth::TrueColor).with_logo_colors(LogoColors::Keep);
+        fb.begin();
+        for cell in crate::util::cell::layout_line("\x1b[31mA\x1b[38;5;208mB\x1b[0mC") {
+            fb.push_ansi(&cell.ansi);
+            fb.put_cell(&cell, (0, 0, 100));
+        }
+        assert_eq!(
+            flush(&mut fb),
+            "\x1b[1;1H\x1b[31mA\x1b[38;5;208mB\x1b[38;2;0;0;100mC\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn replaced_slot_cells_keep_their_slot_hue() {
+        let mut fb = FrameBuf::new(false, ColorDepth::TrueColor).with_logo_slots(&[(200, 0, 0)]);
+        fb.begin();
+        fb.goto_line(1);
+        let mut cells = crate::util::cell::layout_line("ab");
+        cells[0].slot = Some(0);
+        fb.put_cell(&cells[0], (255, 255, 255));
+        fb.put_cell(&cells[1], (255, 255, 255));
+        assert_eq!(
+            flush(&mut fb),
+            "\x1b[1;1H\x1b[38;2;250;0;0ma\x1b[38;2;255;255;255mb\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn backgrounds_and_attributes_dedupe_separately() {
+        let mut fb = FrameBuf::new(false, ColorDepth::TrueColor);
+        fb.begin();
+        fb.goto_line(1);
+        fb.push_ansi("\x1b[1;44m");
+        fb.put('a', (1, 2, 3));
+        fb.put('b', (4, 5, 6));
+        fb.push_ansi("\x1b[2;49m");
+        fb.put_styled(' ', (4, 5, 6), Some((7, 8, 9)), Attrs::UNDERLINE);
+        fb.end_line();
+        fb.put_styled('c', (4, 5, 6), None, Attrs::ITALIC);
+        assert_eq!(
+            flush(&mut fb),
+            "\x1b[1;1H\x1b[1;44;38;2;1;2;3ma\x1b[38;2;4;5;6mb\x1b[2;4;48;2;7;8;9m \
+             \x1b[22;24;3;49mc\x1b[0m"
+        );
+
+        // Backgrounds are cells too: repainting one redraws just that cell.
+        let mut fb = FrameBuf::new(false, ColorDepth::Ansi16);
+        let mut frame = |bg| {
+            fb.begin();
+            fb.goto_line(1);
+            fb.put('x', (255, 255, 255));
+            fb.put_styled(' ', (0, 0, 0), bg, Attrs::NONE);
+            flush(&mut fb)
+        };
+        assert_eq!(frame(None), "\x1b[1;1H\x1b[97mx\x1b[0m");
+        assert_eq!(frame(Some((205, 0, 0))), "\x1b[1;2H\x1b[41m \x1b[0m");
+        assert_eq!(frame(None), "\x1b[1;2H ");
+    }
+
+    #[test]
+    fn inline_frames_move_relative_to_the_cursor() {
+        let mut fb = FrameBuf::new(false, ColorDepth::None).with_inline(true);
+        assert_eq!(draw(&mut fb, &["ab", "cd"]), "ab\x1b[1B\rcd");
+        assert_eq!(draw(&mut fb, &["xb", "cd"]), "\x1b[1A\rx");
+        assert_eq!(draw(&mut fb, &["xb", "ce"]), "\x1b[1B\x1b[2Ge");
+        fb.begin();
+        fb.clear_screen();
+        assert_eq!(flush(&mut fb), "\x1b[1A\r\x1b[0m\x1b[J");
+    }
+
+    #[test]
+    fn reserved_inline_rows_return_to_the_first_row() {
+        let mut fb = FrameBuf::new(false, ColorDepth::None).with_inline(true);
+        draw(&mut fb, &["ab", "cd"]);
+        fb.reserve_rows(3);
+        assert_eq!(
+            draw(&mut fb, &["ab", "cd", "ef"]),
+            "\x1b[1A\r\n\n\x1b[2A\x1b[2B\ref"
+        );
+    }
+
+    #[test]
+    fn overlays_start_at_their_frame_cell() {
+        let mut fb = FrameBuf::new(false, ColorDepth::TrueColor);
+        let mut out = Vec::new();
+        fb.write_overlay(&mut out, "IMG", (0, 0)).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1b7\x1b[1;1HIMG\x1b8");
+        let mut out = Vec::new();
+        fb.write_overlay(&mut out, "IMG", (2, 5)).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1b7\x1b[3;6HIMG\x1b8");
+
+        let mut fb = FrameBuf::new(false, ColorDepth::TrueColor).with_inline(true);
+        draw(&mut fb, &["ab", "cd"]);
+        let mut out = Vec::new();
+        fb.write_overlay(&mut out, "IMG", (0, 0)).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1b7\x1b[1A\rIMG\x1b8");
+        let mut out = Vec::new();
+        fb.write_overlay(&mut out, "IMG", (0, 3)).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1b7\x1b[1A\x1b[4GIMG\x1b8"
+        );
+    }
+
+    #[test]
+    fn sync_wraps_non_empty_frames() {
+        let mut fb = FrameBuf::new(false, ColorDepth::None).with_sync(true);
+        assert_eq!(draw(&mut fb, &["ab"]), "\x1b[?2026h\x1b[1;1Hab\x1b[?2026l");
+        assert_eq!(draw(&mut fb, &["ab"]), "");
+    }
+
+    #[test]
+    fn clear_screen_forgets_the_previous_frame() {
+        let mut fb = FrameBuf::new(false, ColorDepth::None).with_stats(true);
+        draw(&mut fb, &["abc"]);
+        fb.begin();
+        fb.clear_screen();
+        fb.goto_line(1);
+        for ch in "abc".chars() {
+            fb.put(ch, (0, 0, 0));
+        }
+        assert_eq!(flush(&mut fb), "\x1b[0m\x1b[2J\x1b[1;1Habc");
+        let stats = fb.stats().expect("stats enabled");
+        assert_eq!(stats.frames, 2);
+        assert_eq!(
+            stats.bytes,
+            2 * "\x1b[1;1Habc".len() + "\x1b[0m\x1b[2J".len()
+        );
+        assert_eq!(stats.full_bytes, 2 * "\x1b[1;1Habc\x1b[K".len());
     }
 }
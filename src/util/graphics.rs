@@ -0,0 +1,355 @@
+//! Image logos for the terminal: truecolor half-block text that the
+//! animation can tint like any other logo, or a static picture through the
+//! Sixel or kitty graphics protocols.
+
+use crate::util::color::{ansi256_rgb, nearest_ansi256};
+use crate::util::png::Image;
+use std::fmt::Write;
+
+/// `--image-protocol` / `image_protocol`: how `--logo-image` is drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ImageProtocol {
+    /// Kitty or Sixel when the terminal answers for them, else half-blocks.
+    #[default]
+    Auto,
+    HalfBlocks,
+    Sixel,
+    Kitty,
+}
+
+impl ImageProtocol {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "auto" => Some(Self::Auto),
+            "halfblocks" | "half-blocks" | "blocks" => Some(Self::HalfBlocks),
+            "sixel" => Some(Self::Sixel),
+            "kitty" => Some(Self::Kitty),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::HalfBlocks => "halfblocks",
+            Self::Sixel => "sixel",
+            Self::Kitty => "kitty",
+        }
+    }
+}
+
+/// Alpha at or above this counts as an opaque half-block pixel.
+const OPAQUE: u8 = 128;
+/// Largest base64 payload per kitty escape, as the protocol asks.
+const KITTY_CHUNK: usize = 4096;
+
+/// Pixel size of an image scaled to fill `max_cols` x `max_rows` cells of
+/// half-blocks, where a cell shows two square pixels stacked.
+pub fn half_block_size(
+    width: usize,
+    height: usize,
+    max_cols: usize,
+    max_rows: usize,
+) -> (usize, usize) {
+    fit(width, height, max_cols, max_rows * 2)
+}
+
+/// Scale `width` x `height` up or down to fit `max_width` x `max_height`,
+/// keeping the aspect ratio.
+pub fn fit(width: usize, height: usize, max_width: usize, max_height: usize) -> (usize, usize) {
+    let scale = (max_width as f32 / width as f32).min(max_height as f32 / height as f32);
+    (
+        ((width as f32 * scale).round() as usize).clamp(1, max_width.max(1)),
+        ((height as f32 * scale).round() as usize).clamp(1, max_height.max(1)),
+    )
+}
+
+/// Resample to `width` x `height` by averaging the source pixels each target
+/// pixel covers, weighting colors by alpha so transparent edges don't bleed.
+pub fn resize(image: &Image, width: usize, height: usize) -> Image {
+    let span = |i: usize, to: usize, from: usize| {
+        let start = i * from / to;
+        let end = ((i + 1) * from).div_ceil(to).clamp(start + 1, from);
+        start..end
+    };
+    let mut pixels = Vec::with_capacity(width * height);
+    for y in 0..height {
+        let rows = span(y, height, image.height);
+        for x in 0..width {
+            let cols = span(x, width, image.width);
+            let mut sum = [0u32; 4];
+            let mut count = 0u32;
+            for sy in rows.clone() {
+                for sx in cols.clone() {
+                    let [r, g, b, a] = image.pixel(sx, sy);
+                    let a32 = a as u32;
+                    sum[0] += r as u32 * a32;
+                    sum[1] += g as u32 * a32;
+                    sum[2] += b as u32 * a32;
+                    sum[3] += a32;
+                    count += 1;
+                }
+            }
+            let channel = |total: u32| (total + sum[3] / 2).checked_div(sum[3]).unwrap_or(0) as u8;
+            pixels.push([
+                channel(sum[0]),
+                channel(sum[1]),
+                channel(sum[2]),
+                ((sum[3] + count / 2) / count) as u8,
+            ]);
+        }
+    }
+    Image {
+        width,
+        height,
+        pixels,
+    }
+}
+
+/// One text line per two pixel rows. With `colors`, each cell is `▀` with
+/// the upper pixel as foreground and the lower as background (or `▄` when
+/// only the lower one is opaque); without, the blocks alone trace the shape.
+pub fn half_blocks(image: &Image, colors: bool) -> Vec<String> {
+    let opaque = |x: usize, y: usize| {
+        (y < image.height)
+            .then(|| image.pixel(x, y))
+            .filter(|pixel| pixel[3] >= OPAQUE)
+            .map(|[r, g, b, _]| (r, g, b))
+    };
+    let mut lines = Vec::with_capacity(image.height.div_ceil(2));
+    for y in (0..image.height).step_by(2) {
+        let mut line = String::new();
+        let mut pen = (None, None);
+        for x in 0..image.width {
+            let (ch, fg, bg) = match (opaque(x, y), opaque(x, y + 1)) {
+                (None, None) => (' ', None, None),
+                (Some(top), None) => ('▀', Some(top), None),
+                (None, Some(bottom)) => ('▄', Some(bottom), None),
+                (Some(top), Some(bottom)) if colors => ('▀', Some(top), Some(bottom)),
+                (Some(_), Some(_)) => ('█', None, None),
+            };
+            if colors && ch != ' ' && (fg, bg) != pen {
+                if pen.1.is_some() && bg.is_none() {
+                    line.push_str("\x1b[49m");
+                }
+                if let Some((r, g, b)) = fg.filter(|_| fg != pen.0) {
+                    let _ = write!(line, "\x1b[38;2;{r};{g};{b}m");
+                }
+                if let Some((r, g, b)) = bg.filter(|_| bg != pen.1) {
+                    let _ = write!(line, "\x1b[48;2;{r};{g};{b}m");
+                }
+                pen = (fg, bg);
+            } else if colors && ch == ' ' && pen.1.is_some() {
+                line.push_str("\x1b[49m");
+                pen.1 = None;
+            }
+            line.push(ch);
+        }
+        if pen != (None, None) {
+            line.push_str("\x1b[0m");
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Encode as a Sixel image with xterm-256 color registers. Pixels that are
+/// not opaque are left unpainted, so the terminal background shows through.
+pub fn sixel(image: &Image) -> String {
+    let index = |x: usize, y: usize| {
+        let [r, g, b, a] = image.pixel(x, y);
+        (a >= OPAQUE).then(|| nearest_ansi256((r, g, b)))
+    };
+    let mut used = [false; 256];
+    for y in 0..image.height {
+        for x in 0..image.width {
+            if let Some(color) = index(x, y) {
+                used[color as usize] = true;
+            }
+        }
+    }
+
+    let mut out = format!("\x1bP0;1;0q\"1;1;{};{}", image.width, image.height);
+    for (color, _) in used.iter().enumerate().filter(|(_, used)| **used) {
+        let (r, g, b) = ansi256_rgb(color as u8);
+        let percent = |v: u8| (v as u32 * 100 + 127) / 255;
+        let _ = write!(
+            out,
+            "#{color};2;{};{};{}",
+            percent(r),
+            percent(g),
+            percent(b)
+        );
+    }
+    for band in (0..image.height).step_by(6) {
+        if band > 0 {
+            out.push('-');
+        }
+        let mut colors: Vec<u8> = Vec::new();
+        for y in band..(band + 6).min(image.height) {
+            for x in 0..image.width {
+                if let Some(color) = index(x, y)
+                    && !colors.contains(&color)
+                {
+                    colors.push(color);
+                }
+            }
+        }
+        for (n, &color) in colors.iter().enumerate() {
+            if n > 0 {
+                out.push('$');
+            }
+            let _ = write!(out, "#{color}");
+            let sixels = (0..image.width).map(|x| {
+                let bits = (0..6)
+                    .filter(|dy| band + dy < image.height && index(x, band + dy) == Some(color))
+                    .fold(0u8, |bits, dy| bits | 1 << dy);
+                (63 + bits) as char
+            });
+            push_runs(&mut out, sixels);
+        }
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+/// Sixel run-length encoding: `!<count><char>` once a run is worth it.
+fn push_runs(out: &mut String, sixels: impl Iterator<Item = char>) {
+    let mut run: Option<(char, usize)> = None;
+    let flush = |out: &mut String, (ch, count): (char, usize)| {
+        if count > 3 {
+            let _ = write!(out, "!{count}{ch}");
+        } else {
+            out.extend(std::iter::repeat_n(ch, count));
+        }
+    };
+    for ch in sixels {
+        run = match run {
+            Some((last, count)) if last == ch => Some((last, count + 1)),
+            Some(done) => {
+                flush(out, done);
+                Some((ch, 1))
+            }
+            None => Some((ch, 1)),
+        };
+    }
+    if let Some(done) = run {
+        flush(out, done);
+    }
+}
+
+/// Send a PNG file as-is through the kitty graphics protocol, scaled into
+/// `cols` x `rows` cells. The cursor stays put and the terminal is asked
+/// not to reply, since nothing reads stdin for it.
+pub fn kitty(png: &[u8], cols: usize, rows: usize) -> String {
+    let encoded = base64(png);
+    let chunks: Vec<&[u8]> = encoded.as_bytes().chunks(KITTY_CHUNK).collect();
+    let mut out = String::with_capacity(encoded.len() + chunks.len() * 16 + 32);
+    for (n, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(n + 1 < chunks.len());
+        if n == 0 {
+            let _ = write!(out, "\x1b_Ga=T,f=100,c={cols},r={rows},C=1,q=2,m={more};");
+        } else {
+            let _ = write!(out, "\x1b_Gm={more};");
+        }
+        // Base64 output is ASCII, so every chunk is valid UTF-8.
+        out.push_str(std::str::from_utf8(chunk).unwrap_or_default());
+        out.push_str("\x1b\\");
+    }
+    out
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for group in data.chunks(3) {
+        let bytes = [
+            group[0],
+            *group.get(1).unwrap_or(&0),
+            *group.get(2).unwrap_or(&0),
+        ];
+        let bits = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= group.len() {
+                out.push(ALPHABET[((bits >> (18 - 6 * i)) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+    const CLEAR: [u8; 4] = [0, 0, 0, 0];
+
+    fn image(width: usize, pixels: &[[u8; 4]]) -> Image {
+        Image {
+            width,
+            height: pixels.len() / width,
+            pixels: pixels.to_vec(),
+        }
+    }
+
+    #[test]
+    fn boxes_keep_the_aspect_ratio() {
+        assert_eq!(half_block_size(100, 100, 32, 16), (32, 32));
+        assert_eq!(half_block_size(200, 100, 32, 16), (32, 16));
+        assert_eq!(half_block_size(10, 100, 32, 16), (3, 32));
+        assert_eq!(half_block_size(5, 5, 32, 16), (32, 32));
+        assert_eq!(fit(300, 100, 320, 320), (320, 107));
+    }
+
+    #[test]
+    fn resizing_averages_covered_pixels_by_alpha() {
+        let source = image(2, &[RED, BLUE, CLEAR, RED]);
+        let small = resize(&source, 1, 1);
+        assert_eq!(small.pixels, [[170, 0, 85, 191]]);
+        let large = resize(&source, 4, 2);
+        assert_eq!(large.pixel(1, 0), RED);
+        assert_eq!(large.pixel(2, 1), RED);
+        assert_eq!(large.pixel(0, 1), CLEAR);
+    }
+
+    #[test]
+    fn half_blocks_pair_pixel_rows() {
+        let source = image(3, &[RED, RED, CLEAR, BLUE, CLEAR, CLEAR, CLEAR, BLUE, RED]);
+        assert_eq!(
+            half_blocks(&source, true),
+            [
+                "\x1b[38;2;255;0;0m\x1b[48;2;0;0;255m▀\x1b[49m▀ \x1b[0m",
+                " \x1b[38;2;0;0;255m▀\x1b[38;2;255;0;0m▀\x1b[0m",
+            ]
+        );
+        assert_eq!(half_blocks(&source, false), ["█▀ ", " ▀▀"]);
+    }
+
+    #[test]
+    fn sixel_bands_run_length_encode() {
+        let mut pixels = vec![RED; 8 * 7];
+        pixels[8 * 6 + 7] = BLUE;
+        let out = sixel(&image(8, &pixels));
+        assert_eq!(
+            out,
+            "\x1bP0;1;0q\"1;1;8;7#21;2;0;0;100#196;2;100;0;0#196!8~-#196!7@?$#21!7?@\x1b\\"
+        );
+    }
+
+    #[test]
+    fn kitty_sends_chunked_base64() {
+        assert_eq!(base64(b"neon"), "bmVvbg==");
+        assert_eq!(base64(b"fetch!"), "ZmV0Y2gh");
+        assert_eq!(
+            kitty(b"png", 4, 2),
+            "\x1b_Ga=T,f=100,c=4,r=2,C=1,q=2,m=0;cG5n\x1b\\"
+        );
+        let out = kitty(&[0; 4000], 4, 2);
+        assert_eq!(out.matches("\x1b_G").count(), 2);
+        assert!(out.contains("m=1;") && out.contains("\x1b_Gm=0;"));
+    }
+}
@@ -0,0 +1,204 @@
+//! Message catalog for info labels and the locale lookups that pick it.
+//!
+//! Messages are keyed by their English text, gettext style, so untranslated
+//! strings fall back to English for free.
+
+use std::env;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Lang {
+    #[default]
+    En,
+    ZhCn,
+}
+
+/// Simplified Chinese translations, keyed by the English message.
+const ZH_CN: &[(&str, &str)] = &[
+    ("OS", "系统"),
+    ("Host", "主机"),
+    ("Kernel", "内核"),
+    ("Kernel Build", "内核构建"),
+    ("Kernel Taint", "内核污染"),
+    ("Kernel Modules", "内核模块"),
+    ("Uptime", "运行时间"),
+    ("Boot", "启动"),
+    ("Shell", "Shell"),
+    ("Terminal", "终端"),
+    ("CPU", "处理器"),
+    ("Cores", "核心"),
+    ("GPU", "显卡"),
+    ("Resolution", "分辨率"),
+    ("Battery", "电池"),
+    ("Packages", "软件包"),
+    ("Temp", "温度"),
+    ("Memory", "内存"),
+    ("Swap", "交换"),
+    ("Disk", "磁盘"),
+    ("Storage", "存储"),
+    ("Local IP", "本地 IP"),
+    ("Locale", "语言环境"),
+    ("Users", "用户"),
+    ("Last Login", "上次登录"),
+    ("Containers", "容器"),
+    ("Git", "Git"),
+    ("Unknown", "未知"),
+    ("unknown", "未知"),
+    ("Unknown CPU", "未知处理器"),
+    ("Disabled", "已禁用"),
+    ("Secure Boot on", "安全启动已开启"),
+    ("Secure Boot off", "安全启动已关闭"),
+    ("Not tainted", "未污染"),
+    ("loaded", "已加载"),
+    ("session", "会话"),
+    ("sessions", "会话"),
+    ("on", "于"),
+    ("from", "来自"),
+    ("running", "运行中"),
+    ("total", "总计"),
+    ("images", "镜像"),
+    ("pkgs", "个包"),
+    ("clean", "无改动"),
+    ("modified", "处修改"),
+    ("last commit", "最近提交"),
+    ("just now", "刚刚"),
+    ("ago", "前"),
+    ("cores", "核"),
+    ("physical", "物理"),
+    ("logical", "逻辑"),
+    ("day", "天"),
+    ("days", "天"),
+    ("hour", "小时"),
+    ("hours", "小时"),
+    ("min", "分钟"),
+    ("mins", "分钟"),
+    ("minute", "分钟"),
+    ("minutes", "分钟"),
+    ("month", "个月"),
+    ("months", "个月"),
+    ("year", "年"),
+    ("years", "年"),
+];
+
+/// Languages whose locales write decimals with a comma.
+const DECIMAL_COMMA_LANGUAGES: &[&str] = &[
+    "bg", "ca", "cs", "da", "de", "el", "es", "et", "eu", "fi", "fr", "gl", "hr", "hu", "id", "is",
+    "it", "lt", "lv", "nb", "nl", "nn", "no", "pl", "pt", "ro", "ru", "sk", "sl", "sr", "sv", "tr",
+    "uk", "vi",
+];
+
+impl Lang {
+    /// Parse a language tag or POSIX locale name such as `zh-CN`,
+    /// `zh_CN.UTF-8` or `en_US`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let (language, region) = split_locale(value);
+        match (language.as_str(), region.as_str()) {
+            ("c" | "posix" | "en", _) => Some(Self::En),
+            ("zh", "" | "cn" | "sg" | "hans") => Some(Self::ZhCn),
+            _ => None,
+        }
+    }
+
+    /// Pick the message language from `LC_ALL`, `LC_MESSAGES` and `LANG`, in
+    /// POSIX precedence; unsupported languages fall back to English.
+    pub fn from_env() -> Self {
+        locale_env(&["LC_ALL", "LC_MESSAGES", "LANG"])
+            .and_then(|locale| Self::parse(&locale))
+            .unwrap_or_default()
+    }
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::ZhCn => "zh-CN",
+        }
+    }
+
+    /// Translate an English message, returning it unchanged when the catalog
+    /// has no entry.
+    pub fn tr(self, message: &str) -> &str {
+        let catalog = match self {
+            Self::En => return message,
+            Self::ZhCn => ZH_CN,
+        };
+        catalog
+            .iter()
+            .find(|(english, _)| *english == message)
+            .map_or(message, |(_, translated)| translated)
+    }
+}
+
+/// Decimal mark for the numeric locale (`LC_ALL`, `LC_NUMERIC`, `LANG`).
+pub fn decimal_separator_from_env() -> char {
+    locale_env(&["LC_ALL", "LC_NUMERIC", "LANG"])
+        .map_or('.', |locale| decimal_separator_for(&locale))
+}
+
+pub fn decimal_separator_for(locale: &str) -> char {
+    let (language, _) = split_locale(locale);
+    if DECIMAL_COMMA_LANGUAGES.contains(&language.as_str()) {
+        ','
+    } else {
+        '.'
+    }
+}
+
+/// Replace the decimal point in preformatted numeric text.
+pub fn localize_decimal(text: String, separator: char) -> String {
+    if separator == '.' {
+        text
+    } else {
+        text.replace('.', separator.encode_utf8(&mut [0; 4]))
+    }
+}
+
+fn locale_env(names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+}
+
+/// Lowercased (language, region) from `ll_RR.codeset@modifier` or `ll-RR`.
+fn split_locale(value: &str) -> (String, String) {
+    let base = value.split(['.', '@']).next().unwrap_or_default();
+    let mut parts = base.split(['_', '-']);
+    let language = parts.next().unwrap_or_default().to_ascii_lowercase();
+    let region = parts.next().unwrap_or_default().to_ascii_lowercase();
+    (language, region)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_language_tags_and_locales() {
+        assert_eq!(Lang::parse("zh-CN"), Some(Lang::ZhCn));
+        assert_eq!(Lang::parse("zh_CN.UTF-8"), Some(Lang::ZhCn));
+        assert_eq!(Lang::parse("zh"), Some(Lang::ZhCn));
+        assert_eq!(Lang::parse("zh_TW.UTF-8"), None);
+        assert_eq!(Lang::parse("en_GB.UTF-8"), Some(Lang::En));
+        assert_eq!(Lang::parse("C.UTF-8"), Some(Lang::En));
+        assert_eq!(Lang::parse("de_DE"), None);
+    }
+
+    #[test]
+    fn translates_with_english_fallback() {
+        assert_eq!(Lang::ZhCn.tr("Memory"), "内存");
+        assert_eq!(Lang::ZhCn.tr("Swap"), "交换");
+        assert_eq!(Lang::ZhCn.tr("Not in catalog"), "Not in catalog");
+        assert_eq!(Lang::En.tr("Memory"), "Memory");
+    }
+
+    #[test]
+    fn picks_decimal_separator_from_locale() {
+        assert_eq!(decimal_separator_for("de_DE.UTF-8"), ',');
+        assert_eq!(decimal_separator_for("fr_FR"), ',');
+        assert_eq!(decimal_separator_for("zh_CN.UTF-8"), '.');
+        assert_eq!(decimal_separator_for("C"), '.');
+        assert_eq!(
+            localize_decimal("1.50 GiB".to_string(), ','),
+            "1,50 GiB".to_string()
+        );
+    }
+}
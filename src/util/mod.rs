@@ -1,2 +1,14 @@
 pub mod ansi;
+pub mod cell;
+pub mod color;
+#[cfg(test)]
+pub mod fixture;
 pub mod framebuf;
+pub mod graphics;
+pub mod i18n;
+pub mod png;
+pub mod template;
+pub mod term;
+pub mod time;
+pub mod units;
+pub mod width;
@@ -0,0 +1,377 @@
+//! Minimal PNG decoder for `--logo-image`: every color type and bit depth,
+//! without interlacing. Pixels come out as 8-bit straight-alpha RGBA.
+
+use miniz_oxide::inflate::{TINFLStatus, decompress_to_vec_zlib_with_limit};
+
+const SIGNATURE: &[u8; 8] = b"\x89PNG\r\n\x1a\n";
+/// Refuse images whose pixel buffer alone would be unreasonably large.
+const MAX_PIXELS: usize = 1 << 24;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    /// Row-major RGBA.
+    pub pixels: Vec<[u8; 4]>,
+}
+
+impl Image {
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 4] {
+        self.pixels[y * self.width + x]
+    }
+}
+
+struct Header {
+    width: usize,
+    height: usize,
+    depth: u8,
+    color_type: u8,
+}
+
+impl Header {
+    fn channels(&self) -> usize {
+        match self.color_type {
+            0 | 3 => 1,
+            4 => 2,
+            2 => 3,
+            _ => 4,
+        }
+    }
+}
+
+pub fn decode(data: &[u8]) -> Result<Image, String> {
+    let mut rest = data
+        .strip_prefix(SIGNATURE)
+        .ok_or_else(|| "not a PNG file".to_string())?;
+    let mut header = None;
+    let mut palette: Vec<[u8; 4]> = Vec::new();
+    let mut transparent: Option<Vec<u8>> = None;
+    let mut compressed = Vec::new();
+    loop {
+        if rest.len() < 12 {
+            return Err("truncated PNG chunk".to_string());
+        }
+        let len = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+        let body = rest
+            .get(4..8 + len)
+            .ok_or_else(|| "truncated PNG chunk".to_string())?;
+        let crc = rest
+            .get(8 + len..12 + len)
+            .ok_or_else(|| "truncated PNG chunk".to_string())?;
+        if crc32(body) != u32::from_be_bytes([crc[0], crc[1], crc[2], crc[3]]) {
+            return Err("PNG chunk checksum mismatch".to_string());
+        }
+        let (kind, chunk) = body.split_at(4);
+        rest = &rest[12 + len..];
+        match kind {
+            b"IHDR" => header = Some(parse_header(chunk)?),
+            b"PLTE" => {
+                palette = chunk
+                    .chunks_exact(3)
+                    .map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+                    .collect();
+            }
+            b"tRNS" => transparent = Some(chunk.to_vec()),
+            b"IDAT" => compressed.extend_from_slice(chunk),
+            b"IEND" => break,
+            _ => {}
+        }
+    }
+    let header = header.ok_or_else(|| "PNG has no IHDR chunk".to_string())?;
+    if header.color_type == 3 {
+        if palette.is_empty() {
+            return Err("indexed PNG has no palette".to_string());
+        }
+        for (entry, alpha) in palette.iter_mut().zip(transparent.iter().flatten()) {
+            entry[3] = *alpha;
+        }
+    }
+
+    let bits_per_pixel = header.channels() * header.depth as usize;
+    let stride = (header.width * bits_per_pixel).div_ceil(8);
+    let filter_step = bits_per_pixel.div_ceil(8);
+    // Inflate no further than the header's rows need, so a small file can't
+    // expand into gigabytes first.
+    let expected = (stride + 1) * header.height;
+    let data = decompress_to_vec_zlib_with_limit(&compressed, expected).map_err(|err| match err
+        .status
+    {
+        TINFLStatus::HasMoreOutput => "PNG image data is larger than its header says".to_string(),
+        _ => format!("PNG image data is corrupt: {}", err),
+    })?;
+    if data.len() < expected {
+        return Err("PNG image data is truncated".to_string());
+    }
+
+    let mut pixels = Vec::with_capacity(header.width * header.height);
+    let mut prev = vec![0u8; stride];
+    let mut row = vec![0u8; stride];
+    for line in data.chunks_exact(stride + 1).take(header.height) {
+        row.copy_from_slice(&line[1..]);
+        unfilter(line[0], &mut row, &prev, filter_step)?;
+        for x in 0..header.width {
+            pixels.push(read_pixel(
+                &header,
+                &row,
+                x,
+                &palette,
+                transparent.as_deref(),
+            ));
+        }
+        std::mem::swap(&mut prev, &mut row);
+    }
+    Ok(Image {
+        width: header.width,
+        height: header.height,
+        pixels,
+    })
+}
+
+fn parse_header(chunk: &[u8]) -> Result<Header, String> {
+    if chunk.len() != 13 {
+        return Err("invalid PNG header".to_string());
+    }
+    let width = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]) as usize;
+    let height = u32::from_be_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]) as usize;
+    let (depth, color_type) = (chunk[8], chunk[9]);
+    let valid = match color_type {
+        0 => matches!(depth, 1 | 2 | 4 | 8 | 16),
+        3 => matches!(depth, 1 | 2 | 4 | 8),
+        2 | 4 | 6 => matches!(depth, 8 | 16),
+        _ => false,
+    };
+    if !valid {
+        return Err(format!(
+            "unsupported PNG color type {color_type} with bit depth {depth}"
+        ));
+    }
+    if chunk[10] != 0 || chunk[11] != 0 {
+        return Err("unknown PNG compression or filter method".to_string());
+    }
+    if chunk[12] != 0 {
+        return Err("interlaced PNGs are not supported".to_string());
+    }
+    if width == 0 || height == 0 || width.saturating_mul(height) > MAX_PIXELS {
+        return Err(format!("unsupported PNG size {width}x{height}"));
+    }
+    Ok(Header {
+        width,
+        height,
+        depth,
+        color_type,
+    })
+}
+
+/// Undo one scanline's filter in place; `step` is the byte distance to the
+/// corresponding byte of the pixel on the left.
+fn unfilter(filter: u8, row: &mut [u8], prev: &[u8], step: usize) -> Result<(), String> {
+    for i in 0..row.len() {
+        let left = if i >= step { row[i - step] } else { 0 };
+        let up = prev[i];
+        let up_left = if i >= step { prev[i - step] } else { 0 };
+        let predicted = match filter {
+            0 => 0,
+            1 => left,
+            2 => up,
+            3 => ((left as u16 + up as u16) / 2) as u8,
+            4 => paeth(left, up, up_left),
+            _ => return Err(format!("unknown PNG filter type {filter}")),
+        };
+        row[i] = row[i].wrapping_add(predicted);
+    }
+    Ok(())
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = (
+        (p - a as i16).abs(),
+        (p - b as i16).abs(),
+        (p - c as i16).abs(),
+    );
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+fn read_pixel(
+    header: &Header,
+    row: &[u8],
+    x: usize,
+    palette: &[[u8; 4]],
+    transparent: Option<&[u8]>,
+) -> [u8; 4] {
+    let channels = header.channels();
+    let raw = |channel: usize| -> u16 {
+        let index = x * channels + channel;
+        match header.depth {
+            16 => u16::from_be_bytes([row[index * 2], row[index * 2 + 1]]),
+            8 => row[index] as u16,
+            depth => {
+                let bit = index * depth as usize;
+                let shift = 8 - depth as usize - bit % 8;
+                ((row[bit / 8] >> shift) & ((1 << depth) - 1)) as u16
+            }
+        }
+    };
+    let scale = |value: u16| -> u8 {
+        match header.depth {
+            16 => (value >> 8) as u8,
+            depth => (value as u32 * 255 / ((1u32 << depth) - 1)) as u8,
+        }
+    };
+    // tRNS for gray and truecolor images names one fully transparent sample.
+    let keyed = |samples: &[u16]| {
+        transparent.is_some_and(|key| {
+            key.len() == samples.len() * 2
+                && samples
+                    .iter()
+                    .enumerate()
+                    .all(|(i, &sample)| u16::from_be_bytes([key[i * 2], key[i * 2 + 1]]) == sample)
+        })
+    };
+    match header.color_type {
+        0 => {
+            let gray = raw(0);
+            let alpha = if keyed(&[gray]) { 0 } else { 255 };
+            let gray = scale(gray);
+            [gray, gray, gray, alpha]
+        }
+        2 => {
+            let rgb = [raw(0), raw(1), raw(2)];
+            let alpha = if keyed(&rgb) { 0 } else { 255 };
+            [scale(rgb[0]), scale(rgb[1]), scale(rgb[2]), alpha]
+        }
+        3 => palette
+            .get(raw(0) as usize)
+            .copied()
+            .unwrap_or([0, 0, 0, 0]),
+        4 => {
+            let gray = scale(raw(0));
+            [gray, gray, gray, scale(raw(1))]
+        }
+        _ => [scale(raw(0)), scale(raw(1)), scale(raw(2)), scale(raw(3))],
+    }
+}
+
+/// CRC-32 (ISO 3309) as PNG chunks use it.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 3x5 RGB, rows filtered with None, Sub, Up, Average and Paeth in turn.
+    const RGB_FILTERED: [u8; 103] = [
+        137, 80, 78, 71, 13, 10, 26, 10, 0, 0, 0, 13, 73, 72, 68, 82, 0, 0, 0, 3, 0, 0, 0, 5, 8, 2,
+        0, 0, 0, 15, 19, 193, 245, 0, 0, 0, 46, 73, 68, 65, 84, 120, 218, 99, 96, 96, 63, 225, 38,
+        189, 160, 71, 191, 130, 81, 206, 114, 191, 155, 200, 13, 32, 98, 146, 51, 250, 14, 65, 204,
+        54, 25, 65, 70, 202, 47, 128, 136, 5, 36, 32, 2, 66, 0, 156, 50, 17, 102, 14, 149, 74, 186,
+        0, 0, 0, 0, 73, 69, 78, 68, 174, 66, 96, 130,
+    ];
+
+    // 4x1, 2-bit indexed with a tRNS chunk for the first two entries.
+    const INDEXED_2BIT: [u8; 105] = [
+        137, 80, 78, 71, 13, 10, 26, 10, 0, 0, 0, 13, 73, 72, 68, 82, 0, 0, 0, 4, 0, 0, 0, 1, 2, 3,
+        0, 0, 0, 132, 82, 231, 94, 0, 0, 0, 12, 80, 76, 84, 69, 255, 0, 0, 0, 255, 0, 0, 0, 255, 9,
+        9, 9, 92, 113, 126, 134, 0, 0, 0, 2, 116, 82, 78, 83, 255, 128, 8, 15, 179, 106, 0, 0, 0,
+        10, 73, 68, 65, 84, 120, 218, 99, 144, 6, 0, 0, 29, 0, 28, 35, 124, 143, 172, 0, 0, 0, 0,
+        73, 69, 78, 68, 174, 66, 96, 130,
+    ];
+
+    // 2x1, 16-bit gray with alpha.
+    const GRAY_ALPHA_16: [u8; 74] = [
+        137, 80, 78, 71, 13, 10, 26, 10, 0, 0, 0, 13, 73, 72, 68, 82, 0, 0, 0, 2, 0, 0, 0, 1, 16,
+        4, 0, 0, 0, 14, 187, 107, 66, 0, 0, 0, 17, 73, 68, 65, 84, 120, 218, 99, 16, 50, 249, 255,
+        127, 245, 89, 6, 6, 0, 18, 13, 3, 189, 74, 58, 58, 128, 0, 0, 0, 0, 73, 69, 78, 68, 174,
+        66, 96, 130,
+    ];
+
+    #[test]
+    fn decodes_every_filter_type() {
+        let image = decode(&RGB_FILTERED).unwrap();
+        assert_eq!((image.width, image.height), (3, 5));
+        for y in 0..5 {
+            for x in 0..3 {
+                let expected = [
+                    ((x * 70 + y * 30) % 256) as u8,
+                    ((x * 20 + y * 50 + 7) % 256) as u8,
+                    ((200 + 256 - x * 40 - y * 9) % 256) as u8,
+                    255,
+                ];
+                assert_eq!(image.pixel(x, y), expected, "({x}, {y})");
+            }
+        }
+    }
+
+    #[test]
+    fn decodes_packed_palettes_and_wide_samples() {
+        let image = decode(&INDEXED_2BIT).unwrap();
+        assert_eq!(
+            image.pixels,
+            [
+                [255, 0, 0, 255],
+                [0, 255, 0, 128],
+                [0, 0, 255, 255],
+                [9, 9, 9, 255]
+            ]
+        );
+
+        let image = decode(&GRAY_ALPHA_16).unwrap();
+        assert_eq!(
+            image.pixels,
+            [[0x12, 0x12, 0x12, 255], [0xab, 0xab, 0xab, 0]]
+        );
+    }
+
+    #[test]
+    fn rejects_damaged_files() {
+        assert_eq!(decode(b"GIF89a").unwrap_err(), "not a PNG file");
+        let mut damaged = RGB_FILTERED;
+        damaged[50] ^= 1;
+        assert_eq!(decode(&damaged).unwrap_err(), "PNG chunk checksum mismatch");
+        assert_eq!(
+            decode(&RGB_FILTERED[..60]).unwrap_err(),
+            "truncated PNG chunk"
+        );
+    }
+
+    #[test]
+    fn stops_inflating_past_the_header_size() {
+        let chunk = |kind: &[u8], data: &[u8]| {
+            let mut body = kind.to_vec();
+            body.extend_from_slice(data);
+            let mut out = (data.len() as u32).to_be_bytes().to_vec();
+            out.extend_from_slice(&body);
+            out.extend_from_slice(&crc32(&body).to_be_bytes());
+            out
+        };
+        // 1x1 8-bit gray: two bytes of image data, then a megabyte more.
+        let mut png = SIGNATURE.to_vec();
+        png.extend(chunk(b"IHDR", &[0, 0, 0, 1, 0, 0, 0, 1, 8, 0, 0, 0, 0]));
+        let idat = miniz_oxide::deflate::compress_to_vec_zlib(&vec![0u8; 1 << 20], 6);
+        png.extend(chunk(b"IDAT", &idat));
+        png.extend(chunk(b"IEND", &[]));
+        assert_eq!(
+            decode(&png).unwrap_err(),
+            "PNG image data is larger than its header says"
+        );
+    }
+}
@@ -0,0 +1,283 @@
+//! Small `{name:spec}` template language used for info field values.
+//!
+//! Placeholders take an optional spec of `[<>^][width][.precision]`, like
+//! Rust's `format!`; `{{` and `}}` produce literal braces.
+
+use crate::util::i18n::localize_decimal;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TemplateValue {
+    Text(String),
+    Integer(i64),
+    Float(f64),
+}
+
+impl From<&str> for TemplateValue {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+impl From<String> for TemplateValue {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<u64> for TemplateValue {
+    fn from(value: u64) -> Self {
+        Self::Integer(value as i64)
+    }
+}
+
+impl From<usize> for TemplateValue {
+    fn from(value: usize) -> Self {
+        Self::Integer(value as i64)
+    }
+}
+
+impl From<u32> for TemplateValue {
+    fn from(value: u32) -> Self {
+        Self::Integer(i64::from(value))
+    }
+}
+
+impl From<f64> for TemplateValue {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Placeholder {
+    name: String,
+    align: Option<Align>,
+    width: usize,
+    precision: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    Literal(String),
+    Placeholder(Placeholder),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Template {
+    source: String,
+    segments: Vec<Segment>,
+}
+
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = source.char_indices().peekable();
+        while let Some((index, ch)) = chars.next() {
+            match ch {
+                '{' if chars.peek().is_some_and(|&(_, next)| next == '{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek().is_some_and(|&(_, next)| next == '}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let body: String = chars
+                        .by_ref()
+                        .map(|(_, ch)| ch)
+                        .take_while(|&ch| ch != '}')
+                        .collect();
+                    if !source[index + 1..].contains('}') {
+                        return Err(format!("unclosed `{{` at position {}", index + 1));
+                    }
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Placeholder(parse_placeholder(&body)?));
+                }
+                '}' => return Err(format!("unmatched `}}` at position {}", index + 1)),
+                _ => literal.push(ch),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+        Ok(Self {
+            source: source.to_string(),
+            segments,
+        })
+    }
+
+    /// Names of all placeholders, in order of appearance.
+    pub fn placeholders(&self) -> impl Iterator<Item = &str> {
+        self.segments.iter().filter_map(|segment| match segment {
+            Segment::Placeholder(placeholder) => Some(placeholder.name.as_str()),
+            Segment::Literal(_) => None,
+        })
+    }
+
+    /// Render with `values`; placeholders without a value render empty and
+    /// numbers use `decimal_separator`.
+    pub fn render(&self, values: &[(&str, TemplateValue)], decimal_separator: char) -> String {
+        let mut out = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => out.push_str(text),
+                Segment::Placeholder(placeholder) => {
+                    let value = values
+                        .iter()
+                        .find(|(name, _)| *name == placeholder.name)
+                        .map(|(_, value)| value);
+                    out.push_str(&render_placeholder(placeholder, value, decimal_separator));
+                }
+            }
+        }
+        out
+    }
+}
+
+fn parse_placeholder(body: &str) -> Result<Placeholder, String> {
+    let (name, spec) = body.split_once(':').unwrap_or((body, ""));
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("empty placeholder `{}`".to_string());
+    }
+    if !name
+        .chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+    {
+        return Err(format!("invalid placeholder name `{name}`"));
+    }
+    let invalid_spec = || format!("invalid format spec `{spec}` for `{name}`");
+
+    let mut rest = spec;
+    let align = match rest.chars().next() {
+        Some('<') => Some(Align::Left),
+        Some('>') => Some(Align::Right),
+        Some('^') => Some(Align::Center),
+        _ => None,
+    };
+    if align.is_some() {
+        rest = &rest[1..];
+    }
+    let (width, precision) = match rest.split_once('.') {
+        Some((width, precision)) => (
+            width,
+            Some(precision.parse::<usize>().map_err(|_| invalid_spec())?),
+        ),
+        None => (rest, None),
+    };
+    let width = if width.is_empty() {
+        0
+    } else {
+        width.parse::<usize>().map_err(|_| invalid_spec())?
+    };
+    Ok(Placeholder {
+        name: name.to_string(),
+        align,
+        width,
+        precision,
+    })
+}
+
+fn render_placeholder(
+    placeholder: &Placeholder,
+    value: Option<&TemplateValue>,
+    decimal_separator: char,
+) -> String {
+    let text = match (value, placeholder.precision) {
+        (None, _) => String::new(),
+        (Some(TemplateValue::Text(text)), Some(precision)) => {
+            text.chars().take(precision).collect()
+        }
+        (Some(TemplateValue::Text(text)), None) => text.clone(),
+        (Some(TemplateValue::Integer(value)), Some(precision)) => localize_decimal(
+            format!("{:.*}", precision, *value as f64),
+            decimal_separator,
+        ),
+        (Some(TemplateValue::Integer(value)), None) => value.to_string(),
+        (Some(TemplateValue::Float(value)), Some(precision)) => {
+            localize_decimal(format!("{:.*}", precision, value), decimal_separator)
+        }
+        (Some(TemplateValue::Float(value)), None) => {
+            localize_decimal(format!("{:.2}", value), decimal_separator)
+        }
+    };
+    let width = placeholder.width;
+    let default_align = match value {
+        Some(TemplateValue::Text(_)) | None => Align::Left,
+        _ => Align::Right,
+    };
+    match placeholder.align.unwrap_or(default_align) {
+        Align::Left => format!("{:<width$}", text),
+        Align::Right => format!("{:>width$}", text),
+        Align::Center => format!("{:^width$}", text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_named_placeholders_with_specs() {
+        let template = Template::parse("{used_gib:.1}/{total_gib:.0} GiB ({percent}%) {{ok}}")
+            .expect("valid template");
+        let values = [
+            ("used_gib", TemplateValue::from(3.456)),
+            ("total_gib", TemplateValue::from(15.9)),
+            ("percent", TemplateValue::from(22u32)),
+        ];
+        assert_eq!(template.render(&values, '.'), "3.5/16 GiB (22%) {ok}");
+        assert_eq!(template.render(&values, ','), "3,5/16 GiB (22%) {ok}");
+        assert_eq!(
+            template.placeholders().collect::<Vec<_>>(),
+            ["used_gib", "total_gib", "percent"]
+        );
+
+        let padded = Template::parse("[{name:>6}|{name:^7}|{name:.2}|{n:<4}|{n:3}|{gone}]")
+            .expect("valid template");
+        let values = [
+            ("name", TemplateValue::from("zsh")),
+            ("n", TemplateValue::from(7u32)),
+        ];
+        assert_eq!(padded.render(&values, '.'), "[   zsh|  zsh  |zs|7   |  7|]");
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        assert_eq!(
+            Template::parse("{used").unwrap_err(),
+            "unclosed `{` at position 1"
+        );
+        assert_eq!(
+            Template::parse("a } b").unwrap_err(),
+            "unmatched `}` at position 3"
+        );
+        assert_eq!(Template::parse("{}").unwrap_err(), "empty placeholder `{}`");
+        assert_eq!(
+            Template::parse("{used:.x}").unwrap_err(),
+            "invalid format spec `.x` for `used`"
+        );
+        assert_eq!(
+            Template::parse("{used gib}").unwrap_err(),
+            "invalid placeholder name `used gib`"
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! Terminal capability queries answered in-band on the tty.
+
+use std::io::{self, Write};
+use std::time::Duration;
+
+/// DEC private mode for synchronized output: the terminal holds drawing
+/// between set and reset so a frame never shows half-written.
+pub const SYNC_BEGIN: &str = "\x1b[?2026h";
+pub const SYNC_END: &str = "\x1b[?2026l";
+
+/// Ask the terminal (via DECRQM) whether it supports synchronized output.
+///
+/// The query is followed by a primary device attributes request, which every
+/// terminal answers, so ones that ignore DECRQM are detected without waiting
+/// out the whole timeout. Must be called in raw mode, before anything else
+/// reads stdin.
+pub fn query_synchronized_output(timeout: Duration) -> bool {
+    let mut out = io::stdout();
+    if out
+        .write_all(b"\x1b[?2026$p\x1b[c")
+        .and_then(|_| out.flush())
+        .is_err()
+    {
+        return false;
+    }
+    let reply = read_reply(timeout, |reply| da1_end(reply).is_some());
+    decrpm_state(&reply, 2026).is_some_and(mode_supported)
+}
+
+/// Image protocols the terminal answered for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Graphics {
+    pub kitty: bool,
+    pub sixel: bool,
+}
+
+/// Probe for the kitty graphics protocol (a query action the terminal must
+/// acknowledge) and for Sixel (attribute 4 in the primary device attributes).
+/// Same rules as `query_synchronized_output`.
+pub fn query_graphics(timeout: Duration) -> Graphics {
+    let mut out = io::stdout();
+    if out
+        .write_all(b"\x1b_Gi=31,s=1,v=1,a=q,t=d,f=24;AAAA\x1b\\\x1b[c")
+        .and_then(|_| out.flush())
+        .is_err()
+    {
+        return Graphics::default();
+    }
+    let reply = read_reply(timeout, |reply| da1_end(reply).is_some());
+    Graphics {
+        kitty: find(&reply, b"\x1b_Gi=31;OK").is_some(),
+        sixel: da1_params(&reply).is_some_and(|params| params.contains(&4)),
+    }
+}
+
+/// DECRPM states 1 (set), 2 (reset) and 3 (permanently set) mean the mode
+/// is recognized; 0 is unknown and 4 is permanently reset.
+fn mode_supported(state: u8) -> bool {
+    matches!(state, 1..=3)
+}
+
+/// Find `CSI ? <mode> ; <state> $ y` in a reply and return the state.
+fn decrpm_state(reply: &[u8], mode: u16) -> Option<u8> {
+    let prefix = format!("\x1b[?{mode};");
+    let start = find(reply, prefix.as_bytes())? + prefix.len();
+    let rest = &reply[start..];
+    let digits = rest.iter().take_while(|b| b.is_ascii_digit()).count();
+    if !rest[digits..].starts_with(b"$y") {
+        return None;
+    }
+    std::str::from_utf8(&rest[..digits]).ok()?.parse().ok()
+}
+
+/// Index just past a primary device attributes reply (`CSI ? ... c`).
+fn da1_end(reply: &[u8]) -> Option<usize> {
+    da1(reply).map(|(end, _)| end)
+}
+
+/// The attributes a primary device attributes reply lists.
+fn da1_params(reply: &[u8]) -> Option<Vec<u16>> {
+    let (_, params) = da1(reply)?;
+    Some(
+        params
+            .split(|b| *b == b';')
+            .filter_map(|param| std::str::from_utf8(param).ok()?.parse().ok())
+            .collect(),
+    )
+}
+
+fn da1(reply: &[u8]) -> Option<(usize, &[u8])> {
+    let mut from = 0;
+    while let Some(offset) = find(&reply[from..], b"\x1b[?") {
+        let start = from + offset + 3;
+        let params = reply[start..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit() || **b == b';')
+            .count();
+        match reply.get(start + params) {
+            Some(b'c') => return Some((start + params + 1, &reply[start..start + params])),
+            Some(_) => from = start,
+            None => return None,
+        }
+    }
+    None
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(unix)]
+fn read_reply(timeout: Duration, done: impl Fn(&[u8]) -> bool) -> Vec<u8> {
+    use std::time::Instant;
+
+    let deadline = Instant::now() + timeout;
+    let mut reply = Vec::new();
+    let mut chunk = [0u8; 256];
+    while !done(&reply) {
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            break;
+        }
+        let mut fd = libc::pollfd {
+            fd: libc::STDIN_FILENO,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: `fd` is a single valid pollfd and `chunk` outlives the read.
+        let read = unsafe {
+            if libc::poll(&mut fd, 1, left.as_millis().max(1) as libc::c_int) <= 0 {
+                break;
+            }
+            libc::read(
+                libc::STDIN_FILENO,
+                chunk.as_mut_ptr() as *mut libc::c_void,
+                chunk.len(),
+            )
+        };
+        if read <= 0 {
+            break;
+        }
+        reply.extend_from_slice(&chunk[..read as usize]);
+    }
+    reply
+}
+
+/// Console input isn't readable as a byte stream here; assume no support.
+#[cfg(not(unix))]
+fn read_reply(_timeout: Duration, _done: impl Fn(&[u8]) -> bool) -> Vec<u8> {
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decrpm_replies_parse() {
+        let reply = b"\x1b[?2026;2$y\x1b[?62;22c";
+        assert_eq!(decrpm_state(reply, 2026), Some(2));
+        assert_eq!(da1_end(reply), Some(reply.len()));
+        assert!(mode_supported(2));
+
+        // Terminals that don't know DECRQM only answer the DA1 request.
+        let reply = b"\x1b[?1;2c";
+        assert_eq!(decrpm_state(reply, 2026), None);
+        assert_eq!(da1_end(reply), Some(reply.len()));
+
+        assert_eq!(decrpm_state(b"\x1b[?2026;0$y", 2026), Some(0));
+        assert!(!mode_supported(0));
+        assert!(!mode_supported(4));
+        assert_eq!(decrpm_state(b"\x1b[?2026;1$", 2026), None);
+        assert_eq!(da1_end(b"\x1b[?2026;1$y\x1b[?6"), None);
+    }
+
+    #[test]
+    fn device_attributes_list_sixel() {
+        let reply = b"\x1b_Gi=31;OK\x1b\\\x1b[?62;4;22c";
+        assert_eq!(da1_params(reply), Some(vec![62, 4, 22]));
+        assert_eq!(da1_params(b"\x1b[?1;2c"), Some(vec![1, 2]));
+        assert_eq!(da1_params(b"\x1b[?62;4"), None);
+    }
+}
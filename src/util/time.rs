@@ -0,0 +1,169 @@
+use crate::util::i18n::Lang;
+use std::process::Command;
+
+/// Convert days since the Unix epoch into a proleptic Gregorian
+/// (year, month, day), using Howard Hinnant's `civil_from_days`.
+pub fn civil_from_unix_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let y = yoe + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = mp + if mp < 10 { 3 } else { -9 };
+    let year = y + if month <= 2 { 1 } else { 0 };
+
+    (year, month as u32, day as u32)
+}
+
+/// Format a Unix timestamp as `YYYY-MM-DD HH:MM UTC`.
+pub fn format_utc_timestamp(secs: u64) -> String {
+    let (year, month, day) = civil_from_unix_days((secs / 86_400) as i64);
+    let rem = secs % 86_400;
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        (rem % 3_600) / 60
+    )
+}
+
+/// Format a Unix timestamp as RFC 3339 UTC, `YYYY-MM-DDTHH:MM:SSZ`.
+pub fn format_rfc3339_utc(secs: u64) -> String {
+    let (year, month, day) = civil_from_unix_days((secs / 86_400) as i64);
+    let rem = secs % 86_400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        (rem % 3_600) / 60,
+        rem % 60
+    )
+}
+
+/// Format a Unix timestamp in the local timezone via `date`, like the daily
+/// style picker does, falling back to UTC when it is unavailable.
+pub fn format_local_timestamp(secs: u64) -> String {
+    let at = format!("@{secs}");
+    if let Ok(output) = Command::new("date")
+        .args(["-d", &at, "+%Y-%m-%d %H:%M %Z"])
+        .output()
+        && output.status.success()
+        && let Ok(text) = String::from_utf8(output.stdout)
+        && !text.trim().is_empty()
+    {
+        return text.trim().to_string();
+    }
+    format_utc_timestamp(secs)
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UptimeStyle {
+    /// `3 days, 4 hours, 12 mins`
+    #[default]
+    Long,
+    /// `3d 4h 12m`
+    Short,
+}
+
+impl UptimeStyle {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "long" => Some(Self::Long),
+            "short" => Some(Self::Short),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Long => "long",
+            Self::Short => "short",
+        }
+    }
+}
+
+/// Format an uptime as days, hours and minutes, dropping leading zero units.
+/// The long style uses `lang`'s unit words; the short style is
+/// language-neutral.
+pub fn format_uptime(secs: u64, style: UptimeStyle, lang: Lang) -> String {
+    let days = secs / 86_400;
+    let hours = (secs % 86_400) / 3_600;
+    let minutes = (secs % 3_600) / 60;
+    let long = |n: u64, one: &str, many: &str| {
+        format!("{} {}", n, lang.tr(if n == 1 { one } else { many }))
+    };
+    let mut parts = Vec::new();
+    if days > 0 {
+        parts.push(match style {
+            UptimeStyle::Long => long(days, "day", "days"),
+            UptimeStyle::Short => format!("{}d", days),
+        });
+    }
+    if days > 0 || hours > 0 {
+        parts.push(match style {
+            UptimeStyle::Long => long(hours, "hour", "hours"),
+            UptimeStyle::Short => format!("{}h", hours),
+        });
+    }
+    parts.push(match style {
+        UptimeStyle::Long => long(minutes, "min", "mins"),
+        UptimeStyle::Short => format!("{}m", minutes),
+    });
+    match (style, lang) {
+        (UptimeStyle::Long, Lang::En) => parts.join(", "),
+        _ => parts.join(" "),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_uptime_styles() {
+        let secs = 40 * 86_400 + 3 * 3_600 + 12 * 60 + 59;
+        assert_eq!(
+            format_uptime(secs, UptimeStyle::Long, Lang::En),
+            "40 days, 3 hours, 12 mins"
+        );
+        assert_eq!(
+            format_uptime(secs, UptimeStyle::Short, Lang::En),
+            "40d 3h 12m"
+        );
+        assert_eq!(
+            format_uptime(86_400 + 60, UptimeStyle::Long, Lang::En),
+            "1 day, 0 hours, 1 min"
+        );
+        assert_eq!(
+            format_uptime(2 * 3_600, UptimeStyle::Short, Lang::En),
+            "2h 0m"
+        );
+        assert_eq!(format_uptime(59, UptimeStyle::Long, Lang::En), "0 mins");
+        assert_eq!(
+            format_uptime(secs, UptimeStyle::Long, Lang::ZhCn),
+            "40 天 3 小时 12 分钟"
+        );
+    }
+
+    #[test]
+    fn converts_known_days() {
+        assert_eq!(civil_from_unix_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_unix_days(20_000), (2024, 10, 4));
+        assert_eq!(civil_from_unix_days(-1), (1969, 12, 31));
+    }
+
+    #[test]
+    fn formats_utc_timestamp() {
+        assert_eq!(format_utc_timestamp(0), "1970-01-01 00:00 UTC");
+        assert_eq!(format_utc_timestamp(1_728_000_000), "2024-10-04 00:00 UTC");
+        assert_eq!(format_utc_timestamp(1_728_047_580), "2024-10-04 13:13 UTC");
+        assert_eq!(format_rfc3339_utc(1_728_047_589), "2024-10-04T13:13:09Z");
+    }
+}
@@ -0,0 +1,98 @@
+//! Byte-size formatting shared by every size-reporting info field.
+
+use crate::util::i18n::localize_decimal;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnitSystem {
+    /// Powers of 1024: MiB, GiB, TiB.
+    #[default]
+    Binary,
+    /// Powers of 1000: MB, GB, TB.
+    Decimal,
+}
+
+impl UnitSystem {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "binary" | "iec" => Some(Self::Binary),
+            "decimal" | "si" => Some(Self::Decimal),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Binary => "binary",
+            Self::Decimal => "decimal",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ByteUnits {
+    pub system: UnitSystem,
+    pub precision: usize,
+    /// Decimal mark from the numeric locale.
+    pub decimal_separator: char,
+}
+
+impl Default for ByteUnits {
+    fn default() -> Self {
+        Self {
+            system: UnitSystem::Binary,
+            precision: 2,
+            decimal_separator: '.',
+        }
+    }
+}
+
+impl ByteUnits {
+    /// Format `bytes` in the largest unit from MiB up to TiB that keeps the
+    /// value at or above one (smaller sizes stay in MiB).
+    pub fn format(self, bytes: u64) -> String {
+        let (base, names) = match self.system {
+            UnitSystem::Binary => (1024.0_f64, ["MiB", "GiB", "TiB"]),
+            UnitSystem::Decimal => (1000.0_f64, ["MB", "GB", "TB"]),
+        };
+        let mut value = bytes as f64 / (base * base);
+        let mut unit = 0;
+        while value >= base && unit + 1 < names.len() {
+            value /= base;
+            unit += 1;
+        }
+        localize_decimal(
+            format!("{:.*} {}", self.precision, value, names[unit]),
+            self.decimal_separator,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scales_between_mib_and_tib() {
+        let binary = ByteUnits::default();
+        assert_eq!(binary.format(512 * 1024 * 1024), "512.00 MiB");
+        assert_eq!(binary.format(6 * 1024 * 1024 * 1024), "6.00 GiB");
+        assert_eq!(binary.format(3 << 40), "3.00 TiB");
+        assert_eq!(binary.format(5000 << 40), "5000.00 TiB");
+        assert_eq!(binary.format(1024), "0.00 MiB");
+
+        let decimal = ByteUnits {
+            system: UnitSystem::Decimal,
+            precision: 1,
+            decimal_separator: '.',
+        };
+        assert_eq!(decimal.format(512_110_190_592), "512.1 GB");
+        assert_eq!(decimal.format(999_000_000), "999.0 MB");
+        assert_eq!(decimal.format(2_000_000_000_000), "2.0 TB");
+
+        let comma = ByteUnits {
+            decimal_separator: ',',
+            ..binary
+        };
+        assert_eq!(comma.format(1536 * 1024 * 1024), "1,50 GiB");
+    }
+}
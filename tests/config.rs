@@ -52,7 +52,7 @@ fn assert_success(output: &Output) {
 fn print_config_uses_env_config_file() {
     let path = write_temp_config(
         "env",
-        r#"
+        r##"
 style = "matrix"
 speed = 2.0
 duration = 3.5
@@ -62,8 +62,28 @@ no_packages = true
 no_header = true
 mono = true
 no_color = true
+color_mode = "ANSI256"
+dither = true
+alt_screen = true
+fill_background = true
+logo_colors = "Modulate"
+image_protocol = "Sixel"
+logo_position = "Top"
+layout_gap = 1
+layout_padding = 2
+layout_center = true
+min_info_width = 30
 seed = 42
-"#,
+kernel_details = true
+uptime_format = "short"
+units = "decimal"
+unit_precision = 1
+lang = "zh_CN.UTF-8"
+
+[logo_slots]
+c1 = "#5277C3"
+c3 = 196
+"##,
     );
 
     let output = run_with_env_config(&path, &["--print-config"]);
@@ -79,7 +99,24 @@ seed = 42
     assert!(stdout.contains("no_header = true"));
     assert!(stdout.contains("mono = true"));
     assert!(stdout.contains("no_color = true"));
+    assert!(stdout.contains("color_mode = \"256\""));
+    assert!(stdout.contains("dither = true"));
+    assert!(stdout.contains("alt_screen = true"));
+    assert!(stdout.contains("fill_background = true"));
+    assert!(stdout.contains("logo_colors = \"modulate\""));
+    assert!(stdout.contains("image_protocol = \"sixel\""));
+    assert!(stdout.contains("logo_position = \"top\""));
+    assert!(stdout.contains("layout_gap = 1"));
+    assert!(stdout.contains("layout_padding = 2"));
+    assert!(stdout.contains("layout_center = true"));
+    assert!(stdout.contains("min_info_width = 30"));
     assert!(stdout.contains("seed = 42"));
+    assert!(stdout.contains("kernel_details = true"));
+    assert!(stdout.contains("uptime_format = \"short\""));
+    assert!(stdout.contains("units = \"decimal\""));
+    assert!(stdout.contains("unit_precision = 1"));
+    assert!(stdout.contains("lang = \"zh-CN\""));
+    assert!(stdout.contains("[logo_slots]\nc1 = \"#5277c3\"\nc3 = \"#ff0000\""));
 
     let _ = fs::remove_file(path);
 }
@@ -93,6 +130,7 @@ style = "matrix"
 speed = 2.0
 duration = 3.0
 color_fps = 60
+color_mode = "truecolor"
 "#,
     );
 
@@ -100,6 +138,7 @@ color_fps = 60
         &path,
         &[
             "--print-config",
+            "--color-mode=16",
             "--style",
             "fire",
             "--speed=3.0",
@@ -115,6 +154,7 @@ color_fps = 60
     assert!(stdout.contains("speed = 3.0"));
     assert!(stdout.contains("duration = 5.0"));
     assert!(stdout.contains("color_fps = 45"));
+    assert!(stdout.contains("color_mode = \"16\""));
     assert!(!stdout.contains("style = \"matrix\""));
 
     let _ = fs::remove_file(path);
@@ -170,3 +210,173 @@ speed = 2.0
 
     let _ = fs::remove_file(path);
 }
+
+#[test]
+fn custom_fields_follow_show_hide_and_json() {
+    let path = write_temp_config(
+        "custom-fields",
+        r#"
+no_logo = true
+
+[fields.greeting]
+label = "Greeting"
+env = "NEONFETCH_TEST_GREETING"
+
+[fields.release_notes]
+command = "echo ready"
+timeout = 2.5
+"#,
+    );
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_neonfetch"))
+            .args(args)
+            .env("NEONFETCH_CONFIG", &path)
+            .env("NEONFETCH_TEST_GREETING", "hello there")
+            .output()
+            .expect("failed to run neonfetch binary")
+    };
+
+    let output = run(&["--fetch", "--show", "release_notes,os,greeting"]);
+    assert_success(&output);
+    let lines: Vec<String> = stdout(&output).lines().map(str::to_string).collect();
+    assert_eq!(lines[0], "Release notes: ready");
+    assert!(lines[1].starts_with("OS: "));
+    assert_eq!(lines[2], "Greeting: hello there");
+
+    let output = run(&["--json", "--hide", "greeting"]);
+    assert_success(&output);
+    let value: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("stdout should be json");
+    let fields = value["fields"]
+        .as_array()
+        .expect("fields should be an array");
+    let field = |key: &str| fields.iter().find(|field| field["key"] == key);
+    assert_eq!(
+        field("release_notes").expect("custom field")["text"],
+        "Release notes: ready"
+    );
+    assert!(field("greeting").is_none());
+
+    let output = run(&["--list-fields"]);
+    assert!(stdout(&output).lines().any(|line| line == "greeting"));
+
+    let output = run(&["--print-config"]);
+    let printed = stdout(&output);
+    assert!(printed.contains(
+        "[fields.release_notes]\nlabel = \"Release notes\"\ncommand = \"echo ready\"\ntimeout = 2.5"
+    ));
+
+    let _ = fs::remove_file(path);
+}
+
+#[test]
+fn custom_field_errors_reject_config() {
+    for (name, contents) in [
+        ("custom-builtin", "[fields.os]\nenv = \"HOME\"\n"),
+        ("custom-nosource", "[fields.weather]\nlabel = \"Weather\"\n"),
+        (
+            "custom-twosources",
+            "[fields.weather]\nenv = \"A\"\nfile = \"/tmp/b\"\n",
+        ),
+        ("custom-section", "[display]\nmono = true\n"),
+        ("logo-slot-name", "[logo_slots]\nc7 = \"#ffffff\"\n"),
+        ("logo-slot-color", "[logo_slots]\nc1 = \"blue\"\n"),
+        ("logo-position", "logo_position = \"above\"\n"),
+        ("layout-gap", "layout_gap = 99\n"),
+        ("min-info-width", "min_info_width = -1\n"),
+    ] {
+        let path = write_temp_config(name, &format!("mono = true\n{contents}"));
+        let output = run_with_env_config(&path, &["--print-config"]);
+        assert_success(&output);
+        assert!(stdout(&output).contains("mono = false"), "{name}");
+        assert!(stderr(&output).contains("ignoring config"), "{name}");
+        let _ = fs::remove_file(path);
+    }
+}
+
+#[test]
+fn templates_labels_and_separator_shape_field_lines() {
+    let path = write_temp_config(
+        "templates",
+        r#"
+no_logo = true
+separator = " | "
+
+[fields.greeting]
+env = "NEONFETCH_TEST_GREETING"
+
+[labels]
+shell = "Sh"
+
+[templates]
+shell = "<{name}> via {path}"
+greeting = "{value:>8}!"
+"#,
+    );
+    let output = Command::new(env!("CARGO_BIN_EXE_neonfetch"))
+        .args(["--fetch", "--show", "shell,greeting,locale"])
+        .env("NEONFETCH_CONFIG", &path)
+        .env("NEONFETCH_TEST_GREETING", "hi")
+        .env("SHELL", "/usr/bin/fish")
+        .env("LANG", "C.UTF-8")
+        .output()
+        .expect("failed to run neonfetch binary");
+    assert_success(&output);
+    assert_eq!(
+        stdout(&output),
+        "Sh | <fish> via /usr/bin/fish\nGreeting |       hi!\nLocale | C.UTF-8\n"
+    );
+
+    let _ = fs::remove_file(path);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn labels_rename_kernel_detail_lines() {
+    let path = write_temp_config(
+        "kernel-labels",
+        "no_logo = true\n\n[labels]\nkernel_taint = \"Taint\"\nkernel_modules = \"Mods\"\n",
+    );
+    let output = run_with_env_config(&path, &["--fetch", "--show", "kernel", "--kernel-details"]);
+    assert_success(&output);
+    let text = stdout(&output);
+    assert!(
+        text.lines().any(|line| line.starts_with("Taint: ")),
+        "{text}"
+    );
+    assert!(!text.contains("Kernel Taint"), "{text}");
+
+    let _ = fs::remove_file(path);
+}
+
+#[test]
+fn invalid_templates_report_clear_errors() {
+    for (name, contents, message) in [
+        (
+            "template-syntax",
+            "[templates]\nmemory = \"{used_gib:.1\"\n",
+            "line 3: invalid template for `memory`: unclosed `{` at position 1",
+        ),
+        (
+            "template-placeholder",
+            "[templates]\nmemory = \"{free}\"\n",
+            "line 3: template for `memory` uses unknown placeholder `{free}` (available: used, total, used_gib, total_gib, percent, used_bytes, total_bytes, value)",
+        ),
+        (
+            "label-unknown",
+            "[labels]\nweather = \"Wx\"\n",
+            "line 3: unknown field `weather` in [labels]",
+        ),
+    ] {
+        let path = write_temp_config(name, &format!("mono = true\n{contents}"));
+        let output = run_with_env_config(&path, &["--print-config"]);
+        assert_success(&output);
+        assert!(stdout(&output).contains("mono = false"), "{name}");
+        assert!(
+            stderr(&output).contains(message),
+            "{name}: {}",
+            stderr(&output)
+        );
+        let _ = fs::remove_file(path);
+    }
+}
@@ -0,0 +1,288 @@
+//! `--json` output checked against the published schema. The validator below
+//! covers the JSON Schema keywords `schema/neonfetch.schema.json` uses.
+
+use serde_json::{Value, json};
+use std::{
+    fs,
+    path::PathBuf,
+    process::{Command, Output},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+fn run(args: &[&str]) -> Output {
+    let output = Command::new(env!("CARGO_BIN_EXE_neonfetch"))
+        .args(args)
+        .output()
+        .expect("failed to run neonfetch binary");
+    assert!(
+        output.status.success(),
+        "neonfetch {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output
+}
+
+fn schema() -> Value {
+    let text = fs::read_to_string(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/schema/neonfetch.schema.json"
+    ))
+    .expect("schema file should exist");
+    serde_json::from_str(&text).expect("schema should be json")
+}
+
+fn assert_valid(document: &Value) {
+    let schema = schema();
+    let mut errors = Vec::new();
+    validate(&schema, &schema, document, "", &mut errors);
+    assert!(errors.is_empty(), "{errors:#?}\n{document:#}");
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before unix epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!(
+        "neonfetch-{name}-{}-{nanos}.json",
+        std::process::id()
+    ))
+}
+
+#[test]
+fn print_json_schema_matches_published_file() {
+    let output = run(&["--print-json-schema"]);
+    let printed: Value = serde_json::from_slice(&output.stdout).expect("schema should be json");
+    assert_eq!(printed, schema());
+}
+
+#[test]
+fn json_output_validates_against_schema() {
+    let output = run(&["--json"]);
+    let compact: Value = serde_json::from_slice(&output.stdout).expect("stdout should be json");
+    assert_valid(&compact);
+    assert_eq!(compact["neonfetch_version"], env!("CARGO_PKG_VERSION"));
+    assert!(!compact["probes"].as_array().expect("probes").is_empty());
+
+    let output = run(&["--json-pretty", "--show", "os,memory,swap,disk,packages"]);
+    let text = String::from_utf8_lossy(&output.stdout);
+    assert!(text.starts_with("{\n  \""), "{text}");
+    let pretty: Value = serde_json::from_str(&text).expect("stdout should be json");
+    assert_valid(&pretty);
+    let memory = &pretty["fields"][1];
+    assert_eq!(memory["key"], "memory");
+    assert!(memory["values"]["used_bytes"].is_u64());
+}
+
+#[test]
+fn snapshots_round_trip_through_the_versioned_document() {
+    let path = temp_path("roundtrip");
+    let output = run(&["--json", "--show", "header,os,memory,uptime"]);
+    fs::write(&path, &output.stdout).expect("failed to write snapshot");
+    let original: Value = serde_json::from_slice(&output.stdout).expect("stdout should be json");
+
+    let output = run(&[
+        "--from-json",
+        path.to_str().expect("utf-8 path"),
+        "--json",
+        "--hide",
+        "uptime",
+    ]);
+    let replayed: Value = serde_json::from_slice(&output.stdout).expect("stdout should be json");
+    assert_valid(&replayed);
+    assert_eq!(replayed["hostname"], original["hostname"]);
+    assert_eq!(replayed["probes"], json!([]));
+    assert_eq!(replayed["fields"].as_array().expect("fields").len(), 3);
+    assert_eq!(replayed["fields"][2], original["fields"][2]);
+
+    // Typed readings survive, so metrics can be produced from a snapshot.
+    let output = run(&[
+        "--from-json",
+        path.to_str().expect("utf-8 path"),
+        "--format",
+        "prometheus",
+    ]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("\nneonfetch_memory_used_bytes "));
+
+    let _ = fs::remove_file(path);
+}
+
+#[test]
+fn validator_rejects_documents_that_break_the_schema() {
+    let valid = json!({
+        "schema_version": 1,
+        "generated_at": "2026-01-02T03:04:05Z",
+        "neonfetch_version": "0.1.0",
+        "hostname": null,
+        "distro_id": "arch",
+        "fields": [{"key": "os", "value": "Linux", "text": "OS: Linux", "values": {}}],
+        "probes": [{"name": "host", "duration_ms": 1.5, "status": "ok", "error": null}]
+    });
+    assert_valid(&valid);
+
+    let schema = schema();
+    for (pointer, bad) in [
+        ("/schema_version", json!(2)),
+        ("/generated_at", json!("2026-01-02 03:04")),
+        ("/fields/0/values", json!({"ok": [1]})),
+        ("/probes/0/duration_ms", json!(-1)),
+        ("/probes/0/status", json!("skipped")),
+        ("/probes/0/extra", json!(true)),
+    ] {
+        let mut document = valid.clone();
+        let (parent, name) = pointer.rsplit_once('/').expect("pointer");
+        document
+            .pointer_mut(parent)
+            .and_then(Value::as_object_mut)
+            .expect("parent object")
+            .insert(name.to_string(), bad);
+        let mut errors = Vec::new();
+        validate(&schema, &schema, &document, "", &mut errors);
+        assert!(!errors.is_empty(), "{pointer} should be rejected");
+    }
+
+    let mut document = valid.clone();
+    document["probes"][0]
+        .as_object_mut()
+        .expect("probe object")
+        .remove("status");
+    let mut errors = Vec::new();
+    validate(&schema, &schema, &document, "", &mut errors);
+    assert!(
+        !errors.is_empty(),
+        "a probe without status should be rejected"
+    );
+}
+
+fn validate(root: &Value, schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        let target = reference
+            .strip_prefix('#')
+            .and_then(|pointer| root.pointer(pointer))
+            .unwrap_or_else(|| panic!("unresolved $ref {reference}"));
+        return validate(root, target, value, path, errors);
+    }
+    if let Some(expected) = schema.get("const")
+        && value != expected
+    {
+        errors.push(format!("{path}: expected {expected}, got {value}"));
+    }
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array)
+        && !allowed.contains(value)
+    {
+        errors.push(format!("{path}: expected one of {allowed:?}, got {value}"));
+    }
+    if let Some(types) = schema.get("type") {
+        let allowed: Vec<&str> = match types {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.iter().any(|name| has_type(value, name)) {
+            errors.push(format!("{path}: expected {allowed:?}, got {value}"));
+            return;
+        }
+    }
+    if let Some(text) = value.as_str() {
+        let min_length = schema.get("minLength").and_then(Value::as_u64).unwrap_or(0);
+        if (text.chars().count() as u64) < min_length {
+            errors.push(format!("{path}: shorter than {min_length}"));
+        }
+        if let Some(pattern) = schema.get("pattern").and_then(Value::as_str)
+            && !matches_pattern(pattern, text)
+        {
+            errors.push(format!("{path}: {text:?} does not match {pattern}"));
+        }
+    }
+    if let (Some(number), Some(minimum)) = (
+        value.as_f64(),
+        schema.get("minimum").and_then(Value::as_f64),
+    ) && number < minimum
+    {
+        errors.push(format!("{path}: {number} is below {minimum}"));
+    }
+    if let (Some(items), Some(schema)) = (value.as_array(), schema.get("items")) {
+        for (index, item) in items.iter().enumerate() {
+            validate(root, schema, item, &format!("{path}/{index}"), errors);
+        }
+    }
+    if let Some(object) = value.as_object() {
+        for name in schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+        {
+            if !object.contains_key(name) {
+                errors.push(format!("{path}: missing `{name}`"));
+            }
+        }
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for (name, item) in object {
+            let item_path = format!("{path}/{name}");
+            match (
+                properties.and_then(|properties| properties.get(name)),
+                schema.get("additionalProperties"),
+            ) {
+                (Some(property), _) => validate(root, property, item, &item_path, errors),
+                (None, Some(Value::Bool(false))) => {
+                    errors.push(format!("{item_path}: not allowed"))
+                }
+                (None, Some(extra @ Value::Object(_))) => {
+                    validate(root, extra, item, &item_path, errors)
+                }
+                (None, _) => {}
+            }
+        }
+    }
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        other => panic!("unsupported type {other}"),
+    }
+}
+
+/// Anchored patterns built from literals and `[0-9]{n}` runs, which is all
+/// the schema uses.
+fn matches_pattern(pattern: &str, text: &str) -> bool {
+    let pattern = pattern
+        .strip_prefix('^')
+        .and_then(|pattern| pattern.strip_suffix('$'))
+        .unwrap_or_else(|| panic!("unsupported pattern {pattern}"));
+    let mut text = text.chars();
+    let mut rest = pattern;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("[0-9]") {
+            let (count, after) = match after.strip_prefix('{') {
+                Some(quantified) => {
+                    let (count, after) = quantified.split_once('}').expect("closed quantifier");
+                    (count.parse().expect("numeric quantifier"), after)
+                }
+                None => (1, after),
+            };
+            for _ in 0..count {
+                if !text.next().is_some_and(|ch| ch.is_ascii_digit()) {
+                    return false;
+                }
+            }
+            rest = after;
+        } else {
+            let literal = rest.chars().next().expect("non-empty pattern");
+            if text.next() != Some(literal) {
+                return false;
+            }
+            rest = &rest[literal.len_utf8()..];
+        }
+    }
+    text.next().is_none()
+}
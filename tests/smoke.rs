@@ -35,6 +35,115 @@ fn logo_file_output_contains_custom_art() {
     let _ = fs::remove_file(path);
 }
 
+#[test]
+fn wide_logo_characters_align_info_by_display_width() {
+    let path = temp_logo_path("wide");
+    fs::write(&path, "霓虹\nAB\n").expect("failed to write logo file");
+
+    let output = neonfetch_command()
+        .arg(format!("--logo-file={}", path.display()))
+        .args(["--fetch", "--show", "shell,terminal,locale"])
+        .output()
+        .expect("failed to run neonfetch binary");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success());
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(lines[0].starts_with("霓虹  "), "{stdout}");
+    assert!(lines[1].starts_with("AB    "), "{stdout}");
+    // Rows past the logo are padded to the same four columns plus the gap.
+    assert!(lines[2].starts_with("      ") && !lines[2].starts_with("       "));
+
+    let _ = fs::remove_file(path);
+}
+
+#[test]
+fn logo_colors_keep_ansi_art_and_still_align_info() {
+    let path = temp_logo_path("ansi");
+    fs::write(&path, "\x1b[31mRED\x1b[2J\nRR\x1b[0m\n").expect("failed to write logo file");
+    let fetch = |extra: &[&str]| {
+        let output = neonfetch_command()
+            .arg(format!("--logo-file={}", path.display()))
+            .args(["--fetch", "--show", "shell,terminal,locale"])
+            .args(extra)
+            .output()
+            .expect("failed to run neonfetch binary");
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+
+    let plain = fetch(&[]);
+    assert!(!plain.contains('\x1b'), "{plain:?}");
+    assert!(plain.lines().next().unwrap_or("").starts_with("RED  "));
+
+    // Colors carry across lines, each line ends in a reset, and other
+    // escapes are still dropped.
+    let colored = fetch(&["--logo-colors", "keep"]);
+    let lines: Vec<&str> = colored.lines().collect();
+    assert!(lines[0].starts_with("\x1b[31mRED\x1b[0m  "), "{colored:?}");
+    assert!(lines[1].starts_with("\x1b[31mRR\x1b[0m   "), "{colored:?}");
+    assert!(lines[2].starts_with("     ") && !lines[2].starts_with("      "));
+
+    let _ = fs::remove_file(path);
+}
+
+#[test]
+fn logo_images_render_as_half_blocks_or_protocol_overlays() {
+    // 2x2 RGBA: red, blue / green, transparent.
+    const PNG: [u8; 76] = [
+        137, 80, 78, 71, 13, 10, 26, 10, 0, 0, 0, 13, 73, 72, 68, 82, 0, 0, 0, 2, 0, 0, 0, 2, 8, 6,
+        0, 0, 0, 114, 182, 13, 36, 0, 0, 0, 19, 73, 68, 65, 84, 120, 218, 99, 248, 207, 192, 0, 66,
+        96, 10, 72, 48, 48, 0, 0, 63, 210, 5, 251, 179, 214, 138, 55, 0, 0, 0, 0, 73, 69, 78, 68,
+        174, 66, 96, 130,
+    ];
+    let path = temp_logo_path("image");
+    fs::write(&path, PNG).expect("failed to write logo image");
+    let fetch = |extra: &[&str]| {
+        let output = neonfetch_command()
+            .arg(format!("--logo-image={}", path.display()))
+            .args(["--fetch", "--show", "shell"])
+            .args(extra)
+            .output()
+            .expect("failed to run neonfetch binary");
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+
+    // Not a terminal, so auto falls back to half-blocks in the image's colors.
+    let blocks = fetch(&[]);
+    let lines: Vec<&str> = blocks.lines().collect();
+    assert_eq!(lines.len(), 16, "{blocks:?}");
+    assert!(lines[0].starts_with("\x1b[38;2;255;0;0m\x1b[48;2;255;0;0m▀"));
+    assert!(
+        lines[15].contains("\x1b[38;2;0;255;0m\x1b[48;2;0;255;0m▀")
+            && lines[15].contains("\x1b[0m")
+    );
+
+    // Replace mode still hands the animation colored half-blocks; only
+    // `--no-color` reduces the image to its shape.
+    let replace = fetch(&["--logo-colors", "replace"]);
+    assert!(replace.starts_with("\x1b[38;2;255;0;0m"), "{replace:?}");
+    let shape = fetch(&["--no-color"]);
+    let lines: Vec<&str> = shape.lines().collect();
+    assert!(lines[0].starts_with(&"█".repeat(32)), "{shape:?}");
+    let half = format!("{}{}", "█".repeat(16), " ".repeat(16));
+    assert!(lines[15].starts_with(&half), "{shape:?}");
+
+    // Protocol output keeps the logo column blank and draws over it.
+    let kitty = fetch(&["--image-protocol", "kitty"]);
+    assert!(kitty.starts_with(&" ".repeat(34)), "{kitty:?}");
+    assert!(kitty.contains("\x1b7\x1b[16A\r\x1b_Ga=T,f=100,c=32,r=16,C=1,q=2,m=0;iVBORw0KGgo"));
+    assert!(kitty.ends_with("\x1b\\\x1b8"));
+
+    let sixel = fetch(&["--image-protocol", "sixel"]);
+    assert!(
+        sixel.contains("\x1b7\x1b[16A\r\x1bP0;1;0q\"1;1;320;320"),
+        "{sixel:?}"
+    );
+
+    let _ = fs::remove_file(path);
+}
+
 #[test]
 fn missing_logo_file_warns_and_uses_builtin_logo() {
     let path = temp_logo_path("missing");
@@ -157,6 +266,51 @@ fn logo_output_suppresses_header_divider_line() {
     assert_eq!(os_index, header_index + 1);
 }
 
+#[test]
+fn layout_flags_move_the_logo_around_the_info() {
+    let path = temp_logo_path("layout");
+    fs::write(&path, "[]\n[]\n").expect("failed to write logo file");
+    let fetch = |extra: &[&str]| {
+        let output = neonfetch_command()
+            .arg(format!("--logo-file={}", path.display()))
+            .args(["--fetch", "--show", "shell,locale,terminal,kernel"])
+            .args(extra)
+            .output()
+            .expect("failed to run neonfetch binary");
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+
+    let right = fetch(&["--logo-position", "right", "--layout-gap", "1"]);
+    let lines: Vec<&str> = right.lines().collect();
+    let width = lines.iter().map(|line| line.find("[]").unwrap_or(0)).max();
+    assert!(
+        lines[0].starts_with("Shell: ") && lines[0].ends_with(" []  "),
+        "{right:?}"
+    );
+    assert_eq!(lines[1].find("[]"), width, "{right:?}");
+    assert!(lines[2].starts_with("Terminal: ") && !lines[2].contains("[]"));
+
+    let top = fetch(&[
+        "--logo-position=top",
+        "--layout-gap=1",
+        "--layout-padding=2",
+    ]);
+    let lines: Vec<&str> = top.lines().collect();
+    assert_eq!(&lines[..5], ["", "", "  []  ", "  []  ", ""], "{top:?}");
+    assert!(lines[5].starts_with("  Shell: "), "{top:?}");
+
+    let centered = fetch(&["--layout-center"]);
+    let lines: Vec<&str> = centered.lines().collect();
+    assert_eq!(lines.len(), 4, "{centered:?}");
+    assert!(lines[0].starts_with("    Shell: "), "{centered:?}");
+    assert!(lines[1].starts_with("[]  Locale: "), "{centered:?}");
+    assert!(lines[2].starts_with("[]  Terminal: "), "{centered:?}");
+    assert!(lines[3].starts_with("    Kernel: "), "{centered:?}");
+
+    let _ = fs::remove_file(path);
+}
+
 #[test]
 fn no_logo_output_keeps_header_divider_line() {
     let output = neonfetch_command()
@@ -293,8 +447,232 @@ fn json_hide_removes_key() {
     assert!(output.status.success());
     let value: serde_json::Value =
         serde_json::from_slice(&output.stdout).expect("stdout should be json");
-    assert!(value.get("network").is_none());
-    assert!(value.get("os").is_some());
+    assert_eq!(value["schema_version"], 1);
+    let keys = json_field_keys(&value);
+    assert!(!keys.contains(&"network".to_string()));
+    assert!(keys.contains(&"os".to_string()));
+}
+
+#[test]
+fn from_json_renders_snapshot_fields_and_logo() {
+    let path = temp_logo_path("snapshot");
+    fs::write(
+        &path,
+        r#"{"header":"ops@far-away\n-------","memory":"Memory: 1.00 GiB / 4.00 GiB (25%)","git":"Git: infra (main)","weather":"Weather: sunny","distro_id":"arch"}"#,
+    )
+    .expect("failed to write snapshot");
+
+    let output = neonfetch_command()
+        .arg(format!("--from-json={}", path.display()))
+        .args(["--fetch", "--hide", "weather"])
+        .output()
+        .expect("failed to run neonfetch binary");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("      /_-''    ''-_\\      "), "{stdout}");
+    assert!(stdout.contains("ops@far-away"));
+    assert!(stdout.contains("Memory: 1.00 GiB / 4.00 GiB (25%)"));
+    assert!(stdout.contains("Git: infra (main)"));
+    assert!(!stdout.contains("Weather"));
+
+    let output = neonfetch_command()
+        .args(["--from-json", "-", "--json", "--show", "weather,memory"])
+        .stdin(fs::File::open(&path).expect("snapshot exists"))
+        .output()
+        .expect("failed to run neonfetch binary");
+    assert!(output.status.success());
+    let value: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("stdout should be json");
+    assert_eq!(json_field_keys(&value), ["weather", "memory"]);
+    assert_eq!(
+        value["fields"][1]["text"],
+        "Memory: 1.00 GiB / 4.00 GiB (25%)"
+    );
+    assert_eq!(value["distro_id"], "arch");
+    assert_eq!(value["probes"], serde_json::json!([]));
+
+    fs::write(&path, "{\"os\": 7}").expect("failed to write snapshot");
+    let output = neonfetch_command()
+        .args(["--from-json", path.to_str().expect("utf-8 path"), "--fetch"])
+        .output()
+        .expect("failed to run neonfetch binary");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("field `os` must be a string"));
+
+    let _ = fs::remove_file(path);
+}
+
+#[test]
+fn format_prometheus_writes_textfile_metrics() {
+    let output = neonfetch_command()
+        .args(["--format", "prometheus", "--show", "os,uptime,memory"])
+        .output()
+        .expect("failed to run neonfetch binary");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("# TYPE neonfetch_info gauge"), "{stdout}");
+    assert!(stdout.contains("neonfetch_info{hostname="), "{stdout}");
+    assert!(stdout.contains("# TYPE neonfetch_memory_used_bytes gauge"));
+    assert!(stdout.contains("\nneonfetch_uptime_seconds "));
+    assert!(!stdout.contains("neonfetch_disk_used_bytes"));
+    assert!(!stdout.contains('\x1b'));
+
+    let output = neonfetch_command()
+        .args(["--format=xml", "--fetch"])
+        .output()
+        .expect("failed to run neonfetch binary");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown output format 'xml'"));
+}
+
+#[test]
+fn format_flag_serializes_snapshot_fields() {
+    let path = temp_logo_path("format-snapshot");
+    fs::write(
+        &path,
+        r#"{"header":"ops@far-away\n------","memory":"Memory: 1.00 GiB / 4.00 GiB (25%)","distro_id":"arch"}"#,
+    )
+    .expect("failed to write snapshot");
+    let run = |format: &str| {
+        let output = neonfetch_command()
+            .args(["--from-json", path.to_str().expect("utf-8 path")])
+            .args(["--format", format])
+            .output()
+            .expect("failed to run neonfetch binary");
+        assert!(output.status.success(), "{format}");
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+
+    let env = run("env");
+    assert!(env.contains("NEONFETCH_DISTRO_ID=arch\n"), "{env}");
+    assert!(env.contains("NEONFETCH_MEMORY_VALUE='1.00 GiB / 4.00 GiB (25%)'\n"));
+    assert_eq!(
+        run("markdown"),
+        "| Field | Value |\n| --- | --- |\n| header | ops@far-away |\n| Memory | 1.00 GiB / 4.00 GiB (25%) |\n"
+    );
+    assert!(run("toml").contains("[memory]\nlabel = \"Memory\"\n"));
+    assert!(run("yaml").contains("memory:\n  label: \"Memory\"\n"));
+    assert!(
+        run("csv").starts_with("field,name,value\n,distro_id,arch\nheader,value,ops@far-away\n")
+    );
+    let json = run("json");
+    assert!(
+        json.starts_with(r#"{"schema_version":1,"generated_at":"#),
+        "{json}"
+    );
+    assert!(json.contains(
+        r#"{"key":"memory","label":"Memory","value":"1.00 GiB / 4.00 GiB (25%)","text":"#
+    ));
+
+    let _ = fs::remove_file(path);
+}
+
+#[test]
+fn opt_in_fields_require_show() {
+    let output = neonfetch_command()
+        .args(["--json", "--hide", "network"])
+        .env("DOCKER_HOST", "unix:///nonexistent/neonfetch.sock")
+        .output()
+        .expect("failed to run neonfetch binary");
+    assert!(output.status.success());
+    let value: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("stdout should be json");
+    let keys = json_field_keys(&value);
+    assert!(!keys.contains(&"containers".to_string()));
+    assert!(!keys.contains(&"git".to_string()));
+}
+
+#[test]
+fn uptime_and_units_flags_change_formatting() {
+    let output = neonfetch_command()
+        .args([
+            "--fetch",
+            "--no-logo",
+            "--show",
+            "uptime,memory",
+            "--uptime-format",
+            "short",
+            "--units=decimal",
+            "--unit-precision",
+            "0",
+        ])
+        .output()
+        .expect("failed to run neonfetch binary");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).expect("stdout should be utf-8");
+    let uptime = stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("Uptime: "))
+        .expect("uptime line");
+    assert!(
+        uptime.ends_with('m') && !uptime.contains("hours"),
+        "{uptime}"
+    );
+    if let Some(memory) = stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("Memory: "))
+    {
+        assert!(!memory.contains("GiB") && !memory.contains('.'), "{memory}");
+    }
+}
+
+#[test]
+fn lang_flag_localizes_labels_over_environment() {
+    let output = neonfetch_command()
+        .args([
+            "--fetch",
+            "--no-logo",
+            "--show",
+            "uptime,locale",
+            "--lang",
+            "zh-CN",
+        ])
+        .env("LANG", "en_US.UTF-8")
+        .env_remove("LC_ALL")
+        .env_remove("LC_MESSAGES")
+        .output()
+        .expect("failed to run neonfetch binary");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).expect("stdout should be utf-8");
+    assert!(stdout.contains("运行时间: "), "{stdout}");
+    assert!(stdout.contains("语言环境: en_US.UTF-8"), "{stdout}");
+
+    let output = neonfetch_command()
+        .args(["--fetch", "--no-logo", "--show", "uptime"])
+        .env("LANG", "C")
+        .env("LC_ALL", "zh_CN.UTF-8")
+        .output()
+        .expect("failed to run neonfetch binary");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).expect("stdout should be utf-8");
+    assert!(stdout.starts_with("运行时间: "), "{stdout}");
+}
+
+#[test]
+fn machine_output_ignores_display_locale() {
+    let output = neonfetch_command()
+        .args(["--json", "--show", "uptime,memory", "--lang", "zh-CN"])
+        .env("LC_ALL", "de_DE.UTF-8")
+        .output()
+        .expect("failed to run neonfetch binary");
+    assert!(output.status.success());
+    let value: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("stdout should be json");
+    for field in value["fields"].as_array().expect("fields array") {
+        let text = field["text"].as_str().expect("field text");
+        assert!(text.is_ascii(), "{text}");
+        if field["key"] == "memory" {
+            assert!(!text.contains(','), "{text}");
+        }
+    }
+
+    let output = neonfetch_command()
+        .args(["--format", "env", "--show", "uptime", "--lang", "zh-CN"])
+        .output()
+        .expect("failed to run neonfetch binary");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).expect("stdout should be utf-8");
+    assert!(stdout.contains("Uptime"), "{stdout}");
 }
 
 #[test]
@@ -322,6 +700,7 @@ fn list_fields_prints_available_keys() {
         "host",
         "kernel",
         "uptime",
+        "boot",
         "shell",
         "terminal",
         "cpu",
@@ -334,17 +713,51 @@ fn list_fields_prints_available_keys() {
         "memory",
         "swap",
         "disk",
+        "storage",
         "network",
         "locale",
+        "users",
+        "last_login",
+        "containers",
+        "git",
     ] {
         assert!(stdout.lines().any(|line| line == key));
     }
 }
 
+#[cfg(target_os = "linux")]
+#[test]
+fn kernel_details_extends_kernel_field() {
+    let output = neonfetch_command()
+        .args([
+            "--fetch",
+            "--no-logo",
+            "--show",
+            "kernel",
+            "--kernel-details",
+        ])
+        .output()
+        .expect("failed to run neonfetch binary");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).expect("stdout should be utf-8");
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(lines[0].starts_with("Kernel:"));
+    assert!(lines.iter().any(|line| line.starts_with("Kernel Taint:")));
+}
+
 fn neonfetch_command() -> Command {
     Command::new(env!("CARGO_BIN_EXE_neonfetch"))
 }
 
+fn json_field_keys(value: &serde_json::Value) -> Vec<String> {
+    value["fields"]
+        .as_array()
+        .expect("fields should be an array")
+        .iter()
+        .map(|field| field["key"].as_str().expect("string key").to_string())
+        .collect()
+}
+
 fn temp_logo_path(name: &str) -> PathBuf {
     let nanos = SystemTime::now()
         .duration_since(UNIX_EPOCH)